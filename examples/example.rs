@@ -12,8 +12,8 @@ use bitcoind_request::{
         get_block_hash::GetBlockHashCommand,
         get_block_header::GetBlockHeaderCommand,
         get_block_stats::{
-            GetBlockStatsCommand, GetBlockStatsCommandResponse,
-            GetBlockStatsCommandWithAllStatsResponse, StatsArgumentChoices, TargetBlockArgument,
+            GetBlockStatsCommand, GetBlockStatsCommandWithAllStatsResponse, StatsArgumentChoices,
+            TargetBlockArgument,
         },
         get_blockchain_info::GetBlockchainInfoCommand,
         get_chain_tips::GetChainTipsCommand,
@@ -52,7 +52,7 @@ fn format_duration(seconds: i64) -> String {
 
 fn get_block_height(client: &Client) -> u64 {
     let block_count = GetBlockCountCommand::new().call(client);
-    return block_count.unwrap().0;
+    block_count.unwrap().0
 }
 
 fn get_time_since_last_block(client: &Client) -> Seconds {
@@ -60,14 +60,10 @@ fn get_time_since_last_block(client: &Client) -> Seconds {
     let arg = TargetBlockArgument::Height(block_count.unwrap().0);
     let maybe_block_stats_response = GetBlockStatsCommand::new(arg).call(client);
     let time_of_last_block = match maybe_block_stats_response {
-        Ok(block_stats_response) => match block_stats_response {
-            GetBlockStatsCommandResponse::AllStats(response) => response.time,
-            GetBlockStatsCommandResponse::SelectiveStats(response) => response.time.unwrap(),
-        },
+        Ok(block_stats_response) => block_stats_response.time,
         Err(_) => panic!("panic"),
     };
     let current_datetime = chrono::offset::Utc::now();
-    let current_timestamp = current_datetime.timestamp();
     let datetime_of_last_block = Utc.timestamp(time_of_last_block as i64, 0);
     let difference = current_datetime.signed_duration_since(datetime_of_last_block);
     Seconds(difference.num_seconds())
@@ -83,9 +79,7 @@ fn get_average_block_time(client: &Client) -> u64 {
     let maybe_chain_tx_stats = GetChainTxStatsCommand::new()
         .set_n_blocks(2016)
         .call(client);
-    let average_seconds_per_block =
-        maybe_chain_tx_stats.unwrap().window_interval / blocks_to_calculate;
-    average_seconds_per_block
+    maybe_chain_tx_stats.unwrap().window_interval / blocks_to_calculate
 }
 
 fn get_total_money_supply(client: &Client) -> f64 {
@@ -199,9 +193,9 @@ fn main() {
                 println!("size: {}", block.size);
                 println!("weight: {}", block.weight);
             }
-            GetBlockCommandResponse::BlockHash(hash) => panic!("not supported"),
+            GetBlockCommandResponse::BlockHash(_hash) => panic!("not supported"),
         },
-        Err(err) => {}
+        Err(_err) => {}
     }
 
     // let block = GetBlockCommand::new(Blockhash(
@@ -0,0 +1,48 @@
+// Prints the node's sync progress every 10 seconds until it's synced.
+use bitcoind_request::client::Client;
+use bitcoind_request::long_poll::LongPollCancel;
+use bitcoind_request::sync_progress::{SyncEstimate, SyncMonitor};
+
+use std::env;
+use std::time::Duration;
+
+fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => {
+            let seconds = duration.as_secs();
+            format!(
+                "{}h{:02}m{:02}s",
+                seconds / 3600,
+                (seconds / 60) % 60,
+                seconds % 60
+            )
+        }
+        None => "unknown".to_string(),
+    }
+}
+
+fn print_estimate(estimate: &SyncEstimate) {
+    println!(
+        "{}/{} blocks, {:.2}% verified, {:.1} blocks/s, done in {} (by blocks: {}), tip age {}",
+        estimate.sample.blocks,
+        estimate.sample.headers,
+        estimate.sample.verification_progress * 100.0,
+        estimate.blocks_per_second.unwrap_or_default(),
+        format_duration(estimate.eta),
+        format_duration(estimate.eta_by_blocks),
+        format_duration(estimate.tip_age()),
+    );
+}
+
+fn main() {
+    let password = env::var("BITCOIND_PASSWORD").expect("BITCOIND_PASSWORD env variable not set");
+    let username = env::var("BITCOIND_USERNAME").expect("BITCOIND_USERNAME env variable not set");
+    let url = env::var("BITCOIND_URL").expect("BITCOIND_URL env variable not set");
+    let client = Client::new(&url, &username, &password).expect("failed to create client");
+
+    let monitor = SyncMonitor::new(&client, LongPollCancel::new());
+    match monitor.run(print_estimate) {
+        Ok(_) => println!("synced"),
+        Err(error) => eprintln!("error: {}", error),
+    }
+}
@@ -0,0 +1,63 @@
+// bitcoind-exporter: serves a node's metrics on /metrics for Prometheus to scrape.
+//
+//     bitcoind-exporter --listen 127.0.0.1:9332 --collectors chain,mempool,peers
+use std::net::TcpListener;
+use std::process;
+
+use clap::Parser;
+
+use bitcoind_request::config::ConnectionArgs;
+use bitcoind_request::metrics::{Collector, Exporter, DEFAULT_NAMESPACE};
+
+#[derive(Parser)]
+#[command(
+    name = "bitcoind-exporter",
+    version,
+    about = "Serve a bitcoind node's metrics in the Prometheus text format"
+)]
+struct Cli {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+    /// The address to serve /metrics on
+    #[arg(long, default_value = "127.0.0.1:9332")]
+    listen: String,
+    /// The collectors to run, comma separated: chain, mempool, peers, network, mining, chaintips
+    #[arg(long, value_delimiter = ',', default_values_t = Collector::ALL)]
+    collectors: Vec<Collector>,
+    /// The prefix of the metric names
+    #[arg(long, default_value = DEFAULT_NAMESPACE)]
+    namespace: String,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let client = match cli
+        .connection
+        .resolve()
+        .and_then(|settings| settings.connect(cli.connection.timeout()))
+    {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    };
+    let listener = match TcpListener::bind(&cli.listen) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("error: couldn't listen on {}: {}", cli.listen, error);
+            process::exit(1);
+        }
+    };
+    let exporter = Exporter::new(&client)
+        .set_collectors(cli.collectors)
+        .set_namespace(cli.namespace);
+    eprintln!("serving metrics on http://{}/metrics", cli.listen);
+    let result = exporter.serve(listener, |collector, error| {
+        eprintln!("collector {} failed: {}", collector, error);
+    });
+    if let Err(error) = result {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
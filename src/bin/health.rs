@@ -0,0 +1,158 @@
+// bitcoind-health: checks a node's health once and prints the verdict, or serves it on /health
+// and /ready for load balancers and probes.
+//
+//     bitcoind-health --min-peers-degraded 8 && echo ok
+//     bitcoind-health --listen 0.0.0.0:9333
+use std::net::TcpListener;
+use std::process;
+use std::time::Duration;
+
+use clap::Parser;
+
+use bitcoind_request::config::ConnectionArgs;
+use bitcoind_request::health::{HealthChecker, HealthStatus, Threshold, Thresholds, Verdict};
+
+#[derive(Parser)]
+#[command(
+    name = "bitcoind-health",
+    version,
+    about = "Check a bitcoind node's health, exiting with 1 if it's unhealthy (2 with --ready unless it's healthy)"
+)]
+struct Cli {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+    /// Serve the verdict on /health and /ready at this address instead of checking once
+    #[arg(long)]
+    listen: Option<String>,
+    /// Fail unless the node is healthy, rather than only if it's unhealthy
+    #[arg(long, conflicts_with = "listen")]
+    ready: bool,
+    #[command(flatten)]
+    thresholds: ThresholdArgs,
+}
+
+// Each one overrides the default of Thresholds::new.
+#[derive(clap::Args)]
+struct ThresholdArgs {
+    /// Degraded if the tip is older than this many seconds
+    #[arg(long, value_name = "SECONDS")]
+    tip_age_degraded: Option<u64>,
+    /// Unhealthy if the tip is older than this many seconds
+    #[arg(long, value_name = "SECONDS")]
+    tip_age_unhealthy: Option<u64>,
+    /// Degraded if the headers are more than this many blocks ahead of the validated blocks
+    #[arg(long, value_name = "BLOCKS")]
+    header_lag_degraded: Option<u64>,
+    /// Unhealthy if the headers are more than this many blocks ahead of the validated blocks
+    #[arg(long, value_name = "BLOCKS")]
+    header_lag_unhealthy: Option<u64>,
+    /// Degraded with fewer connected peers
+    #[arg(long, value_name = "PEERS")]
+    min_peers_degraded: Option<u64>,
+    /// Unhealthy with fewer connected peers
+    #[arg(long, value_name = "PEERS")]
+    min_peers_unhealthy: Option<u64>,
+    /// Degraded with outbound peers in fewer network groups
+    #[arg(long, value_name = "GROUPS")]
+    min_outbound_netgroups_degraded: Option<u64>,
+    /// Unhealthy with outbound peers in fewer network groups
+    #[arg(long, value_name = "GROUPS")]
+    min_outbound_netgroups_unhealthy: Option<u64>,
+    /// Degraded below this verification progress, from 0 to 1
+    #[arg(long, value_name = "PROGRESS")]
+    min_verification_progress: Option<f64>,
+    /// Don't count initial block download as unhealthy
+    #[arg(long)]
+    allow_initial_block_download: bool,
+    /// Don't count the node's warnings as degrading it
+    #[arg(long)]
+    allow_warnings: bool,
+}
+
+impl ThresholdArgs {
+    fn thresholds(&self) -> Thresholds {
+        let defaults = Thresholds::new();
+        let seconds = |seconds: Option<u64>, default: Duration| {
+            seconds.map(Duration::from_secs).unwrap_or(default)
+        };
+        Thresholds {
+            tip_age: Threshold {
+                degraded: seconds(self.tip_age_degraded, defaults.tip_age.degraded),
+                unhealthy: seconds(self.tip_age_unhealthy, defaults.tip_age.unhealthy),
+            },
+            header_lag: Threshold {
+                degraded: self
+                    .header_lag_degraded
+                    .unwrap_or(defaults.header_lag.degraded),
+                unhealthy: self
+                    .header_lag_unhealthy
+                    .unwrap_or(defaults.header_lag.unhealthy),
+            },
+            peers: Threshold {
+                degraded: self.min_peers_degraded.unwrap_or(defaults.peers.degraded),
+                unhealthy: self.min_peers_unhealthy.unwrap_or(defaults.peers.unhealthy),
+            },
+            outbound_netgroups: Threshold {
+                degraded: self
+                    .min_outbound_netgroups_degraded
+                    .unwrap_or(defaults.outbound_netgroups.degraded),
+                unhealthy: self
+                    .min_outbound_netgroups_unhealthy
+                    .unwrap_or(defaults.outbound_netgroups.unhealthy),
+            },
+            min_verification_progress: self
+                .min_verification_progress
+                .unwrap_or(defaults.min_verification_progress),
+            allow_initial_block_download: self.allow_initial_block_download,
+            allow_warnings: self.allow_warnings,
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let client = match cli
+        .connection
+        .resolve()
+        .and_then(|settings| settings.connect(cli.connection.timeout()))
+    {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    };
+    let checker = HealthChecker::new(&client).set_thresholds(cli.thresholds.thresholds());
+
+    match &cli.listen {
+        Some(address) => {
+            let listener = match TcpListener::bind(address) {
+                Ok(listener) => listener,
+                Err(error) => {
+                    eprintln!("error: couldn't listen on {}: {}", address, error);
+                    process::exit(1);
+                }
+            };
+            eprintln!("serving health on http://{}/health", address);
+            let result = checker.serve(listener, |error| {
+                eprintln!("health check failed: {}", error);
+            });
+            if let Err(error) = result {
+                eprintln!("error: {}", error);
+                process::exit(1);
+            }
+        }
+        None => {
+            let verdict = checker
+                .check()
+                .unwrap_or_else(|error| Verdict::unreachable(&error));
+            println!("{}", serde_json::to_string_pretty(&verdict).unwrap());
+            if verdict.status == HealthStatus::Unhealthy {
+                process::exit(1);
+            }
+            if cli.ready && verdict.status != HealthStatus::Healthy {
+                process::exit(2);
+            }
+        }
+    }
+}
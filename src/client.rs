@@ -1,3 +1,5 @@
+use std::io;
+use std::sync::OnceLock;
 use std::time::Duration;
 
 use jsonrpc::{
@@ -6,8 +8,16 @@ use jsonrpc::{
 };
 use serde_json::value::RawValue;
 
+use crate::version::{detect_node_version, NodeVersion};
+
+// The default in the library is 15 seconds, but we're setting to very high here to prevent error
+// during the call to gettxoutsetinfo.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
 pub struct Client {
     json_rpc_client: JsonRPCClient,
+    timeout: Duration,
+    node_version: OnceLock<NodeVersion>,
 }
 
 pub struct Request<'a>(JsonRPCRequest<'a>);
@@ -15,11 +25,18 @@ pub struct Request<'a>(JsonRPCRequest<'a>);
 impl<'a> Request<'a> {}
 
 impl Client {
-    // TODO: Add error handling if this fails
     pub fn new(url: &str, user: &str, pass: &str) -> Result<Self, simple_http::Error> {
-        // The default in the library is 15 seconds, but we're setting to very high here to prevent error
-        // during the call to gettxoutsetinfo.
-        let timeout = Duration::from_secs(300);
+        Client::with_timeout(url, user, pass, DEFAULT_TIMEOUT)
+    }
+    // How long a request may take before it fails with a transport error. Calls that block on the
+    // node, like the wait RPCs or getblocktemplate long polling, need a timeout above the time
+    // they wait for.
+    pub fn with_timeout(
+        url: &str,
+        user: &str,
+        pass: &str,
+        timeout: Duration,
+    ) -> Result<Self, simple_http::Error> {
         let simple_http_transport = SimpleHttpTransport::builder()
             .url(url)?
             .auth(user, Some(pass))
@@ -27,20 +44,53 @@ impl Client {
             .build();
         let client = Client {
             json_rpc_client: JsonRPCClient::with_transport(simple_http_transport),
+            timeout,
+            node_version: OnceLock::new(),
         };
         Ok(client)
     }
-    pub fn build_request<'a>(
-        &self,
-        command: &'a str,
-        params: &'a Vec<Box<RawValue>>,
-    ) -> Request<'a> {
-        let json_rpc_request = self.json_rpc_client.build_request(command, &params);
-        let request = Request(json_rpc_request);
-        request
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+    // The version of the node, detected with getnetworkinfo on first use. A failed detection
+    // isn't cached, so the next call tries again.
+    pub fn node_version(&self) -> Result<NodeVersion, jsonrpc::Error> {
+        if let Some(version) = self.node_version.get() {
+            return Ok(*version);
+        }
+        let version = detect_node_version(self)?;
+        Ok(*self.node_version.get_or_init(|| version))
+    }
+    pub fn build_request<'a>(&self, command: &'a str, params: &'a [Box<RawValue>]) -> Request<'a> {
+        let json_rpc_request = self.json_rpc_client.build_request(command, params);
+        Request(json_rpc_request)
     }
     pub fn send_request(&self, request: Request) -> Result<JsonRPCResponse, jsonrpc::Error> {
-        let response = self.json_rpc_client.send_request(request.0);
-        response
+        self.json_rpc_client.send_request(request.0)
+    }
+    // Sends the requests in one round trip. Responses are in the order of the requests, None for
+    // a request the node didn't answer.
+    pub fn send_batch(
+        &self,
+        requests: Vec<Request>,
+    ) -> Result<Vec<Option<JsonRPCResponse>>, jsonrpc::Error> {
+        let requests: Vec<JsonRPCRequest> = requests.into_iter().map(|request| request.0).collect();
+        self.json_rpc_client.send_batch(&requests)
+    }
+}
+
+// Whether a call failed because the client timed out waiting for the response, rather than
+// because the node returned an error.
+pub fn is_timeout(error: &jsonrpc::Error) -> bool {
+    match error {
+        jsonrpc::Error::Transport(error) => match error.downcast_ref::<simple_http::Error>() {
+            Some(simple_http::Error::Timeout) => true,
+            Some(simple_http::Error::SocketError(error)) => matches!(
+                error.kind(),
+                io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+            ),
+            _ => false,
+        },
+        _ => false,
     }
 }
@@ -0,0 +1,81 @@
+/*
+addnode "node" "command" ( v2transport )
+
+Attempts to add or remove a node from the addnode list.
+Or try a connection to a node once.
+Nodes added using addnode (or -connect) are protected from DoS disconnection and are not required to be
+full nodes/support SegWit as other outbound peers are (though such peers will not be synced from).
+Addnode connections are limited to 8 at a time and are counted separately from the -maxconnections limit.
+
+Arguments:
+1. node           (string, required) The address of the peer to connect to
+2. command        (string, required) 'add' to add a node to the list, 'remove' to remove a node from the list, 'onetry' to try a connection to the node once
+3. v2transport    (boolean, optional, default=set by -v2transport) Attempt to connect using BIP324 v2 transport protocol (ignored for 'remove' command)
+
+Result:
+null    (json null)
+
+Examples:
+> bitcoin-cli addnode "192.168.0.6:8333" "onetry" true
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "addnode", "params": ["192.168.0.6:8333", "onetry", true]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const ADD_NODE_COMMAND: &str = "addnode";
+
+pub enum AddNodeCommandArg {
+    Add,    // add a node to the addnode list
+    Remove, // remove a node from the addnode list
+    OneTry, // try a connection to the node once
+}
+
+pub struct AddNodeCommand {
+    node: String,               // (string, required) The address of the peer to connect to
+    command: AddNodeCommandArg, // (string, required) What to do with the node
+    v2transport: Option<bool>, // (boolean, optional, default=set by -v2transport) Attempt to connect using BIP324 v2 transport protocol
+}
+impl AddNodeCommand {
+    pub fn new(node: String, command: AddNodeCommandArg) -> Self {
+        AddNodeCommand {
+            node,
+            command,
+            v2transport: None,
+        }
+    }
+    pub fn set_v2transport(mut self, v2transport: bool) -> Self {
+        self.v2transport = Some(v2transport);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddNodeCommandResponse;
+
+impl CallableCommand for AddNodeCommand {
+    type Response = AddNodeCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let command_arg = match self.command {
+            AddNodeCommandArg::Add => "add",
+            AddNodeCommandArg::Remove => "remove",
+            AddNodeCommandArg::OneTry => "onetry",
+        };
+        let node_arg_raw_value = to_raw_value(&self.node).unwrap();
+        let command_arg_raw_value = to_raw_value(command_arg).unwrap();
+        let mut params = vec![node_arg_raw_value, command_arg_raw_value];
+        if let Some(v2transport) = &self.v2transport {
+            let v2transport_arg_raw_value = to_raw_value(&v2transport).unwrap();
+            params.push(v2transport_arg_raw_value)
+        }
+        let r = request(client, ADD_NODE_COMMAND, params)?;
+        let response: AddNodeCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
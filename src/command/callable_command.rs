@@ -3,4 +3,9 @@ use crate::client::Client;
 pub trait CallableCommand {
     type Response;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error>;
+    // Whether calling the command again has no further effect on the node, so a retry or caching
+    // layer may replay it or serve it from a cache. Commands that change node state return false.
+    fn is_idempotent(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,47 @@
+/*
+clearbanned
+
+Clear all banned IPs.
+
+Result:
+null    (json null)
+
+Examples:
+> bitcoin-cli clearbanned
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "clearbanned", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const CLEAR_BANNED_COMMAND: &str = "clearbanned";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ClearBannedCommandResponse;
+
+pub struct ClearBannedCommand {}
+impl ClearBannedCommand {
+    pub fn new() -> Self {
+        ClearBannedCommand {}
+    }
+}
+impl Default for ClearBannedCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for ClearBannedCommand {
+    type Response = ClearBannedCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let params = vec![];
+        let r = request(client, CLEAR_BANNED_COMMAND, params)?;
+        let response: ClearBannedCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
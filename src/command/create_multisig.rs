@@ -0,0 +1,92 @@
+/*
+createmultisig nrequired ["key",...] ( "address_type" )
+
+Creates a multi-signature address with n signature of m keys required.
+It returns a json object with the address and redeemScript.
+
+Arguments:
+1. nrequired       (numeric, required) The number of required signatures out of the n keys.
+2. keys            (json array, required) The hex-encoded public keys.
+     [
+       "key",      (string) The hex-encoded public key
+       ...
+     ]
+3. address_type    (string, optional, default="legacy") The address type to use. Options are "legacy", "p2sh-segwit", and "bech32".
+
+Result:
+{                            (json object)
+  "address" : "str",         (string) The value of the new multisig address.
+  "redeemScript" : "hex",    (string) The string value of the hex-encoded redemption script.
+  "descriptor" : "str",      (string) The descriptor for this multisig
+  "warnings" : [             (json array, optional) Any warnings resulting from the creation of this multisig
+    "str",                   (string)
+    ...
+  ]
+}
+
+Examples:
+Create a multisig address from 2 public keys
+> bitcoin-cli createmultisig 2 "[\"03789ed0bb717d88f7d321a368d905e7430207ebbd82bd342cf11ae157a7ace5fd\",\"03dbc6764b8884a92e871274b87583e6d5c2a58819473e17e107ef3f6aa5a61626\"]"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "createmultisig", "params": [2, ["03789ed0bb717d88f7d321a368d905e7430207ebbd82bd342cf11ae157a7ace5fd","03dbc6764b8884a92e871274b87583e6d5c2a58819473e17e107ef3f6aa5a61626"]]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::types::transaction::BitcoinAddress;
+
+const CREATE_MULTISIG_COMMAND: &str = "createmultisig";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MultisigAddressType {
+    Legacy,     // P2SH
+    P2shSegwit, // P2WSH nested in P2SH
+    Bech32,     // P2WSH
+}
+
+pub struct CreateMultisigCommand {
+    nrequired: u32, // (numeric, required) The number of required signatures out of the n keys.
+    keys: Vec<String>, // (json array, required) The hex-encoded public keys.
+    address_type: Option<MultisigAddressType>, // (string, optional, default="legacy") The address type to use.
+}
+impl CreateMultisigCommand {
+    pub fn new(nrequired: u32, keys: Vec<String>) -> Self {
+        CreateMultisigCommand {
+            nrequired,
+            keys,
+            address_type: None,
+        }
+    }
+    pub fn set_address_type(mut self, address_type: MultisigAddressType) -> Self {
+        self.address_type = Some(address_type);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateMultisigCommandResponse {
+    pub address: BitcoinAddress, // The value of the new multisig address.
+    #[serde(rename = "redeemScript")]
+    pub redeem_script: String, // The string value of the hex-encoded redemption script.
+    pub descriptor: String,      // The descriptor for this multisig
+    pub warnings: Option<Vec<String>>, // Any warnings resulting from the creation of this multisig
+}
+
+impl CallableCommand for CreateMultisigCommand {
+    type Response = CreateMultisigCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let nrequired_arg_raw_value = to_raw_value(&self.nrequired).unwrap();
+        let keys_arg_raw_value = to_raw_value(&self.keys).unwrap();
+        let mut params = vec![nrequired_arg_raw_value, keys_arg_raw_value];
+        if let Some(address_type) = &self.address_type {
+            let address_type_arg_raw_value = to_raw_value(address_type).unwrap();
+            params.push(address_type_arg_raw_value);
+        }
+        let r = request(client, CREATE_MULTISIG_COMMAND, params)?;
+        let response: CreateMultisigCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,108 @@
+/*
+decoderawtransaction "hexstring" ( iswitness )
+
+Return a JSON object representing the serialized, hex-encoded transaction.
+
+Arguments:
+1. hexstring    (string, required) The transaction hex string
+2. iswitness    (boolean, optional, default=depends on heuristic tests) Whether the transaction hex is a serialized witness transaction.
+                If iswitness is not present, heuristic tests will be used in decoding.
+                If true, only witness deserialization will be tried.
+                If false, only non-witness deserialization will be tried.
+                This boolean should reflect whether the transaction has inputs
+                (e.g. fully valid, or on-chain transactions), if known by the caller.
+
+Result:
+{                           (json object)
+  "txid" : "hex",           (string) The transaction id
+  "hash" : "hex",           (string) The transaction hash (differs from txid for witness transactions)
+  "size" : n,               (numeric) The transaction size
+  "vsize" : n,              (numeric) The virtual transaction size (differs from size for witness transactions)
+  "weight" : n,             (numeric) The transaction's weight (between vsize*4 - 3 and vsize*4)
+  "version" : n,            (numeric) The version
+  "locktime" : xxx,         (numeric) The lock time
+  "vin" : [                 (json array)
+    {                       (json object)
+      "txid" : "hex",       (string) The transaction id
+      "vout" : n,           (numeric) The output number
+      "scriptSig" : {       (json object) The script
+        "asm" : "str",      (string) asm
+        "hex" : "hex"       (string) hex
+      },
+      "txinwitness" : [     (json array)
+        "hex",              (string) hex-encoded witness data (if any)
+        ...
+      ],
+      "sequence" : n        (numeric) The script sequence number
+    },
+    ...
+  ],
+  "vout" : [                (json array)
+    {                       (json object)
+      "value" : n,          (numeric) The value in BTC
+      "n" : n,              (numeric) index
+      "scriptPubKey" : {    (json object)
+        "asm" : "str",      (string) the asm
+        "hex" : "hex",      (string) the hex
+        "type" : "str",     (string) The type, eg 'pubkeyhash'
+        "address" : "str"   (string, optional) The Bitcoin address (only if a well-defined address exists)
+      }
+    },
+    ...
+  ]
+}
+
+Examples:
+> bitcoin-cli decoderawtransaction "hexstring"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "decoderawtransaction", "params": ["hexstring"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::types::transaction::Transaction;
+
+const DECODE_RAW_TRANSACTION_COMMAND: &str = "decoderawtransaction";
+
+pub struct DecodeRawTransactionCommand {
+    hexstring: String,        // (string, required) The transaction hex string
+    is_witness: Option<bool>, // (boolean, optional, default=depends on heuristic tests) Whether the transaction hex is a serialized witness transaction.
+}
+impl DecodeRawTransactionCommand {
+    pub fn new(hexstring: String) -> Self {
+        DecodeRawTransactionCommand {
+            hexstring,
+            is_witness: None,
+        }
+    }
+    pub fn set_is_witness(mut self, is_witness: bool) -> Self {
+        self.is_witness = Some(is_witness);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DecodeRawTransactionCommandResponse(pub Transaction);
+
+impl From<DecodeRawTransactionCommandResponse> for Transaction {
+    fn from(response: DecodeRawTransactionCommandResponse) -> Self {
+        response.0
+    }
+}
+
+impl CallableCommand for DecodeRawTransactionCommand {
+    type Response = DecodeRawTransactionCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let hexstring_arg_raw_value = to_raw_value(&self.hexstring).unwrap();
+        let mut params = vec![hexstring_arg_raw_value];
+        if let Some(is_witness) = &self.is_witness {
+            let is_witness_arg_raw_value = to_raw_value(&is_witness).unwrap();
+            params.push(is_witness_arg_raw_value)
+        }
+        let r = request(client, DECODE_RAW_TRANSACTION_COMMAND, params)?;
+        let response: DecodeRawTransactionCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,133 @@
+/*
+deriveaddresses "descriptor" ( range )
+
+Derives one or more addresses corresponding to an output descriptor.
+Examples of output descriptors are:
+    pkh(<pubkey>)                                     P2PKH outputs for the given pubkey
+    wpkh(<pubkey>)                                    Native segwit P2PKH outputs for the given pubkey
+    sh(multi(<n>,<pubkey>,<pubkey>,...))              P2SH-multisig outputs for the given threshold and pubkeys
+    raw(<hex script>)                                 Outputs whose output script equals the specified hex-encoded bytes
+    tr(<pubkey>,multi_a(<n>,<pubkey>,<pubkey>,...))   P2TR-multisig outputs for the given threshold and pubkeys
+
+In the above, <pubkey> either refers to a fixed public key in hexadecimal notation, or to an xpub/xprv optionally followed by one
+or more path elements separated by "/", where "h" represents a hardened child key.
+For more information on output descriptors, see the documentation in the doc/descriptors.md file.
+
+Arguments:
+1. descriptor    (string, required) The descriptor.
+2. range         (numeric or array, optional) If a ranged descriptor is used, this specifies the end or the range (in [begin,end] notation) to derive.
+
+Result (for single derivation descriptors):
+[           (json array)
+  "str",    (string) the derived addresses
+  ...
+]
+
+Result (for multipath descriptors):
+[           (json array) The derived addresses for each of the multipath expansions of the descriptor, in multipath specifier order
+  [         (json array) The derived addresses for a multipath descriptor expansion
+    "str",  (string) the derived address
+    ...
+  ],
+  ...
+]
+
+Examples:
+First three native segwit receive addresses
+> bitcoin-cli deriveaddresses "wpkh([d34db33f/84h/0h/0h]xpub6DJ2dNUysrn5Vt36jH2KLBT2i1auw1tTSSomg8PhqNiUtx8QX2SvC9nrHu81fT41fvDUnhMjEzQgXnQjKEu3oaqMSzhSrHMxyyoEAmUHQbY/0/\*)#cjjspncu" "[0,2]"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "deriveaddresses", "params": ["wpkh([d34db33f/84h/0h/0h]xpub6DJ2dNUysrn5Vt36jH2KLBT2i1auw1tTSSomg8PhqNiUtx8QX2SvC9nrHu81fT41fvDUnhMjEzQgXnQjKEu3oaqMSzhSrHMxyyoEAmUHQbY/0/\*)#cjjspncu", "[0,2]"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::types::transaction::BitcoinAddress;
+use crate::version::{require_version, NodeVersion};
+
+const DERIVE_ADDRESSES_COMMAND: &str = "deriveaddresses";
+
+// The child indexes of a ranged descriptor to use. Both ends are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescriptorRange {
+    End(u64),        // From 0 to end, serialized as a number
+    Range(u64, u64), // From begin to end, serialized as [begin,end]
+}
+
+impl DescriptorRange {
+    pub fn begin(&self) -> u64 {
+        match self {
+            DescriptorRange::End(_) => 0,
+            DescriptorRange::Range(begin, _) => *begin,
+        }
+    }
+    pub fn end(&self) -> u64 {
+        match self {
+            DescriptorRange::End(end) => *end,
+            DescriptorRange::Range(_, end) => *end,
+        }
+    }
+}
+
+impl Serialize for DescriptorRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            DescriptorRange::End(end) => serializer.serialize_u64(*end),
+            DescriptorRange::Range(begin, end) => [begin, end].serialize(serializer),
+        }
+    }
+}
+
+pub struct DeriveAddressesCommand {
+    descriptor: String,             // (string, required) The descriptor.
+    range: Option<DescriptorRange>, // (numeric or array, optional) If a ranged descriptor is used, this specifies the end or the range (in [begin,end] notation) to derive.
+}
+impl DeriveAddressesCommand {
+    pub fn new(descriptor: String) -> Self {
+        DeriveAddressesCommand {
+            descriptor,
+            range: None,
+        }
+    }
+    // Required for ranged descriptors, and rejected for others.
+    pub fn set_range(mut self, range: DescriptorRange) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum DeriveAddressesCommandResponse {
+    Addresses(Vec<BitcoinAddress>),      // the derived addresses
+    Multipath(Vec<Vec<BitcoinAddress>>), // The derived addresses for each of the multipath expansions of the descriptor, in multipath specifier order
+}
+
+impl DeriveAddressesCommandResponse {
+    // All derived addresses, with those of multipath expansions concatenated in order.
+    pub fn addresses(&self) -> Vec<&BitcoinAddress> {
+        match self {
+            DeriveAddressesCommandResponse::Addresses(addresses) => addresses.iter().collect(),
+            DeriveAddressesCommandResponse::Multipath(expansions) => {
+                expansions.iter().flatten().collect()
+            }
+        }
+    }
+}
+
+impl CallableCommand for DeriveAddressesCommand {
+    type Response = DeriveAddressesCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        require_version(client, DERIVE_ADDRESSES_COMMAND, NodeVersion::V0_18)?;
+        let descriptor_arg_raw_value = to_raw_value(&self.descriptor).unwrap();
+        let mut params = vec![descriptor_arg_raw_value];
+        if let Some(range) = &self.range {
+            let range_arg_raw_value = to_raw_value(range).unwrap();
+            params.push(range_arg_raw_value);
+        }
+        let r = request(client, DERIVE_ADDRESSES_COMMAND, params)?;
+        let response: DeriveAddressesCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,66 @@
+/*
+disconnectnode ( "address" nodeid )
+
+Immediately disconnects from the specified peer node.
+
+Strictly one out of 'address' and 'nodeid' can be provided to identify the node.
+
+To disconnect by nodeid, either set 'address' to the empty string, or call using the named 'nodeid' argument only.
+
+Arguments:
+1. address    (string, optional, default=fallback to nodeid) The IP address/port of the node
+2. nodeid     (numeric, optional, default=fallback to address) The node ID (see getpeerinfo for node IDs)
+
+Result:
+null    (json null)
+
+Examples:
+> bitcoin-cli disconnectnode "192.168.0.6:8333"
+> bitcoin-cli disconnectnode "" 1
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "disconnectnode", "params": ["192.168.0.6:8333"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "disconnectnode", "params": ["", 1]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const DISCONNECT_NODE_COMMAND: &str = "disconnectnode";
+
+pub enum DisconnectNodeTarget {
+    Address(String), // The IP address/port of the node
+    NodeId(u64),     // The node ID (see getpeerinfo for node IDs)
+}
+
+pub struct DisconnectNodeCommand {
+    target: DisconnectNodeTarget,
+}
+impl DisconnectNodeCommand {
+    pub fn new(target: DisconnectNodeTarget) -> Self {
+        DisconnectNodeCommand { target }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DisconnectNodeCommandResponse;
+
+impl CallableCommand for DisconnectNodeCommand {
+    type Response = DisconnectNodeCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let params = match &self.target {
+            DisconnectNodeTarget::Address(address) => vec![to_raw_value(address).unwrap()],
+            // The address has to be empty to disconnect by node id.
+            DisconnectNodeTarget::NodeId(node_id) => {
+                vec![to_raw_value("").unwrap(), to_raw_value(node_id).unwrap()]
+            }
+        };
+        let r = request(client, DISCONNECT_NODE_COMMAND, params)?;
+        let response: DisconnectNodeCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,81 @@
+/*
+generateblock "output" ["rawtx/txid",...] ( submit )
+
+Mine a set of ordered transactions to a specified address or descriptor and return the block hash.
+
+Arguments:
+1. output               (string, required) The address or descriptor to send the newly generated bitcoin to.
+2. transactions         (json array, required) An array of hex strings which are either txids or raw transactions.
+                        Txids must reference transactions currently in the mempool.
+                        All transactions must be valid and in valid order, otherwise the block will be rejected.
+     [
+       "rawtx/txid",    (string)
+       ...
+     ]
+3. submit               (boolean, optional, default=true) Whether to submit the block before the RPC call returns or to return it as hex.
+
+Result:
+{                    (json object)
+  "hash" : "hex",    (string) hash of generated block
+  "hex" : "hex"      (string, optional) hex of generated block, only present when submit=false
+}
+
+Examples:
+Generate a block to myaddress, with txs rawtx and mempool_txid
+> bitcoin-cli generateblock "myaddress" '["rawtx", "mempool_txid"]'
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::version::{require_version, NodeVersion};
+use crate::Blockhash;
+
+const GENERATE_BLOCK_COMMAND: &str = "generateblock";
+
+pub struct GenerateBlockCommand {
+    output: String, // (string, required) The address or descriptor to send the newly generated bitcoin to.
+    transactions: Vec<String>, // (json array, required) Txids of mempool transactions or raw transactions, in block order.
+    submit: Option<bool>, // (boolean, optional, default=true) Whether to submit the block before the RPC call returns or to return it as hex.
+}
+impl GenerateBlockCommand {
+    pub fn new(output: String, transactions: Vec<String>) -> Self {
+        GenerateBlockCommand {
+            output,
+            transactions,
+            submit: None,
+        }
+    }
+    pub fn set_submit(mut self, submit: bool) -> Self {
+        self.submit = Some(submit);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GenerateBlockCommandResponse {
+    pub hash: Blockhash,     // hash of generated block
+    pub hex: Option<String>, // hex of generated block, only present when submit=false
+}
+
+impl CallableCommand for GenerateBlockCommand {
+    type Response = GenerateBlockCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        require_version(client, GENERATE_BLOCK_COMMAND, NodeVersion::V0_21)?;
+        let output_arg_raw_value = to_raw_value(&self.output).unwrap();
+        let transactions_arg_raw_value = to_raw_value(&self.transactions).unwrap();
+        let mut params = vec![output_arg_raw_value, transactions_arg_raw_value];
+        if let Some(submit) = &self.submit {
+            let submit_arg_raw_value = to_raw_value(&submit).unwrap();
+            params.push(submit_arg_raw_value)
+        }
+        let r = request(client, GENERATE_BLOCK_COMMAND, params)?;
+        let response: GenerateBlockCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,73 @@
+/*
+generatetoaddress nblocks "address" ( maxtries )
+
+Mine to a specified address and return the block hashes.
+
+Arguments:
+1. nblocks     (numeric, required) How many blocks are generated.
+2. address     (string, required) The address to send the newly generated bitcoin to.
+3. maxtries    (numeric, optional, default=1000000) How many iterations to try.
+
+Result:
+[           (json array) hashes of blocks generated
+  "hex",    (string) blockhash
+  ...
+]
+
+Examples:
+Generate 11 blocks to myaddress
+> bitcoin-cli generatetoaddress 11 "myaddress"
+If you are using the Bitcoin Core wallet, you can get a new address to send the newly generated bitcoin to with:
+> bitcoin-cli getnewaddress
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::Blockhash;
+
+const GENERATE_TO_ADDRESS_COMMAND: &str = "generatetoaddress";
+
+pub struct GenerateToAddressCommand {
+    nblocks: u64,          // (numeric, required) How many blocks are generated.
+    address: String,       // (string, required) The address to send the newly generated bitcoin to.
+    maxtries: Option<u64>, // (numeric, optional, default=1000000) How many iterations to try.
+}
+impl GenerateToAddressCommand {
+    pub fn new(nblocks: u64, address: String) -> Self {
+        GenerateToAddressCommand {
+            nblocks,
+            address,
+            maxtries: None,
+        }
+    }
+    pub fn set_maxtries(mut self, maxtries: u64) -> Self {
+        self.maxtries = Some(maxtries);
+        self
+    }
+}
+
+// hashes of blocks generated
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GenerateToAddressCommandResponse(pub Vec<Blockhash>);
+
+impl CallableCommand for GenerateToAddressCommand {
+    type Response = GenerateToAddressCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let nblocks_arg_raw_value = to_raw_value(&self.nblocks).unwrap();
+        let address_arg_raw_value = to_raw_value(&self.address).unwrap();
+        let mut params = vec![nblocks_arg_raw_value, address_arg_raw_value];
+        if let Some(maxtries) = &self.maxtries {
+            let maxtries_arg_raw_value = to_raw_value(&maxtries).unwrap();
+            params.push(maxtries_arg_raw_value)
+        }
+        let r = request(client, GENERATE_TO_ADDRESS_COMMAND, params)?;
+        let response: GenerateToAddressCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,84 @@
+/*
+generatetodescriptor num_blocks "descriptor" ( maxtries )
+
+Mine to a specified descriptor and return the block hashes.
+
+Arguments:
+1. num_blocks    (numeric, required) How many blocks are generated.
+2. descriptor    (string, required) The descriptor to send the newly generated bitcoin to.
+3. maxtries      (numeric, optional, default=1000000) How many iterations to try.
+
+Result:
+[           (json array) hashes of blocks generated
+  "hex",    (string) blockhash
+  ...
+]
+
+Examples:
+Generate 11 blocks to mydesc
+> bitcoin-cli generatetodescriptor 11 "mydesc"
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::version::{require_version, NodeVersion};
+use crate::Blockhash;
+
+const GENERATE_TO_DESCRIPTOR_COMMAND: &str = "generatetodescriptor";
+
+// Anyone can spend outputs to this descriptor (a bare OP_TRUE), which makes it handy for mining on
+// regtest without a wallet.
+pub const OP_TRUE_DESCRIPTOR: &str = "raw(51)";
+
+pub struct GenerateToDescriptorCommand {
+    num_blocks: u64,       // (numeric, required) How many blocks are generated.
+    descriptor: String, // (string, required) The descriptor to send the newly generated bitcoin to.
+    maxtries: Option<u64>, // (numeric, optional, default=1000000) How many iterations to try.
+}
+impl GenerateToDescriptorCommand {
+    pub fn new(num_blocks: u64, descriptor: String) -> Self {
+        GenerateToDescriptorCommand {
+            num_blocks,
+            descriptor,
+            maxtries: None,
+        }
+    }
+    pub fn set_maxtries(mut self, maxtries: u64) -> Self {
+        self.maxtries = Some(maxtries);
+        self
+    }
+}
+
+// hashes of blocks generated
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GenerateToDescriptorCommandResponse(pub Vec<Blockhash>);
+
+impl CallableCommand for GenerateToDescriptorCommand {
+    type Response = GenerateToDescriptorCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        require_version(client, GENERATE_TO_DESCRIPTOR_COMMAND, NodeVersion::V0_20)?;
+        let num_blocks_arg_raw_value = to_raw_value(&self.num_blocks).unwrap();
+        let descriptor_arg_raw_value = to_raw_value(&self.descriptor).unwrap();
+        let mut params = vec![num_blocks_arg_raw_value, descriptor_arg_raw_value];
+        if let Some(maxtries) = &self.maxtries {
+            let maxtries_arg_raw_value = to_raw_value(&maxtries).unwrap();
+            params.push(maxtries_arg_raw_value)
+        }
+        let r = request(client, GENERATE_TO_DESCRIPTOR_COMMAND, params)?;
+        let response: GenerateToDescriptorCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
+
+// Mines `num_blocks` blocks to OP_TRUE_DESCRIPTOR, standing in for the removed "generate" RPC.
+pub fn generate(client: &Client, num_blocks: u64) -> Result<Vec<Blockhash>, jsonrpc::Error> {
+    let response = GenerateToDescriptorCommand::new(num_blocks, OP_TRUE_DESCRIPTOR.to_string())
+        .call(client)?;
+    Ok(response.0)
+}
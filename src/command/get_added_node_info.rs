@@ -0,0 +1,93 @@
+/*
+getaddednodeinfo ( "node" )
+
+Returns information about the given added node, or all added nodes
+(note that onetry addnodes are not listed here)
+
+Arguments:
+1. node    (string, optional, default=all nodes) If provided, return information about this specific node, otherwise all nodes are returned.
+
+Result:
+[                                (json array)
+  {                              (json object)
+    "addednode" : "str",         (string) The node IP address or name (as provided to addnode)
+    "connected" : true|false,    (boolean) If connected
+    "addresses" : [              (json array) Only when connected = true
+      {                          (json object)
+        "address" : "str",       (string) The bitcoin server IP and port we're connected to
+        "connected" : "str"      (string) connection, inbound or outbound
+      },
+      ...
+    ]
+  },
+  ...
+]
+
+Examples:
+> bitcoin-cli getaddednodeinfo "192.168.0.201"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getaddednodeinfo", "params": ["192.168.0.201"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const GET_ADDED_NODE_INFO_COMMAND: &str = "getaddednodeinfo";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionDirection {
+    Inbound,
+    Outbound,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AddedNodeAddress {
+    pub address: String, // The bitcoin server IP and port we're connected to
+    pub connected: ConnectionDirection, // connection, inbound or outbound
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AddedNodeInfo {
+    pub addednode: String, // The node IP address or name (as provided to addnode)
+    pub connected: bool,   // If connected
+    #[serde(default)]
+    pub addresses: Vec<AddedNodeAddress>, // Only when connected = true
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetAddedNodeInfoCommandResponse(pub Vec<AddedNodeInfo>);
+
+pub struct GetAddedNodeInfoCommand {
+    node: Option<String>, // (string, optional, default=all nodes) If provided, return information about this specific node
+}
+impl GetAddedNodeInfoCommand {
+    pub fn new() -> Self {
+        GetAddedNodeInfoCommand { node: None }
+    }
+    pub fn set_node(mut self, node: String) -> Self {
+        self.node = Some(node);
+        self
+    }
+}
+impl Default for GetAddedNodeInfoCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for GetAddedNodeInfoCommand {
+    type Response = GetAddedNodeInfoCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let mut params = vec![];
+        if let Some(node) = &self.node {
+            let node_arg_raw_value = to_raw_value(&node).unwrap();
+            params.push(node_arg_raw_value)
+        }
+        let r = request(client, GET_ADDED_NODE_INFO_COMMAND, params)?;
+        let response: GetAddedNodeInfoCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
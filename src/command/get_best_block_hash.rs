@@ -17,16 +17,15 @@ use crate::Blockhash;
 use crate::BlockhashHexEncoded;
 use serde::Deserialize;
 use serde::Serialize;
-use serde_json::value::{to_raw_value, RawValue};
+use serde_json::value::RawValue;
 
-pub struct GetBestBlockHashCommand {
-    blockhash_hex_encoded: BlockhashHexEncoded,
-}
+#[derive(Default)]
+pub struct GetBestBlockHashCommand {}
 impl GetBestBlockHashCommand {
-    pub fn new(blockhash_hex_encoded: BlockhashHexEncoded) -> Self {
-        GetBestBlockHashCommand {
-            blockhash_hex_encoded,
-        }
+    // getbestblockhash takes no arguments, so the blockhash was never sent.
+    #[deprecated(note = "the blockhash is ignored, use GetBestBlockHashCommand::default()")]
+    pub fn new(_blockhash_hex_encoded: BlockhashHexEncoded) -> Self {
+        GetBestBlockHashCommand {}
     }
 }
 #[derive(Serialize, Deserialize, Debug)]
@@ -35,10 +34,9 @@ pub struct GetBestBlockHashCommandResponse(pub Blockhash);
 impl CallableCommand for GetBestBlockHashCommand {
     type Response = GetBestBlockHashCommandResponse;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
-        let blockhash_arg = &self.blockhash_hex_encoded.0;
         let command = "getbestblockhash";
         let params: Vec<Box<RawValue>> = vec![];
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetBestBlockHashCommandResponse = r.result()?;
         Ok(response)
     }
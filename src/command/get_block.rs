@@ -5,10 +5,11 @@ getblock "blockhash" ( verbosity )
 If verbosity is 0, returns a string that is serialized, hex-encoded data for block 'hash'.
 If verbosity is 1, returns an Object with information about block <hash>.
 If verbosity is 2, returns an Object with information about block <hash> and information about each transaction.
+If verbosity is 3, returns an Object with information about block <hash> and information about each transaction, including prevout information for inputs (only for unpruned blocks in the current best chain).
 
 Arguments:
 1. blockhash    (string, required) The block hash
-2. verbosity    (numeric, optional, default=1) 0 for hex-encoded data, 1 for a json object, and 2 for json object with transaction data
+2. verbosity    (numeric, optional, default=1) 0 for hex-encoded data, 1 for a json object, 2 for json object with transaction data, and 3 for JSON object with transaction data including prevout information for inputs
 
 Result (for verbosity = 0):
 "hex"    (string) A string that is serialized, hex-encoded data for block 'hash'
@@ -45,6 +46,34 @@ Result (for verbosity = 2):
   "tx" : [    (json array)
     {         (json object)
       ...     The transactions in the format of the getrawtransaction RPC. Different from verbosity = 1 "tx" result
+      "fee" : n         (numeric) The transaction fee in BTC, omitted if block undo data is not available
+    },
+    ...
+  ]
+}
+
+Result (for verbosity = 3):
+{                                        (json object)
+  ...,                                   Same output as verbosity = 2
+  "tx" : [                               (json array)
+    {                                    (json object)
+      "vin" : [                          (json array)
+        {                                (json object)
+          ...,                           The same output as verbosity = 2
+          "prevout" : {                  (json object) (Only if undo information is available)
+            "generated" : true|false,    (boolean) Coinbase or not
+            "height" : n,                (numeric) The height of the prevout
+            "value" : n,                 (numeric) The value in BTC
+            "scriptPubKey" : {           (json object)
+              "asm" : "str",             (string) The asm
+              "hex" : "str",             (string) The hex
+              "address" : "str",         (string, optional) The Bitcoin address (only if a well-defined address exists)
+              "type" : "str"             (string) The type (one of: nonstandard, pubkey, pubkeyhash, scripthash, multisig, nulldata, witness_v0_scripthash, witness_v0_keyhash, witness_v1_taproot, witness_unknown)
+            }
+          }
+        },
+        ...
+      ]
     },
     ...
   ]
@@ -55,104 +84,56 @@ Examples:
 > curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getblock", "params": ["00000000c937983704a73af28acdec37b049d214adbda81d7e2a3dd146f6ed09"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
 */
 use crate::command::{request::request, CallableCommand};
+use crate::types::work::{Bits, Work};
 use crate::Blockhash;
 use serde::{Deserialize, Serialize};
 use serde_json::value::to_raw_value;
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-#[serde(untagged)]
-pub enum Vin {
-    Coinbase(CoinbaseVin),
-    NonCoinbase(NonCoinbaseVin),
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct HexEncodedWitnessData(pub String);
-
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct CoinbaseVin {
-    pub coinbase: String,
-    pub sequence: u64, // The script sequence number
-    pub txinwitness: Option<Vec<HexEncodedWitnessData>>, // hex-encoded witness data (if any)
-}
+pub use crate::types::transaction::{
+    CoinbaseVin, HexEncodedWitnessData, NonCoinbaseVin, Prevout, ScriptPubKey, ScriptSig,
+    Transaction, Vin, Vout,
+};
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct ScriptSig {
-    pub asm: String, // "asm", NOT A HEX
-    pub hex: String, // "hex", hex
-}
+// Kept for callers written against the old, block-specific transaction type.
+pub type DecodeRawTransactionResponse = Transaction;
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct NonCoinbaseVin {
-    pub txid: String, // "hex" The transaction id
-    pub vout: u64,    // The output number
-    pub script_sig: ScriptSig,
-    pub sequence: u64, // The script sequence number
-    // TODO: Why is this optional?
-    pub txinwitness: Option<Vec<HexEncodedWitnessData>>,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ScriptPubKey {
-    pub asm: String,
-    pub hex: String,
-    pub address: Option<String>,
-    // TODO: Can't use "type" as a key because it's a reserved word in Rust.
-    #[serde(rename = "type")]
-    pub type_: String,
-}
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct Vout {
-    pub value: f64,
-    pub n: i64,
-    pub script_pub_key: ScriptPubKey,
-}
-#[derive(Serialize, Deserialize, Debug)]
-pub struct DecodeRawTransactionResponse {
-    pub in_active_chain: Option<bool>,
-    pub hex: String,   // "hex" The serialized, hex-encoded data for 'txid'
-    pub txid: String,  // "hex" The transaction id (same as provided)
-    pub hash: String,  // "hex" The transaction hash (differs from txid for witness transactions)
-    pub size: u64,     // The serialized transaction size
-    pub vsize: u64,    // The virtual transaction size (differs from size for witness transactions)
-    pub weight: u64,   //  The transaction's weight (between vsize*4-3 and vsize*4)
-    pub version: u64,  //  The version
-    pub locktime: u64, // The lock time
-    pub vin: Vec<Vin>,
-    pub vout: Vec<Vout>,
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum GetBlockCommandTransactionResponse {
+    Raw(Transaction),
+    Id(String),
 }
 
-// TODO: I don't think this belongs in this package. We should focus on RPC request and responses
-// and abstract a better data layer into another package.
-impl DecodeRawTransactionResponse {
-    pub fn is_coinbase_transaction(&self) -> bool {
-        match self.vin.first().unwrap() {
-            Vin::Coinbase(_x) => true,
-            Vin::NonCoinbase(_x) => false,
+impl GetBlockCommandTransactionResponse {
+    pub fn txid(&self) -> &str {
+        match self {
+            GetBlockCommandTransactionResponse::Raw(transaction) => &transaction.txid,
+            GetBlockCommandTransactionResponse::Id(txid) => txid,
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-pub enum GetBlockCommandTransactionResponse {
-    Raw(DecodeRawTransactionResponse),
-    Id(String),
+// Fails with the txid when the block was requested without transaction information.
+impl TryFrom<GetBlockCommandTransactionResponse> for Transaction {
+    type Error = String;
+    fn try_from(response: GetBlockCommandTransactionResponse) -> Result<Self, Self::Error> {
+        match response {
+            GetBlockCommandTransactionResponse::Raw(transaction) => Ok(transaction),
+            GetBlockCommandTransactionResponse::Id(txid) => Err(txid),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum GetBlockCommandResponse {
     BlockHash(String),
     Block(Block),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Block {
     pub hash: String,        // "hex" (string) the block hash (same as provided)
@@ -168,21 +149,20 @@ pub struct Block {
     pub time: u64,          // "unix epoch time" The block time expressed in UNIX epoch time
     pub mediantime: u64,    // "unix epoch time" The median block time expressed in UNIX epoch time
     pub nonce: u64,         // The nonce
-    pub bits: String,       // "hex" The bits
+    pub bits: Bits,         // "hex" The bits
     pub difficulty: f64,    // The difficulty
-    pub chainwork: String, // "hex" Expected number of hashes required to produce the chain up to this block (in hex)
-    pub n_tx: u64,         // The number of transactions in the block
+    pub chainwork: Work, // "hex" Expected number of hashes required to produce the chain up to this block (in hex)
+    pub n_tx: u64,       // The number of transactions in the block
     pub previousblockhash: Option<String>, // The hash of the previous block
     // TODO: Why isn't this always there?
     pub nextblockhash: Option<String>, // The hash of the next block
 }
 
-type GetBlockAsSerializedHextEncodedDataCommandResponse = String;
-
 pub enum GetBlockCommandVerbosity {
-    SerializedHexEncodedData,                 // argument of 0
-    BlockObjectWithoutTransactionInformation, // argument of 1
-    BlockObjectWithTransactionInformation,    // argument of 2
+    SerializedHexEncodedData,                                   // argument of 0
+    BlockObjectWithoutTransactionInformation,                   // argument of 1
+    BlockObjectWithTransactionInformation,                      // argument of 2
+    BlockObjectWithTransactionInformationAndPrevoutInformation, // argument of 3
 }
 
 pub struct GetBlockCommand {
@@ -202,12 +182,6 @@ impl GetBlockCommand {
     }
 }
 
-// TODO: This will only work for GetBlockCommandVerbosity::BlockObjectWithoutTransactionInformation
-//       because the json response has a different structure it returns for each verbosity option.
-//       For example, GetBlockCommandVerbosity::BlockObjectWithTransactionInformation will return
-//       an array for 'tx' field with full transaction structure, instead of only hashes for the
-//       transaction. To accomplish this, we need to figure out how to have serde handle
-//       conditional responses and map them to appropriate structs.
 impl CallableCommand for GetBlockCommand {
     type Response = GetBlockCommandResponse;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
@@ -215,13 +189,16 @@ impl CallableCommand for GetBlockCommand {
             GetBlockCommandVerbosity::SerializedHexEncodedData => 0,
             GetBlockCommandVerbosity::BlockObjectWithoutTransactionInformation => 1,
             GetBlockCommandVerbosity::BlockObjectWithTransactionInformation => 2,
+            GetBlockCommandVerbosity::BlockObjectWithTransactionInformationAndPrevoutInformation => {
+                3
+            }
         };
         let blockhash_arg = &self.blockhash.0;
         let blockhash_arg_raw_value = to_raw_value(&blockhash_arg).unwrap();
         let verbosity_arg_raw_value = to_raw_value(&verbosity_arg).unwrap();
         let command = "getblock";
         let params = vec![blockhash_arg_raw_value, verbosity_arg_raw_value];
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetBlockCommandResponse = r.result()?;
         Ok(response)
     }
@@ -22,6 +22,11 @@ impl GetBlockCountCommand {
         GetBlockCountCommand {}
     }
 }
+impl Default for GetBlockCountCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetBlockCountCommandResponse(pub u64);
@@ -31,7 +36,7 @@ impl CallableCommand for GetBlockCountCommand {
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
         let command = "getblockcount";
         let params: Vec<Box<RawValue>> = vec![];
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetBlockCountCommandResponse = r.result()?;
         Ok(response)
     }
@@ -0,0 +1,80 @@
+/*
+getblockfilter "blockhash" ( "filtertype" )
+
+Retrieve a BIP 157 content filter for a particular block.
+
+Arguments:
+1. blockhash     (string, required) The hash of the block
+2. filtertype    (string, optional, default="basic") The type name of the filter
+
+Result:
+{                      (json object)
+  "filter" : "hex",    (string) the hex-encoded filter data
+  "header" : "hex"     (string) the hex-encoded filter header
+}
+
+Examples:
+> bitcoin-cli getblockfilter "00000000c937983704a73af28acdec37b049d214adbda81d7e2a3dd146f6ed09" "basic"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getblockfilter", "params": ["00000000c937983704a73af28acdec37b049d214adbda81d7e2a3dd146f6ed09", "basic"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::consensus::block_filter::{BlockFilter, BlockFilterError};
+use crate::consensus::hash::Hash256;
+use crate::version::{require_version, NodeVersion};
+use crate::Blockhash;
+
+const GET_BLOCK_FILTER_COMMAND: &str = "getblockfilter";
+
+// Requires the node to run with -blockfilterindex.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockFilterType {
+    Basic, // BIP158 basic filter
+}
+
+pub struct GetBlockFilterCommand {
+    blockhash: Blockhash,        // (string, required) The hash of the block
+    filtertype: BlockFilterType, // (string, optional, default="basic") The type name of the filter
+}
+impl GetBlockFilterCommand {
+    pub fn new(blockhash: Blockhash) -> Self {
+        GetBlockFilterCommand {
+            blockhash,
+            filtertype: BlockFilterType::Basic,
+        }
+    }
+    pub fn set_filtertype(mut self, filtertype: BlockFilterType) -> Self {
+        self.filtertype = filtertype;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetBlockFilterCommandResponse {
+    pub filter: String,  // the hex-encoded filter data
+    pub header: Hash256, // the hex-encoded filter header
+}
+
+impl GetBlockFilterCommandResponse {
+    pub fn decode_filter(&self) -> Result<BlockFilter, BlockFilterError> {
+        BlockFilter::from_hex(&self.filter)
+    }
+}
+
+impl CallableCommand for GetBlockFilterCommand {
+    type Response = GetBlockFilterCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        require_version(client, GET_BLOCK_FILTER_COMMAND, NodeVersion::V0_19)?;
+        let blockhash_arg_raw_value = to_raw_value(&self.blockhash.0).unwrap();
+        let filtertype_arg_raw_value = to_raw_value(&self.filtertype).unwrap();
+        let params = vec![blockhash_arg_raw_value, filtertype_arg_raw_value];
+        let r = request(client, GET_BLOCK_FILTER_COMMAND, params)?;
+        let response: GetBlockFilterCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
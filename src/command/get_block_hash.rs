@@ -17,7 +17,7 @@ use crate::command::CallableCommand;
 use crate::Blockhash;
 use crate::{client::Client, command::request::request};
 use serde::{Deserialize, Serialize};
-use serde_json::value::{to_raw_value, RawValue};
+use serde_json::value::to_raw_value;
 
 type BlockHeight = u64;
 pub struct GetBlockHashCommand {
@@ -39,7 +39,7 @@ impl CallableCommand for GetBlockHashCommand {
         let height_arg_raw_value = to_raw_value(height_arg).unwrap();
         let command = "getblockhash";
         let params = vec![height_arg_raw_value];
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetBlockHashCommandResponse = r.result()?;
         Ok(response)
     }
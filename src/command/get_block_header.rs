@@ -36,12 +36,14 @@ Examples:
 > curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getblockheader", "params": ["00000000c937983704a73af28acdec37b049d214adbda81d7e2a3dd146f6ed09"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
  */
 use crate::command::{request::request, CallableCommand};
+use crate::types::work::{Bits, Work};
 use crate::Blockhash;
 use serde::{Deserialize, Serialize};
 use serde_json::value::to_raw_value;
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum GetBlockHeaderCommandResponse {
     BlockHash(String),
     BlockHeader(BlockHeader),
@@ -59,10 +61,10 @@ pub struct BlockHeader {
     pub time: u64,          // "unix epoch time" The block time expressed in UNIX epoch time
     pub mediantime: u64,    // "unix epoch time" The median block time expressed in UNIX epoch time
     pub nonce: u64,         // The nonce
-    pub bits: String,       // "hex" The bits
+    pub bits: Bits,         // "hex" The bits
     pub difficulty: f64,    // The difficulty
-    pub chainwork: String, // "hex" Expected number of hashes required to produce the chain up to this block (in hex)
-    pub n_tx: u64,         // The number of transactions in the block
+    pub chainwork: Work, // "hex" Expected number of hashes required to produce the chain up to this block (in hex)
+    pub n_tx: u64,       // The number of transactions in the block
     pub previousblockhash: Option<String>, // The hash of the previous block
     // TODO: Why isn't this always there?
     pub nextblockhash: Option<String>, // The hash of the next block
@@ -93,7 +95,7 @@ impl CallableCommand for GetBlockHeaderCommand {
         let verbose_arg_raw_value = to_raw_value(&verbose_arg).unwrap();
         let command = "getblockheader";
         let params = vec![blockhash_arg_raw_value, verbose_arg_raw_value];
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetBlockHeaderCommandResponse = r.result()?;
         Ok(response)
     }
@@ -48,8 +48,10 @@ Result:
   "total_weight" : n,          (numeric) Total weight of all non-coinbase transactions
   "totalfee" : n,              (numeric) The fee total
   "txs" : n,                   (numeric) The number of transactions (including coinbase)
-  "utxo_increase" : n,         (numeric) The increase/decrease in the number of unspent outputs
-  "utxo_size_inc" : n          (numeric) The increase/decrease in size for the utxo index (not discounting op_return and similar)
+  "utxo_increase" : n,         (numeric) The increase/decrease in the number of unspent outputs (not discounting op_return and similar)
+  "utxo_increase_actual" : n,  (numeric, optional) The increase/decrease in the number of unspent outputs, not counting unspendables
+  "utxo_size_inc" : n,         (numeric) The increase/decrease in size for the utxo index (not discounting op_return and similar)
+  "utxo_size_inc_actual" : n   (numeric, optional) The increase/decrease in size for the utxo index, not counting unspendables
 }
 
 Examples:
@@ -58,24 +60,27 @@ Examples:
 > curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getblockstats", "params": ["00000000c937983704a73af28acdec37b049d214adbda81d7e2a3dd146f6ed09", ["minfeerate","avgfeerate"]]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
 > curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getblockstats", "params": [1000, ["minfeerate","avgfeerate"]]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
  */
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::value::to_raw_value;
+use std::error::Error;
 use std::fmt;
 
 use crate::{client::Client, command::CallableCommand, Blockhash};
 
 use super::request::request;
 
+const GET_BLOCK_STATS_COMMAND: &str = "getblockstats";
+
 type BlockHeight = u64;
 pub enum TargetBlockArgument {
     Hash(Blockhash),
     Height(BlockHeight),
 }
-// TODO: Fill in all of these:
-//     https://bitcoincore.org/en/doc/0.21.0/rpc/blockchain/getblockstats/
-#[derive(Serialize, Deserialize, Debug)]
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum StatsArgumentChoices {
     AvgFee,
+    AvgFeeRate,
     AvgTxSize,
     Blockhash,
     FeeRatePercentiles,
@@ -102,101 +107,126 @@ pub enum StatsArgumentChoices {
     TotalFee,
     Txs,
     UtxoIncrease,
+    UtxoIncreaseActual, // added in bitcoin core 25
     UtxoSizeInc,
+    UtxoSizeIncActual, // added in bitcoin core 25
 }
-impl fmt::Display for StatsArgumentChoices {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+
+impl StatsArgumentChoices {
+    pub const ALL: [StatsArgumentChoices; 31] = [
+        StatsArgumentChoices::AvgFee,
+        StatsArgumentChoices::AvgFeeRate,
+        StatsArgumentChoices::AvgTxSize,
+        StatsArgumentChoices::Blockhash,
+        StatsArgumentChoices::FeeRatePercentiles,
+        StatsArgumentChoices::Height,
+        StatsArgumentChoices::Ins,
+        StatsArgumentChoices::MaxFee,
+        StatsArgumentChoices::MaxFeeRate,
+        StatsArgumentChoices::MaxTxSize,
+        StatsArgumentChoices::MedianFee,
+        StatsArgumentChoices::MedianTime,
+        StatsArgumentChoices::MedianTxSize,
+        StatsArgumentChoices::MinFee,
+        StatsArgumentChoices::MinFeeRate,
+        StatsArgumentChoices::MinTxSize,
+        StatsArgumentChoices::Outs,
+        StatsArgumentChoices::Subsidy,
+        StatsArgumentChoices::SwTotalSize,
+        StatsArgumentChoices::SwTotalWeight,
+        StatsArgumentChoices::SwTxs,
+        StatsArgumentChoices::Time,
+        StatsArgumentChoices::TotalOut,
+        StatsArgumentChoices::TotalSize,
+        StatsArgumentChoices::TotalWeight,
+        StatsArgumentChoices::TotalFee,
+        StatsArgumentChoices::Txs,
+        StatsArgumentChoices::UtxoIncrease,
+        StatsArgumentChoices::UtxoIncreaseActual,
+        StatsArgumentChoices::UtxoSizeInc,
+        StatsArgumentChoices::UtxoSizeIncActual,
+    ];
+    // The name of the stat, both in the stats argument and as the field of the result.
+    pub fn as_str(&self) -> &'static str {
         match self {
-            StatsArgumentChoices::AvgFee => write!(f, "avgfee"),
-            StatsArgumentChoices::AvgTxSize => write!(f, "avgtxsize"),
-            StatsArgumentChoices::Blockhash => write!(f, "blockhash"),
-            StatsArgumentChoices::FeeRatePercentiles => write!(f, "feerate_percentiles"),
-            StatsArgumentChoices::Height => write!(f, "height"),
-            StatsArgumentChoices::Ins => write!(f, "ins"),
-            StatsArgumentChoices::MaxFee => write!(f, "maxfee"),
-            StatsArgumentChoices::MaxFeeRate => write!(f, "maxfeerate"),
-            StatsArgumentChoices::MaxTxSize => write!(f, "maxtxsize"),
-            StatsArgumentChoices::MedianFee => write!(f, "medianfee"),
-            StatsArgumentChoices::MedianTime => write!(f, "mediantime"),
-            StatsArgumentChoices::MedianTxSize => write!(f, "mediantxsize"),
-            StatsArgumentChoices::MinFee => write!(f, "minfee"),
-            StatsArgumentChoices::MinFeeRate => write!(f, "minfeerate"),
-            StatsArgumentChoices::MinTxSize => write!(f, "mintxsize"),
-            StatsArgumentChoices::Outs => write!(f, "outs"),
-            StatsArgumentChoices::Subsidy => write!(f, "subsidy"),
-            StatsArgumentChoices::SwTotalSize => write!(f, "swtotal_size"),
-            StatsArgumentChoices::SwTotalWeight => write!(f, "swtotal_weight"),
-            StatsArgumentChoices::SwTxs => write!(f, "swtxs"),
-            StatsArgumentChoices::Time => write!(f, "time"),
-            StatsArgumentChoices::TotalOut => write!(f, "total_out"),
-            StatsArgumentChoices::TotalSize => write!(f, "total_size"),
-            StatsArgumentChoices::TotalWeight => write!(f, "total_weight"),
-            StatsArgumentChoices::TotalFee => write!(f, "totalfee"),
-            StatsArgumentChoices::Txs => write!(f, "txs"),
-            StatsArgumentChoices::UtxoIncrease => write!(f, "utxo_increase"),
-            StatsArgumentChoices::UtxoSizeInc => write!(f, "utxo_size_inc"),
+            StatsArgumentChoices::AvgFee => "avgfee",
+            StatsArgumentChoices::AvgFeeRate => "avgfeerate",
+            StatsArgumentChoices::AvgTxSize => "avgtxsize",
+            StatsArgumentChoices::Blockhash => "blockhash",
+            StatsArgumentChoices::FeeRatePercentiles => "feerate_percentiles",
+            StatsArgumentChoices::Height => "height",
+            StatsArgumentChoices::Ins => "ins",
+            StatsArgumentChoices::MaxFee => "maxfee",
+            StatsArgumentChoices::MaxFeeRate => "maxfeerate",
+            StatsArgumentChoices::MaxTxSize => "maxtxsize",
+            StatsArgumentChoices::MedianFee => "medianfee",
+            StatsArgumentChoices::MedianTime => "mediantime",
+            StatsArgumentChoices::MedianTxSize => "mediantxsize",
+            StatsArgumentChoices::MinFee => "minfee",
+            StatsArgumentChoices::MinFeeRate => "minfeerate",
+            StatsArgumentChoices::MinTxSize => "mintxsize",
+            StatsArgumentChoices::Outs => "outs",
+            StatsArgumentChoices::Subsidy => "subsidy",
+            StatsArgumentChoices::SwTotalSize => "swtotal_size",
+            StatsArgumentChoices::SwTotalWeight => "swtotal_weight",
+            StatsArgumentChoices::SwTxs => "swtxs",
+            StatsArgumentChoices::Time => "time",
+            StatsArgumentChoices::TotalOut => "total_out",
+            StatsArgumentChoices::TotalSize => "total_size",
+            StatsArgumentChoices::TotalWeight => "total_weight",
+            StatsArgumentChoices::TotalFee => "totalfee",
+            StatsArgumentChoices::Txs => "txs",
+            StatsArgumentChoices::UtxoIncrease => "utxo_increase",
+            StatsArgumentChoices::UtxoIncreaseActual => "utxo_increase_actual",
+            StatsArgumentChoices::UtxoSizeInc => "utxo_size_inc",
+            StatsArgumentChoices::UtxoSizeIncActual => "utxo_size_inc_actual",
         }
     }
 }
+impl fmt::Display for StatsArgumentChoices {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+impl Serialize for StatsArgumentChoices {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+impl<'de> Deserialize<'de> for StatsArgumentChoices {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        StatsArgumentChoices::ALL
+            .into_iter()
+            .find(|stat| stat.as_str() == name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown block stat: {}", name)))
+    }
+}
+
 pub struct GetBlockStatsCommand {
     target_block: TargetBlockArgument,
-    stats: Vec<StatsArgumentChoices>,
 }
 
 impl GetBlockStatsCommand {
     pub fn new(target_block: TargetBlockArgument) -> Self {
-        GetBlockStatsCommand {
-            target_block,
-            stats: vec![],
-        }
+        GetBlockStatsCommand { target_block }
     }
-    pub fn add_selective_stats(
-        &mut self,
-        stats_argument_choices: Vec<StatsArgumentChoices>,
-    ) -> &Self {
-        self.stats = stats_argument_choices;
-        self
+    // Only compute the chosen stats, which is cheaper for the node. The response only gives
+    // access to those.
+    pub fn select(self, stats: Vec<StatsArgumentChoices>) -> GetSelectedBlockStatsCommand {
+        GetSelectedBlockStatsCommand {
+            target_block: self.target_block,
+            stats,
+        }
     }
 }
-#[derive(Serialize, Deserialize, Debug)]
-pub struct GetBlockStatsCommandWithSelectiveStatsResponse {
-    pub avgfee: Option<u64>,                   // Average fee in the block
-    pub avgfeerate: Option<u64>,               // Average feerate (in satoshis per virtual byte)
-    pub avgtxsize: Option<u64>,                // Average transaction size
-    pub blockhash: Option<String>, // "hex" The block hash (to check for potential reorgs)
-    pub feerate_percentiles: Option<[u64; 5]>, //  Feerates at the 10th, 25th, 50th, 75th, and 90th percentile weight unit (in satoshis per virtual byte)
-    //  index 0,                         (numeric) The 10th percentile feerate
-    //  index 1                        (numeric) The 25th percentile feerate
-    //  index 2                         (numeric) The 50th percentile feerate
-    //  index 3,                         (numeric) The 75th percentile feerate
-    //  index 4                         (numeric) The 90th percentile feerate
-    pub height: Option<u64>,         // The height of the block
-    pub ins: Option<u64>,            // The number of inputs (excluding coinbase)
-    pub maxfee: Option<u64>,         // Maximum fee in the block
-    pub maxfeerate: Option<u64>,     // Maximum feerate (in satoshis per virtual byte)
-    pub maxtxsize: Option<u64>,      // Maximum transaction size
-    pub medianfee: Option<u64>,      //Truncated median fee in the block
-    pub mediantime: Option<u64>,     // The block median time past
-    pub mediantxsize: Option<u64>,   // Truncated median transaction size
-    pub minfee: Option<u64>,         // Minimum fee in the block
-    pub minfeerate: Option<u64>,     // Minimum feerate (in satoshis per virtual byte)
-    pub mintxsize: Option<u64>,      // Minimum transaction size
-    pub outs: Option<u64>,           // The number of outputs
-    pub subsidy: Option<u64>,        // The block subsidy
-    pub swtotal_size: Option<u64>,   // Total size of all segwit transactions
-    pub swtotal_weight: Option<u64>, // Total weight of all segwit transactions
-    pub swtxs: Option<u64>,          // The number of segwit transactions
-    pub time: Option<u64>,           // The block time
-    pub total_out: Option<u64>, // Total amount in all outputs (excluding coinbase and thus reward [ie subsidy + totalfee])
-    pub total_size: Option<u64>, // Total size of all non-coinbase transactions
-    pub total_weight: Option<u64>, // Total weight of all non-coinbase transactions
-    pub totalfee: Option<u64>,  // The fee total
-    pub txs: Option<u64>,       // The number of transactions (including coinbase)
-    pub utxo_increase: Option<u64>, // The increase/decrease in the number of unspent outputs
-    pub utxo_size_inc: Option<u64>, // The increase/decrease in size for the utxo index (not discounting op_return and similar)
+
+pub struct GetSelectedBlockStatsCommand {
+    target_block: TargetBlockArgument,
+    stats: Vec<StatsArgumentChoices>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetBlockStatsCommandWithAllStatsResponse {
     pub avgfee: u64,                   // Average fee in the block
     pub avgfeerate: u64,               // Average feerate (in satoshis per virtual byte)
@@ -208,60 +238,155 @@ pub struct GetBlockStatsCommandWithAllStatsResponse {
     //  index 2                         (numeric) The 50th percentile feerate
     //  index 3,                         (numeric) The 75th percentile feerate
     //  index 4                         (numeric) The 90th percentile feerate
-    pub height: u64,         // The height of the block
-    pub ins: u64,            // The number of inputs (excluding coinbase)
-    pub maxfee: u64,         // Maximum fee in the block
-    pub maxfeerate: u64,     // Maximum feerate (in satoshis per virtual byte)
-    pub maxtxsize: u64,      // Maximum transaction size
-    pub medianfee: u64,      //Truncated median fee in the block
-    pub mediantime: u64,     // The block median time past
-    pub mediantxsize: u64,   // Truncated median transaction size
-    pub minfee: u64,         // Minimum fee in the block
-    pub minfeerate: u64,     // Minimum feerate (in satoshis per virtual byte)
-    pub mintxsize: u64,      // Minimum transaction size
-    pub outs: u64,           // The number of outputs
-    pub subsidy: u64,        // The block subsidy
-    pub swtotal_size: u64,   // Total size of all segwit transactions
-    pub swtotal_weight: u64, // Total weight of all segwit transactions
-    pub swtxs: u64,          // The number of segwit transactions
-    pub time: u64,           // The block time
+    pub height: u64,                       // The height of the block
+    pub ins: u64,                          // The number of inputs (excluding coinbase)
+    pub maxfee: u64,                       // Maximum fee in the block
+    pub maxfeerate: u64,                   // Maximum feerate (in satoshis per virtual byte)
+    pub maxtxsize: u64,                    // Maximum transaction size
+    pub medianfee: u64,                    //Truncated median fee in the block
+    pub mediantime: u64,                   // The block median time past
+    pub mediantxsize: u64,                 // Truncated median transaction size
+    pub minfee: u64,                       // Minimum fee in the block
+    pub minfeerate: u64,                   // Minimum feerate (in satoshis per virtual byte)
+    pub mintxsize: u64,                    // Minimum transaction size
+    pub outs: u64,                         // The number of outputs
+    pub subsidy: u64,                      // The block subsidy
+    pub swtotal_size: u64,                 // Total size of all segwit transactions
+    pub swtotal_weight: u64,               // Total weight of all segwit transactions
+    pub swtxs: u64,                        // The number of segwit transactions
+    pub time: u64,                         // The block time
     pub total_out: u64, // Total amount in all outputs (excluding coinbase and thus reward [ie subsidy + totalfee])
     pub total_size: u64, // Total size of all non-coinbase transactions
     pub total_weight: u64, // Total weight of all non-coinbase transactions
     pub totalfee: u64,  // The fee total
     pub txs: u64,       // The number of transactions (including coinbase)
-    pub utxo_increase: i64, // The increase/decrease in the number of unspent outputs
+    pub utxo_increase: i64, // The increase/decrease in the number of unspent outputs (not discounting op_return and similar)
+    pub utxo_increase_actual: Option<i64>, // The increase/decrease in the number of unspent outputs, not counting unspendables (added in bitcoin core 25)
     pub utxo_size_inc: i64, // The increase/decrease in size for the utxo index (not discounting op_return and similar)
+    pub utxo_size_inc_actual: Option<i64>, // The increase/decrease in size for the utxo index, not counting unspendables (added in bitcoin core 25)
+}
+
+// A stat was read from a selective response that it wasn't selected for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatNotSelected(pub StatsArgumentChoices);
+
+impl fmt::Display for StatNotSelected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "block stat {} wasn't selected", self.0)
+    }
+}
+
+impl Error for StatNotSelected {}
+
+// The node only returns the selected stats, so the fields are private and each accessor fails
+// with StatNotSelected for a stat that wasn't selected, rather than handing out a default.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetBlockStatsCommandWithSelectiveStatsResponse {
+    avgfee: Option<u64>,
+    avgfeerate: Option<u64>,
+    avgtxsize: Option<u64>,
+    blockhash: Option<String>,
+    feerate_percentiles: Option<[u64; 5]>,
+    height: Option<u64>,
+    ins: Option<u64>,
+    maxfee: Option<u64>,
+    maxfeerate: Option<u64>,
+    maxtxsize: Option<u64>,
+    medianfee: Option<u64>,
+    mediantime: Option<u64>,
+    mediantxsize: Option<u64>,
+    minfee: Option<u64>,
+    minfeerate: Option<u64>,
+    mintxsize: Option<u64>,
+    outs: Option<u64>,
+    subsidy: Option<u64>,
+    swtotal_size: Option<u64>,
+    swtotal_weight: Option<u64>,
+    swtxs: Option<u64>,
+    time: Option<u64>,
+    total_out: Option<u64>,
+    total_size: Option<u64>,
+    total_weight: Option<u64>,
+    totalfee: Option<u64>,
+    txs: Option<u64>,
+    utxo_increase: Option<i64>,
+    utxo_increase_actual: Option<i64>,
+    utxo_size_inc: Option<i64>,
+    utxo_size_inc_actual: Option<i64>,
+}
+
+macro_rules! selected_stat_accessors {
+    ($($field:ident: $type:ty => $stat:ident,)*) => {
+        impl GetBlockStatsCommandWithSelectiveStatsResponse {
+            $(
+                pub fn $field(&self) -> Result<$type, StatNotSelected> {
+                    self.$field
+                        .clone()
+                        .ok_or(StatNotSelected(StatsArgumentChoices::$stat))
+                }
+            )*
+        }
+    };
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-#[serde(untagged)]
-pub enum GetBlockStatsCommandResponse {
-    SelectiveStats(GetBlockStatsCommandWithSelectiveStatsResponse),
-    AllStats(GetBlockStatsCommandWithAllStatsResponse),
+selected_stat_accessors! {
+    avgfee: u64 => AvgFee,
+    avgfeerate: u64 => AvgFeeRate,
+    avgtxsize: u64 => AvgTxSize,
+    blockhash: String => Blockhash,
+    feerate_percentiles: [u64; 5] => FeeRatePercentiles,
+    height: u64 => Height,
+    ins: u64 => Ins,
+    maxfee: u64 => MaxFee,
+    maxfeerate: u64 => MaxFeeRate,
+    maxtxsize: u64 => MaxTxSize,
+    medianfee: u64 => MedianFee,
+    mediantime: u64 => MedianTime,
+    mediantxsize: u64 => MedianTxSize,
+    minfee: u64 => MinFee,
+    minfeerate: u64 => MinFeeRate,
+    mintxsize: u64 => MinTxSize,
+    outs: u64 => Outs,
+    subsidy: u64 => Subsidy,
+    swtotal_size: u64 => SwTotalSize,
+    swtotal_weight: u64 => SwTotalWeight,
+    swtxs: u64 => SwTxs,
+    time: u64 => Time,
+    total_out: u64 => TotalOut,
+    total_size: u64 => TotalSize,
+    total_weight: u64 => TotalWeight,
+    totalfee: u64 => TotalFee,
+    txs: u64 => Txs,
+    utxo_increase: i64 => UtxoIncrease,
+    utxo_increase_actual: i64 => UtxoIncreaseActual,
+    utxo_size_inc: i64 => UtxoSizeInc,
+    utxo_size_inc_actual: i64 => UtxoSizeIncActual,
+}
+
+fn hash_or_height_arg(target_block: &TargetBlockArgument) -> Box<serde_json::value::RawValue> {
+    match target_block {
+        TargetBlockArgument::Hash(hash) => to_raw_value(&hash).unwrap(),
+        TargetBlockArgument::Height(height) => to_raw_value(&height).unwrap(),
+    }
 }
 
 impl CallableCommand for GetBlockStatsCommand {
-    type Response = GetBlockStatsCommandResponse;
+    type Response = GetBlockStatsCommandWithAllStatsResponse;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
-        let target_block = &self.target_block;
-        let hash_or_height_arg_raw_value = match target_block {
-            TargetBlockArgument::Hash(hash) => to_raw_value(&hash).unwrap(),
-            TargetBlockArgument::Height(height) => to_raw_value(&height).unwrap(),
-        };
+        let params = vec![hash_or_height_arg(&self.target_block)];
+        let r = request(client, GET_BLOCK_STATS_COMMAND, params)?;
+        let response: GetBlockStatsCommandWithAllStatsResponse = r.result()?;
+        Ok(response)
+    }
+}
 
-        // TODO: Add stats param!
-        let stats_arg: Vec<String> = self.stats.iter().map(|stat| stat.to_string()).collect();
-        let stats_arg_raw_value = to_raw_value(&stats_arg).unwrap();
-        let command = "getblockstats";
-        let params = vec![hash_or_height_arg_raw_value, stats_arg_raw_value];
-        let r = request(client, command, params);
-        let response: GetBlockStatsCommandResponse = if stats_arg.is_empty() {
-            GetBlockStatsCommandResponse::AllStats(r.result()?)
-        } else {
-            GetBlockStatsCommandResponse::SelectiveStats(r.result()?)
-        };
+impl CallableCommand for GetSelectedBlockStatsCommand {
+    type Response = GetBlockStatsCommandWithSelectiveStatsResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let stats_arg_raw_value = to_raw_value(&self.stats).unwrap();
+        let params = vec![hash_or_height_arg(&self.target_block), stats_arg_raw_value];
+        let r = request(client, GET_BLOCK_STATS_COMMAND, params)?;
+        let response: GetBlockStatsCommandWithSelectiveStatsResponse = r.result()?;
         Ok(response)
     }
 }
@@ -0,0 +1,345 @@
+/*
+getblocktemplate ( "template_request" )
+
+If the request parameters include a 'mode' key, that is used to explicitly select between the default 'template' request or a 'proposal'.
+It returns data needed to construct a block to work on.
+For full specification, see BIPs 22, 23, 9, and 145:
+    https://github.com/bitcoin/bips/blob/master/bip-0022.mediawiki
+    https://github.com/bitcoin/bips/blob/master/bip-0023.mediawiki
+    https://github.com/bitcoin/bips/blob/master/bip-0009.mediawiki#getblocktemplate_changes
+    https://github.com/bitcoin/bips/blob/master/bip-0145.mediawiki
+
+Arguments:
+1. template_request         (json object, optional) Format of the template
+     {
+       "mode": "str",       (string, optional) This must be set to "template", "proposal" (see BIP 23), or omitted
+       "capabilities": [    (json array, optional) A list of strings
+         "str",             (string) client side supported feature, 'longpoll', 'coinbasevalue', 'proposal', 'serverlist', 'workid'
+         ...
+       ],
+       "rules": [           (json array, required) A list of strings
+         "segwit",          (string, required) (literal) indicates client side segwit support
+         "str",             (string) other client side supported softfork deployment
+         ...
+       ],
+       "longpollid": "str", (string, optional) delay processing request until the result would vary significantly from the "longpollid" of a prior template
+       "data": "hex",       (string, optional) proposed block data to check, encoded in hexadecimal; valid only for mode="proposal"
+     }
+
+Result (If the proposal was accepted with mode=='proposal'):
+null    (json null)
+
+Result (If the proposal was not accepted with mode=='proposal'):
+"str"    (string) According to BIP22
+
+Result (Otherwise):
+{                                          (json object)
+  "version" : n,                           (numeric) The preferred block version
+  "rules" : [                              (json array) specific block rules that are to be enforced
+    "str",                                 (string) name of a rule the client must understand to some extent; see BIP 9 for format
+    ...
+  ],
+  "vbavailable" : {                        (json object) set of pending, supported versionbit (BIP 9) softfork deployments
+    "rulename" : n,                        (numeric) identifies the bit number as indicating acceptance and readiness for the named softfork rule
+    ...
+  },
+  "capabilities" : [                       (json array)
+    "str",                                 (string) A supported feature, for example 'proposal'
+    ...
+  ],
+  "vbrequired" : n,                        (numeric) bit mask of versionbits the server requires set in submissions
+  "previousblockhash" : "str",             (string) The hash of current highest block
+  "transactions" : [                       (json array) contents of non-coinbase transactions that should be included in the next block
+    {                                      (json object)
+      "data" : "hex",                      (string) transaction data encoded in hexadecimal (byte-for-byte)
+      "txid" : "hex",                      (string) transaction id encoded in little-endian hexadecimal
+      "hash" : "hex",                      (string) hash encoded in little-endian hexadecimal (including witness data)
+      "depends" : [                        (json array) array of numbers
+        n,                                 (numeric) transactions before this one (by 1-based index in 'transactions' list) that must be present in the final block if this one is
+        ...
+      ],
+      "fee" : n,                           (numeric) difference in value between transaction inputs and outputs (in satoshis); for coinbase transactions, this is a negative Number of the total collected block fees (ie, not including the block subsidy); if key is not present, fee is unknown and clients MUST NOT assume there isn't one
+      "sigops" : n,                        (numeric) total SigOps cost, as counted for purposes of block limits; if key is not present, sigop cost is unknown and clients MUST NOT assume it is zero
+      "weight" : n                         (numeric) total transaction weight, as counted for purposes of block limits
+    },
+    ...
+  ],
+  "coinbaseaux" : {                        (json object) data that should be included in the coinbase's scriptSig content
+    "key" : "hex",                         (string) values must be in the coinbase (keys may be ignored)
+    ...
+  },
+  "coinbasevalue" : n,                     (numeric) maximum allowable input to coinbase transaction, including the generation award and transaction fees (in satoshis)
+  "longpollid" : "str",                    (string) an id to include with a request to longpoll on an update to this template
+  "target" : "str",                        (string) The hash target
+  "mintime" : xxx,                         (numeric) The minimum timestamp appropriate for the next block time, expressed in UNIX epoch time
+  "mutable" : [                            (json array) list of ways the block template may be changed
+    "str",                                 (string) A way the block template may be changed, e.g. 'time', 'transactions', 'prevblock'
+    ...
+  ],
+  "noncerange" : "hex",                    (string) A range of valid nonces
+  "sigoplimit" : n,                        (numeric) limit of sigops in blocks
+  "sizelimit" : n,                         (numeric) limit of block size
+  "weightlimit" : n,                       (numeric, optional) limit of block weight
+  "curtime" : xxx,                         (numeric) current timestamp in UNIX epoch time
+  "bits" : "str",                          (string) compressed target of next block
+  "height" : n,                            (numeric) The height of the next block
+  "signet_challenge" : "hex",              (string, optional) Only on signet
+  "default_witness_commitment" : "str"     (string, optional) a valid witness commitment for the unmodified block template
+}
+
+Examples:
+> bitcoin-cli getblocktemplate '{"rules": ["segwit"]}'
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getblocktemplate", "params": [{"rules": ["segwit"]}]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::to_raw_value;
+use std::collections::HashMap;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::submit_block::BlockRejectionReason;
+use crate::command::CallableCommand;
+use crate::types::transaction::Txid;
+use crate::types::work::{Bits, Target};
+use crate::Blockhash;
+
+const GET_BLOCK_TEMPLATE_COMMAND: &str = "getblocktemplate";
+
+// A softfork rule the client supports. bitcoind refuses template requests without "segwit", and
+// without "signet" on signet.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TemplateRule {
+    Segwit,
+    Signet,
+    Csv,
+    Taproot,
+    Other(String),
+}
+
+impl TemplateRule {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TemplateRule::Segwit => "segwit",
+            TemplateRule::Signet => "signet",
+            TemplateRule::Csv => "csv",
+            TemplateRule::Taproot => "taproot",
+            TemplateRule::Other(rule) => rule,
+        }
+    }
+}
+
+impl From<&str> for TemplateRule {
+    fn from(rule: &str) -> Self {
+        // A leading "!" marks a rule the client must understand to use the template.
+        match rule.trim_start_matches('!') {
+            "segwit" => TemplateRule::Segwit,
+            "signet" => TemplateRule::Signet,
+            "csv" => TemplateRule::Csv,
+            "taproot" => TemplateRule::Taproot,
+            _ => TemplateRule::Other(rule.to_string()),
+        }
+    }
+}
+
+impl Serialize for TemplateRule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TemplateRule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let rule = String::deserialize(deserializer)?;
+        Ok(TemplateRule::from(rule.as_str()))
+    }
+}
+
+// A client side supported feature (BIP22/BIP23).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateCapability {
+    Longpoll,
+    CoinbaseTxn,
+    CoinbaseValue,
+    Proposal,
+    ServerList,
+    WorkId,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum TemplateRequestMode {
+    Template,
+    Proposal,
+}
+
+#[derive(Serialize, Debug)]
+struct TemplateRequest<'a> {
+    mode: TemplateRequestMode,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    capabilities: &'a [TemplateCapability],
+    rules: &'a [TemplateRule],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    longpollid: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<&'a str>,
+}
+
+pub struct GetBlockTemplateCommand {
+    rules: Vec<TemplateRule>, // (json array, required) client side supported softfork deployments
+    capabilities: Vec<TemplateCapability>, // (json array, optional) client side supported features
+    longpollid: Option<String>, // (string, optional) delay processing request until the result would vary significantly from the "longpollid" of a prior template
+    proposal: Option<String>, // (string, optional) proposed block data to check, encoded in hexadecimal; sets mode="proposal"
+}
+impl GetBlockTemplateCommand {
+    pub fn new() -> Self {
+        GetBlockTemplateCommand {
+            rules: vec![TemplateRule::Segwit],
+            capabilities: vec![],
+            longpollid: None,
+            proposal: None,
+        }
+    }
+    pub fn set_rules(mut self, rules: Vec<TemplateRule>) -> Self {
+        self.rules = rules;
+        self
+    }
+    pub fn set_capabilities(mut self, capabilities: Vec<TemplateCapability>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+    // Waits until the template would change from the one with this longpollid. The call can
+    // block for a long time, so the client's timeout has to allow for it.
+    pub fn set_longpollid(mut self, longpollid: String) -> Self {
+        self.longpollid = Some(longpollid);
+        self
+    }
+    // Checks a block without submitting it (BIP23 mode=proposal).
+    pub fn set_proposal(mut self, hexdata: String) -> Self {
+        self.proposal = Some(hexdata);
+        self
+    }
+}
+impl Default for GetBlockTemplateCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TemplateTransaction {
+    pub data: String,        // transaction data encoded in hexadecimal (byte-for-byte)
+    pub txid: Txid,          // transaction id encoded in little-endian hexadecimal
+    pub hash: String,        // hash encoded in little-endian hexadecimal (including witness data)
+    pub depends: Vec<u64>, // transactions before this one (by 1-based index in 'transactions' list) that must be present in the final block if this one is
+    pub fee: Option<i64>, // difference in value between transaction inputs and outputs (in satoshis); unknown if not present
+    pub sigops: Option<u64>, // total SigOps cost, as counted for purposes of block limits; unknown if not present
+    pub weight: u64,         // total transaction weight, as counted for purposes of block limits
+}
+
+// A way the block template may be changed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateMutation {
+    Time,
+    Transactions,
+    #[serde(rename = "prevblock")]
+    PrevBlock,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockTemplate {
+    pub version: i32,                               // The preferred block version
+    pub rules: Vec<TemplateRule>,                   // specific block rules that are to be enforced
+    pub vbavailable: HashMap<String, u8>, // set of pending, supported versionbit (BIP 9) softfork deployments
+    pub capabilities: Vec<String>,        // A supported feature, for example 'proposal'
+    pub vbrequired: u32, // bit mask of versionbits the server requires set in submissions
+    pub previousblockhash: Blockhash, // The hash of current highest block
+    pub transactions: Vec<TemplateTransaction>, // contents of non-coinbase transactions that should be included in the next block
+    pub coinbaseaux: HashMap<String, String>, // data that should be included in the coinbase's scriptSig content
+    pub coinbasevalue: u64, // maximum allowable input to coinbase transaction, including the generation award and transaction fees (in satoshis)
+    pub longpollid: String, // an id to include with a request to longpoll on an update to this template
+    pub target: Target,     // The hash target
+    pub mintime: u64, // The minimum timestamp appropriate for the next block time, expressed in UNIX epoch time
+    pub mutable: Vec<TemplateMutation>, // list of ways the block template may be changed
+    pub noncerange: String, // A range of valid nonces
+    pub sigoplimit: u64, // limit of sigops in blocks
+    pub sizelimit: u64, // limit of block size
+    pub weightlimit: Option<u64>, // limit of block weight
+    pub curtime: u64, // current timestamp in UNIX epoch time
+    pub bits: Bits,   // compressed target of next block
+    pub height: u64,  // The height of the next block
+    pub signet_challenge: Option<String>, // Only on signet
+    pub default_witness_commitment: Option<String>, // a valid witness commitment for the unmodified block template
+}
+
+impl BlockTemplate {
+    // The total fees of the template's transactions, or None if any fee is unknown.
+    pub fn total_fees(&self) -> Option<i64> {
+        self.transactions
+            .iter()
+            .map(|transaction| transaction.fee)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum GetBlockTemplateCommandResponse {
+    Template(Box<BlockTemplate>),
+    ProposalAccepted,                       // mode=proposal and the block is valid
+    ProposalRejected(BlockRejectionReason), // mode=proposal and the block is invalid
+}
+
+// Serialized the way the node returns it.
+impl Serialize for GetBlockTemplateCommandResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            GetBlockTemplateCommandResponse::Template(template) => template.serialize(serializer),
+            GetBlockTemplateCommandResponse::ProposalAccepted => serializer.serialize_none(),
+            GetBlockTemplateCommandResponse::ProposalRejected(reason) => {
+                reason.serialize(serializer)
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GetBlockTemplateCommandResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Template(Box<BlockTemplate>),
+            Rejected(BlockRejectionReason),
+            Accepted(()),
+        }
+        let response = match Raw::deserialize(deserializer)? {
+            Raw::Template(template) => GetBlockTemplateCommandResponse::Template(template),
+            Raw::Rejected(reason) => GetBlockTemplateCommandResponse::ProposalRejected(reason),
+            Raw::Accepted(()) => GetBlockTemplateCommandResponse::ProposalAccepted,
+        };
+        Ok(response)
+    }
+}
+
+impl CallableCommand for GetBlockTemplateCommand {
+    type Response = GetBlockTemplateCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let template_request = TemplateRequest {
+            mode: match self.proposal {
+                Some(_) => TemplateRequestMode::Proposal,
+                None => TemplateRequestMode::Template,
+            },
+            capabilities: &self.capabilities,
+            rules: &self.rules,
+            longpollid: self.longpollid.as_deref(),
+            data: self.proposal.as_deref(),
+        };
+        let template_request_arg_raw_value = to_raw_value(&template_request).unwrap();
+        let params = vec![template_request_arg_raw_value];
+        let r = request(client, GET_BLOCK_TEMPLATE_COMMAND, params)?;
+        let response: GetBlockTemplateCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
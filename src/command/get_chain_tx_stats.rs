@@ -27,7 +27,6 @@ Examples:
 use crate::command::request::request;
 use crate::command::CallableCommand;
 use crate::Blockhash;
-use crate::BlockhashHexEncoded;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::value::{to_raw_value, RawValue};
@@ -52,6 +51,11 @@ impl GetChainTxStatsCommand {
         self
     }
 }
+impl Default for GetChainTxStatsCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetChainTxStatsCommandResponse {
     pub time: u64, // The timestamp for the final block in the window, expressed in UNIX epoch time
@@ -78,7 +82,7 @@ impl CallableCommand for GetChainTxStatsCommand {
             let blockhash_arg_raw_value = to_raw_value(&blockhash_str).unwrap();
             params.push(blockhash_arg_raw_value)
         }
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetChainTxStatsCommandResponse = r.result()?;
         Ok(response)
     }
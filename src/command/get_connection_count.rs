@@ -21,6 +21,11 @@ impl GetConnectionCountCommand {
         GetConnectionCountCommand {}
     }
 }
+impl Default for GetConnectionCountCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetConnectionCountCommandResponse(pub u64);
@@ -30,7 +35,7 @@ impl CallableCommand for GetConnectionCountCommand {
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
         let command = "getconnectioncount";
         let params: Vec<Box<RawValue>> = vec![];
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetConnectionCountCommandResponse = r.result()?;
         Ok(response)
     }
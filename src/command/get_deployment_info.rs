@@ -0,0 +1,227 @@
+/*
+getdeploymentinfo ( "blockhash" )
+
+Returns an object containing various state info regarding deployments of consensus changes.
+
+Arguments:
+1. blockhash    (string, optional, default="hash of current chain tip") The block hash at which to query deployment state
+
+Result:
+{                                          (json object)
+  "hash" : "str",                          (string) requested block hash (or tip)
+  "height" : n,                            (numeric) requested block height (or tip)
+  "deployments" : {                        (json object)
+    "xxxx" : {                             (json object) name of the deployment
+      "type" : "str",                      (string) one of "buried", "bip9"
+      "height" : n,                        (numeric, optional) height of the first block which the rules are or will be enforced (only for "buried" type, or "bip9" type with "active" status)
+      "active" : true|false,               (boolean) true if the rules are enforced for the mempool and the next block
+      "bip9" : {                           (json object, optional) status of bip9 softforks (only for "bip9" type)
+        "bit" : n,                         (numeric, optional) the bit (0-28) in the block version field used to signal this softfork (only for "started" and "locked_in" status)
+        "start_time" : xxx,                (numeric) the minimum median time past of a block at which the bit gains its meaning
+        "timeout" : xxx,                   (numeric) the median time past of a block at which the deployment is considered failed if not yet locked in
+        "min_activation_height" : n,       (numeric) minimum height of blocks for which the rules may be enforced
+        "status" : "str",                  (string) status of deployment at specified block (one of "defined", "started", "locked_in", "active", "failed")
+        "since" : n,                       (numeric) height of the first block to which the status applies
+        "status_next" : "str",             (string) status of deployment at the next block
+        "statistics" : {                   (json object, optional) numeric statistics about signalling for a softfork (only for "started" and "locked_in" status)
+          "period" : n,                    (numeric) the length in blocks of the signalling period
+          "threshold" : n,                 (numeric, optional) the number of blocks with the version bit set required to activate the feature (only for "started" status)
+          "elapsed" : n,                   (numeric) the number of blocks elapsed since the beginning of the current period
+          "count" : n,                     (numeric) the number of blocks with the version bit set in the current period
+          "possible" : true|false          (boolean, optional) returns false if there are not enough blocks left in this period to pass activation threshold (only for "started" status)
+        },
+        "signalling" : "str"               (string, optional) indicates blocks that signalled with a # and blocks that did not with a -
+      }
+    }
+  }
+}
+
+Examples:
+> bitcoin-cli getdeploymentinfo
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getdeploymentinfo", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+use std::collections::HashMap;
+
+use crate::client::Client;
+use crate::command::get_blockchain_info::GetBlockchainInfoCommand;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::version::{require_version, NodeVersion};
+use crate::Blockhash;
+
+const GET_DEPLOYMENT_INFO_COMMAND: &str = "getdeploymentinfo";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeploymentStatus {
+    Defined,  // the start time hasn't been reached
+    Started,  // blocks are signalling
+    LockedIn, // enough blocks signalled, the rules are enforced from the next period
+    Active,   // the rules are enforced
+    Failed,   // the timeout was reached without locking in
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bip9Statistics {
+    pub period: u64,            // the length in blocks of the signalling period
+    pub threshold: Option<u64>, // the number of blocks with the version bit set required to activate the feature (only for "started" status)
+    pub elapsed: u64, // the number of blocks elapsed since the beginning of the current period
+    pub count: u64,   // the number of blocks with the version bit set in the current period
+    pub possible: Option<bool>, // returns false if there are not enough blocks left in this period to pass activation threshold (only for "started" status)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bip9 {
+    pub bit: Option<u8>, // the bit (0-28) in the block version field used to signal this softfork (only for "started" and "locked_in" status)
+    pub start_time: i64, // the minimum median time past of a block at which the bit gains its meaning. -1 for deployments that are always active
+    pub timeout: i64, // the median time past of a block at which the deployment is considered failed if not yet locked in
+    pub min_activation_height: Option<u64>, // minimum height of blocks for which the rules may be enforced (only returned by bitcoin core 0.21.1 and later)
+    pub status: DeploymentStatus,           // status of deployment at specified block
+    pub since: u64,                         // height of the first block to which the status applies
+    pub status_next: Option<DeploymentStatus>, // status of deployment at the next block (only returned by getdeploymentinfo)
+    pub statistics: Option<Bip9Statistics>, // numeric statistics about signalling for a softfork (only for "started" and "locked_in" status)
+    pub signalling: Option<String>, // indicates blocks that signalled with a # and blocks that did not with a - (only returned by getdeploymentinfo)
+}
+
+impl Bip9 {
+    // Whether each block of the current period so far signalled, in order.
+    pub fn signalling_blocks(&self) -> Option<Vec<bool>> {
+        let signalling = self.signalling.as_ref()?;
+        Some(signalling.chars().map(|block| block == '#').collect())
+    }
+    // The height the rules are enforced from once locked in: the start of the next period, but
+    // no earlier than min_activation_height.
+    fn locked_in_activation_height(&self) -> Option<u64> {
+        let period = self.statistics.as_ref()?.period;
+        let next_period = self.since + period;
+        Some(next_period.max(self.min_activation_height.unwrap_or(0)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Deployment {
+    // Activated at a fixed height hardcoded into the node.
+    Buried {
+        height: u64,  // height of the first block which the rules are enforced
+        active: bool, // true if the rules are enforced for the mempool and the next block
+    },
+    Bip9 {
+        height: Option<u64>, // height of the first block which the rules are enforced (only for "active" status)
+        active: bool,        // true if the rules are enforced for the mempool and the next block
+        bip9: Bip9,          // status of bip9 softforks
+    },
+}
+
+impl Deployment {
+    pub fn is_active(&self) -> bool {
+        match self {
+            Deployment::Buried { active, .. } => *active,
+            Deployment::Bip9 { active, .. } => *active,
+        }
+    }
+    // The height the rules are or will be enforced from, if known.
+    pub fn activation_height(&self) -> Option<u64> {
+        match self {
+            Deployment::Buried { height, .. } => Some(*height),
+            Deployment::Bip9 {
+                height: Some(height),
+                ..
+            } => Some(*height),
+            Deployment::Bip9 { bip9, .. } if bip9.status == DeploymentStatus::LockedIn => {
+                bip9.locked_in_activation_height()
+            }
+            Deployment::Bip9 { .. } => None,
+        }
+    }
+    // Whether the rules are enforced for the block at `height`, given the deployment's state as
+    // of the block at `reference_height`. None if that can't be known yet, e.g. for a height past
+    // the reference block of a deployment that is still signalling.
+    pub fn is_active_at(&self, height: u64, reference_height: u64) -> Option<bool> {
+        if let Some(activation_height) = self.activation_height() {
+            return Some(height >= activation_height);
+        }
+        match self {
+            Deployment::Bip9 { bip9, .. } if bip9.status == DeploymentStatus::Failed => Some(false),
+            _ if height <= reference_height => Some(false),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetDeploymentInfoCommandResponse {
+    pub hash: Blockhash,                          // requested block hash (or tip)
+    pub height: u64,                              // requested block height (or tip)
+    pub deployments: HashMap<String, Deployment>, // keyed by deployment name, e.g. "segwit" or "taproot"
+}
+
+impl GetDeploymentInfoCommandResponse {
+    // See Deployment::is_active_at. None for unknown deployments.
+    pub fn is_active_at(&self, deployment: &str, height: u64) -> Option<bool> {
+        self.deployments
+            .get(deployment)?
+            .is_active_at(height, self.height)
+    }
+}
+
+pub struct GetDeploymentInfoCommand {
+    blockhash: Option<Blockhash>, // (string, optional, default="hash of current chain tip") The block hash at which to query deployment state
+}
+impl GetDeploymentInfoCommand {
+    pub fn new() -> Self {
+        GetDeploymentInfoCommand { blockhash: None }
+    }
+    pub fn set_blockhash(mut self, blockhash: Blockhash) -> Self {
+        self.blockhash = Some(blockhash);
+        self
+    }
+}
+impl Default for GetDeploymentInfoCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for GetDeploymentInfoCommand {
+    type Response = GetDeploymentInfoCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        require_version(client, GET_DEPLOYMENT_INFO_COMMAND, NodeVersion::V23)?;
+        let mut params = vec![];
+        if let Some(blockhash) = &self.blockhash {
+            let blockhash_arg_raw_value = to_raw_value(&blockhash.0).unwrap();
+            params.push(blockhash_arg_raw_value);
+        }
+        let r = request(client, GET_DEPLOYMENT_INFO_COMMAND, params)?;
+        let response: GetDeploymentInfoCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
+
+// The deployments as of the chain tip on any version of bitcoin core: from getdeploymentinfo, or
+// from the softforks of getblockchaininfo on nodes older than v23.
+pub fn get_deployments(
+    client: &Client,
+) -> Result<GetDeploymentInfoCommandResponse, jsonrpc::Error> {
+    if client.node_version()? >= NodeVersion::V23 {
+        return GetDeploymentInfoCommand::new().call(client);
+    }
+    let blockchain_info = GetBlockchainInfoCommand::new().call(client)?;
+    Ok(GetDeploymentInfoCommandResponse {
+        hash: Blockhash(blockchain_info.bestblockhash),
+        height: blockchain_info.blocks,
+        deployments: blockchain_info.softforks.unwrap_or_default(),
+    })
+}
+
+// Whether the rules of a deployment (e.g. "taproot") are enforced at `height`, on any version of
+// bitcoin core. None if the node doesn't know the deployment or it isn't decided yet.
+pub fn is_deployment_active_at(
+    client: &Client,
+    deployment: &str,
+    height: u64,
+) -> Result<Option<bool>, jsonrpc::Error> {
+    Ok(get_deployments(client)?.is_active_at(deployment, height))
+}
@@ -0,0 +1,64 @@
+/*
+getdescriptorinfo "descriptor"
+
+Analyses a descriptor.
+
+Arguments:
+1. descriptor    (string, required) The descriptor.
+
+Result:
+{                                   (json object)
+  "descriptor" : "str",             (string) The descriptor in canonical form, without private keys. For a multipath descriptor, only the first will be returned.
+  "multipath_expansion" : [         (json array, optional) All descriptors produced by expanding multipath derivation elements. Only if the provided descriptor specifies multipath derivation elements.
+    "str",                          (string)
+    ...
+  ],
+  "checksum" : "str",               (string) The checksum for the input descriptor
+  "isrange" : true|false,           (boolean) Whether the descriptor is ranged
+  "issolvable" : true|false,        (boolean) Whether the descriptor is solvable
+  "hasprivatekeys" : true|false     (boolean) Whether the input descriptor contained at least one private key
+}
+
+Examples:
+Analyse a descriptor
+> bitcoin-cli getdescriptorinfo "wpkh([d34db33f/84h/0h/0h]0279be667ef9dcbbac55a06295Ce870b07029Bfcdb2dce28d959f2815b16f81798)"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getdescriptorinfo", "params": ["wpkh([d34db33f/84h/0h/0h]0279be667ef9dcbbac55a06295Ce870b07029Bfcdb2dce28d959f2815b16f81798)"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const GET_DESCRIPTOR_INFO_COMMAND: &str = "getdescriptorinfo";
+
+pub struct GetDescriptorInfoCommand {
+    descriptor: String, // (string, required) The descriptor.
+}
+impl GetDescriptorInfoCommand {
+    pub fn new(descriptor: String) -> Self {
+        GetDescriptorInfoCommand { descriptor }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetDescriptorInfoCommandResponse {
+    pub descriptor: String, // The descriptor in canonical form, without private keys and with its checksum. For a multipath descriptor, only the first will be returned.
+    pub multipath_expansion: Option<Vec<String>>, // All descriptors produced by expanding multipath derivation elements (only returned by newer versions of bitcoin core)
+    pub checksum: String, // The checksum for the input descriptor, which differs from the canonical descriptor's if the input had private keys
+    pub isrange: bool,    // Whether the descriptor is ranged
+    pub issolvable: bool, // Whether the descriptor is solvable
+    pub hasprivatekeys: bool, // Whether the input descriptor contained at least one private key
+}
+
+impl CallableCommand for GetDescriptorInfoCommand {
+    type Response = GetDescriptorInfoCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let descriptor_arg_raw_value = to_raw_value(&self.descriptor).unwrap();
+        let params = vec![descriptor_arg_raw_value];
+        let r = request(client, GET_DESCRIPTOR_INFO_COMMAND, params)?;
+        let response: GetDescriptorInfoCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -22,6 +22,11 @@ impl GetDifficultyCommand {
         GetDifficultyCommand {}
     }
 }
+impl Default for GetDifficultyCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 // TODO: struct GetDifficultyCommandResponse(String);
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetDifficultyCommandResponse(pub f64);
@@ -30,7 +35,7 @@ impl CallableCommand for GetDifficultyCommand {
     type Response = GetDifficultyCommandResponse;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
         let params = vec![];
-        let r = request(client, GET_DIFFICULTY_COMMAND, params);
+        let r = request(client, GET_DIFFICULTY_COMMAND, params)?;
         let response: GetDifficultyCommandResponse = r.result()?;
         Ok(response)
     }
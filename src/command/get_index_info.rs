@@ -0,0 +1,135 @@
+/*
+getindexinfo ( "index_name" )
+
+Returns the status of one or all available indices currently running in the node.
+
+Arguments:
+1. index_name    (string, optional) Filter results for an index with a specific name.
+
+Result:
+{                               (json object)
+  "name" : {                    (json object) The name of the index
+    "synced" : true|false,      (boolean) Whether the index is synced or not
+    "best_block_height" : n     (numeric) The block height to which the index is synced
+  }
+}
+
+Examples:
+> bitcoin-cli getindexinfo
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getindexinfo", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+> bitcoin-cli getindexinfo txindex
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getindexinfo", "params": [txindex]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::to_raw_value;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::version::{require_version, NodeVersion};
+
+const GET_INDEX_INFO_COMMAND: &str = "getindexinfo";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IndexName {
+    TxIndex,               // "txindex", enabled with -txindex
+    CoinStatsIndex,        // "coinstatsindex", enabled with -coinstatsindex
+    BasicBlockFilterIndex, // "basic block filter index", enabled with -blockfilterindex
+    Other(String),         // Indexes added by newer versions of bitcoin core
+}
+
+impl IndexName {
+    pub fn as_str(&self) -> &str {
+        match self {
+            IndexName::TxIndex => "txindex",
+            IndexName::CoinStatsIndex => "coinstatsindex",
+            IndexName::BasicBlockFilterIndex => "basic block filter index",
+            IndexName::Other(name) => name,
+        }
+    }
+}
+
+impl From<&str> for IndexName {
+    fn from(name: &str) -> Self {
+        match name {
+            "txindex" => IndexName::TxIndex,
+            "coinstatsindex" => IndexName::CoinStatsIndex,
+            "basic block filter index" => IndexName::BasicBlockFilterIndex,
+            name => IndexName::Other(name.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for IndexName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for IndexName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for IndexName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(IndexName::from(name.as_str()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct IndexInfo {
+    pub synced: bool,           // Whether the index is synced or not
+    pub best_block_height: u64, // The block height to which the index is synced
+}
+
+// Only indexes that are enabled are included.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetIndexInfoCommandResponse(pub HashMap<IndexName, IndexInfo>);
+
+impl GetIndexInfoCommandResponse {
+    pub fn get(&self, index: &IndexName) -> Option<&IndexInfo> {
+        self.0.get(index)
+    }
+    // Whether the index is enabled and has caught up with the chain.
+    pub fn is_synced(&self, index: &IndexName) -> bool {
+        self.get(index).map(|info| info.synced).unwrap_or(false)
+    }
+}
+
+pub struct GetIndexInfoCommand {
+    index_name: Option<IndexName>, // (string, optional) Filter results for an index with a specific name.
+}
+impl GetIndexInfoCommand {
+    pub fn new() -> Self {
+        GetIndexInfoCommand { index_name: None }
+    }
+    pub fn set_index_name(mut self, index_name: IndexName) -> Self {
+        self.index_name = Some(index_name);
+        self
+    }
+}
+impl Default for GetIndexInfoCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for GetIndexInfoCommand {
+    type Response = GetIndexInfoCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        require_version(client, GET_INDEX_INFO_COMMAND, NodeVersion::V0_21)?;
+        let mut params = vec![];
+        if let Some(index_name) = &self.index_name {
+            let index_name_arg_raw_value = to_raw_value(index_name).unwrap();
+            params.push(index_name_arg_raw_value);
+        }
+        let r = request(client, GET_INDEX_INFO_COMMAND, params)?;
+        let response: GetIndexInfoCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,99 @@
+/*
+getmemoryinfo ( "mode" )
+
+Returns an object containing information about memory usage.
+
+Arguments:
+1. mode    (string, optional, default="stats") determines what kind of information is returned.
+           - "stats" returns general statistics about memory usage in the daemon.
+           - "mallocinfo" returns an XML string describing low-level heap state (only available if compiled with glibc).
+
+Result (mode "stats"):
+{                         (json object)
+  "locked" : {            (json object) Information about locked memory manager
+    "used" : n,           (numeric) Number of bytes used
+    "free" : n,           (numeric) Number of bytes available in current arenas
+    "total" : n,          (numeric) Total number of bytes managed
+    "locked" : n,         (numeric) Amount of bytes that succeeded locking. If this number is smaller than total, locking pages failed at some point and key data could be swapped to disk.
+    "chunks_used" : n,    (numeric) Number allocated chunks
+    "chunks_free" : n     (numeric) Number unused chunks
+  }
+}
+
+Result (mode "mallocinfo"):
+"str"    (string) "<malloc version="1">..."
+
+Examples:
+> bitcoin-cli getmemoryinfo
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getmemoryinfo", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const GET_MEMORY_INFO_COMMAND: &str = "getmemoryinfo";
+
+pub enum GetMemoryInfoCommandMode {
+    Stats,      // general statistics about memory usage in the daemon
+    MallocInfo, // an XML string describing low-level heap state (only available if compiled with glibc)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LockedMemoryInfo {
+    pub used: u64,        // Number of bytes used
+    pub free: u64,        // Number of bytes available in current arenas
+    pub total: u64,       // Total number of bytes managed
+    pub locked: u64, // Amount of bytes that succeeded locking. If this number is smaller than total, locking pages failed at some point and key data could be swapped to disk.
+    pub chunks_used: u64, // Number allocated chunks
+    pub chunks_free: u64, // Number unused chunks
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MemoryStats {
+    pub locked: LockedMemoryInfo, // Information about locked memory manager
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum GetMemoryInfoCommandResponse {
+    Stats(MemoryStats),
+    MallocInfo(String), // "<malloc version="1">..."
+}
+
+pub struct GetMemoryInfoCommand {
+    mode: GetMemoryInfoCommandMode,
+}
+impl GetMemoryInfoCommand {
+    pub fn new() -> Self {
+        GetMemoryInfoCommand {
+            mode: GetMemoryInfoCommandMode::Stats,
+        }
+    }
+    pub fn set_mode(mut self, mode: GetMemoryInfoCommandMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+impl Default for GetMemoryInfoCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for GetMemoryInfoCommand {
+    type Response = GetMemoryInfoCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let mode_arg = match self.mode {
+            GetMemoryInfoCommandMode::Stats => "stats",
+            GetMemoryInfoCommandMode::MallocInfo => "mallocinfo",
+        };
+        let mode_arg_raw_value = to_raw_value(mode_arg).unwrap();
+        let params = vec![mode_arg_raw_value];
+        let r = request(client, GET_MEMORY_INFO_COMMAND, params)?;
+        let response: GetMemoryInfoCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
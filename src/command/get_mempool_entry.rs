@@ -43,11 +43,11 @@ Examples:
 > bitcoin-cli getmempoolentry "mytxid"
 > curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getmempoolentry", "params": ["mytxid"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
 */
-use serde::{Deserialize, Serialize};
 use serde_json::value::to_raw_value;
 
 use crate::client::Client;
-use crate::{command::CallableCommand, Blockhash};
+use crate::command::CallableCommand;
+use crate::types::transaction::MempoolEntry;
 
 use crate::command::request::request;
 
@@ -61,49 +61,18 @@ impl GetMempoolEntryCommand {
         GetMempoolEntryCommand { txid }
     }
 }
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Fees {
-    pub base: f64,
-    pub modified: f64,
-    pub ancestor: f64,
-    pub descendant: f64,
-}
+pub use crate::types::transaction::MempoolEntryFees as Fees;
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct GetMempoolEntryCommandResponse {
-    pub vsize: u64,
-    pub weight: u64,
-    pub fee: f64,
-    pub modifiedfee: f64,
-    // TODO: represent using unix time
-    pub time: u64,
-    pub height: u64,
-    pub descendantcount: u64,
-    pub descendantsize: u64,
-    pub descendantfees: u64,
-    pub ancestorcount: u64,
-    pub ancestorsize: u64,
-    pub ancestorfees: u64,
-    // TODO: represent using hex
-    pub wtxid: String,
-    pub fees: Fees,
-    pub depends: Vec<String>,
-    pub spentby: Vec<String>,
-    #[serde(alias = "bip125-replaceable")]
-    pub bip125_replaceable: bool,
-    pub unbroadcast: bool,
-}
+pub type GetMempoolEntryCommandResponse = MempoolEntry;
 
 impl CallableCommand for GetMempoolEntryCommand {
     type Response = GetMempoolEntryCommandResponse;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
         let txid_arg = &self.txid;
-        // TODO: Add blockhas param!
-        //let blockhash_arg = &self.blockhash.0;
         let txid_arg_raw_value = to_raw_value(&txid_arg).unwrap();
         let command = "getmempoolentry";
         let params = vec![txid_arg_raw_value];
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetMempoolEntryCommandResponse = r.result()?;
         Ok(response)
     }
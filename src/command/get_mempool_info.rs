@@ -33,18 +33,23 @@ impl GetMempoolInfoCommand {
         GetMempoolInfoCommand {}
     }
 }
+impl Default for GetMempoolInfoCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct GetMempoolInfoCommandResponse {
-    loaded: bool,
-    size: u64,
-    bytes: u64,
-    usage: u64,
-    total_fee: f64,
-    maxmempool: u64,
-    mempoolminfee: f64,
-    minrelaytxfee: f64,
-    unbroadcastcount: u64,
+    pub loaded: bool,                  // True if the mempool is fully loaded
+    pub size: u64,                     // Current tx count
+    pub bytes: u64, // Sum of all virtual transaction sizes as defined in BIP 141. Differs from actual serialized size because witness data is discounted
+    pub usage: u64, // Total memory usage for the mempool
+    pub total_fee: Option<f64>, // Total fees for the mempool in BTC, ignoring modified fees through prioritizetransaction (not returned by older versions)
+    pub maxmempool: u64,        // Maximum memory usage for the mempool
+    pub mempoolminfee: f64, // Minimum fee rate in BTC/kvB for tx to be accepted. Is the maximum of minrelaytxfee and minimum mempool fee
+    pub minrelaytxfee: f64, // Current minimum relay fee for transactions
+    pub unbroadcastcount: Option<u64>, // Current number of transactions that haven't passed initial broadcast yet (added in bitcoin core 0.21)
 }
 
 impl CallableCommand for GetMempoolInfoCommand {
@@ -52,7 +57,7 @@ impl CallableCommand for GetMempoolInfoCommand {
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
         let command = "getmempoolinfo";
         let params: Vec<Box<RawValue>> = vec![];
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetMempoolInfoCommandResponse = r.result()?;
         Ok(response)
     }
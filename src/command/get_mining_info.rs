@@ -18,11 +18,10 @@ Examples:
 > bitcoin-cli getmininginfo
 > curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getmininginfo", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
 */
+use crate::command::get_network_info::Warnings;
 use crate::command::CallableCommand;
-use crate::Blockhash;
 use crate::{client::Client, command::request::request};
 use serde::{Deserialize, Serialize};
-use serde_json::value::{to_raw_value, RawValue};
 
 const GET_DIFFICULTY_COMMAND: &str = "getmininginfo";
 
@@ -36,19 +35,24 @@ pub struct GetMiningInfoCommandResponse {
     pub networkhashps: f64,          // The network hashes per second
     pub pooledtx: u64,               // The size of the mempool
     pub chain: String,               // current network name (main, test, regtest)
-    pub warnings: String,            // any network and blockchain warnings
+    pub warnings: Warnings,          // any network and blockchain warnings
 }
 impl GetMiningInfoCommand {
     pub fn new() -> Self {
         GetMiningInfoCommand {}
     }
 }
+impl Default for GetMiningInfoCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl CallableCommand for GetMiningInfoCommand {
     type Response = GetMiningInfoCommandResponse;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
         let params = vec![];
-        let r = request(client, GET_DIFFICULTY_COMMAND, params);
+        let r = request(client, GET_DIFFICULTY_COMMAND, params)?;
         let response: GetMiningInfoCommandResponse = r.result()?;
         Ok(response)
     }
@@ -0,0 +1,72 @@
+/*
+getnettotals
+
+Returns information about network traffic, including bytes in, bytes out,
+and current system time.
+
+Result:
+{                                              (json object)
+  "totalbytesrecv" : n,                        (numeric) Total bytes received
+  "totalbytessent" : n,                        (numeric) Total bytes sent
+  "timemillis" : xxx,                          (numeric) Current system UNIX epoch time in milliseconds
+  "uploadtarget" : {                           (json object)
+    "timeframe" : n,                           (numeric) Length of the measuring timeframe in seconds
+    "target" : n,                              (numeric) Target in bytes
+    "target_reached" : true|false,             (boolean) True if target is reached
+    "serve_historical_blocks" : true|false,    (boolean) True if serving historical blocks
+    "bytes_left_in_cycle" : n,                 (numeric) Bytes left in current time cycle
+    "time_left_in_cycle" : n                   (numeric) Seconds left in current time cycle
+  }
+}
+
+Examples:
+> bitcoin-cli getnettotals
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getnettotals", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const GET_NET_TOTALS_COMMAND: &str = "getnettotals";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UploadTarget {
+    pub timeframe: u64,                // Length of the measuring timeframe in seconds
+    pub target: u64,                   // Target in bytes (0 when -maxuploadtarget isn't set)
+    pub target_reached: bool,          // True if target is reached
+    pub serve_historical_blocks: bool, // True if serving historical blocks
+    pub bytes_left_in_cycle: u64,      // Bytes left in current time cycle
+    pub time_left_in_cycle: u64,       // Seconds left in current time cycle
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetNetTotalsCommandResponse {
+    pub totalbytesrecv: u64,        // Total bytes received
+    pub totalbytessent: u64,        // Total bytes sent
+    pub timemillis: u64,            // Current system UNIX epoch time in milliseconds
+    pub uploadtarget: UploadTarget, // Upload target (-maxuploadtarget) information
+}
+
+pub struct GetNetTotalsCommand {}
+impl GetNetTotalsCommand {
+    pub fn new() -> Self {
+        GetNetTotalsCommand {}
+    }
+}
+impl Default for GetNetTotalsCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for GetNetTotalsCommand {
+    type Response = GetNetTotalsCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let params = vec![];
+        let r = request(client, GET_NET_TOTALS_COMMAND, params)?;
+        let response: GetNetTotalsCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
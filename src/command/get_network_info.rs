@@ -58,6 +58,33 @@ impl GetNetworkInfoCommand {
         GetNetworkInfoCommand {}
     }
 }
+impl Default for GetNetworkInfoCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Any network and blockchain warnings. A single string, empty if there are none, up to bitcoin
+// core 27, and a list from 28 on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Warnings {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl Warnings {
+    pub fn messages(&self) -> Vec<&str> {
+        match self {
+            Warnings::Single(warning) if warning.is_empty() => vec![],
+            Warnings::Single(warning) => vec![warning.as_str()],
+            Warnings::Multiple(warnings) => warnings.iter().map(String::as_str).collect(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.messages().is_empty()
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Network {
@@ -79,18 +106,18 @@ pub struct GetNetworkInfoCommandResponse {
     pub subversion: String,
     pub protocolversion: u64,
     pub localservices: String,
-    pub localservicesnames: Vec<String>,
+    pub localservicesnames: Option<Vec<String>>, // added in bitcoin core 0.19
     pub localrelay: bool,
-    pub timeoffset: u64,
+    pub timeoffset: i64,
     pub connections: u64,
-    pub connections_in: u64,
-    pub connections_out: u64,
+    pub connections_in: Option<u64>,  // added in bitcoin core 0.21
+    pub connections_out: Option<u64>, // added in bitcoin core 0.21
     pub networkactive: bool,
     pub networks: Vec<Network>,
     pub relayfee: f64,
     pub incrementalfee: f64,
     pub localaddresses: Vec<LocalAddress>,
-    pub warnings: String,
+    pub warnings: Warnings,
 }
 
 impl CallableCommand for GetNetworkInfoCommand {
@@ -98,7 +125,7 @@ impl CallableCommand for GetNetworkInfoCommand {
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
         let command = "getnetworkinfo";
         let params: Vec<Box<RawValue>> = vec![];
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetNetworkInfoCommandResponse = r.result()?;
         Ok(response)
     }
@@ -22,6 +22,7 @@ Examples:
 > curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getnodeaddresses", "params": [8]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
  */
 use crate::command::CallableCommand;
+use crate::version::{require_version, NodeVersion};
 use crate::{client::Client, command::request::request};
 use serde::{Deserialize, Serialize};
 use serde_json::value::to_raw_value;
@@ -70,10 +71,22 @@ impl GetNodeAddressesCommand {
         self
     }
 }
+impl Default for GetNodeAddressesCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl CallableCommand for GetNodeAddressesCommand {
     type Response = GetNodeAddressesCommandResponse;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let command = "getnodeaddresses";
+        // The network argument was added in bitcoin core 22.
+        let minimum_version = match self.network {
+            NetworkArg::All => NodeVersion::V0_18,
+            _ => NodeVersion::V22,
+        };
+        require_version(client, command, minimum_version)?;
         let count_arg = match &self.count {
             CountArg::MaxAddresses(count) => count,
             CountArg::AllAddresses => &0,
@@ -94,8 +107,7 @@ impl CallableCommand for GetNodeAddressesCommand {
             }
             None => vec![count_arg_raw_value],
         };
-        let command = "getnodeaddresses";
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetNodeAddressesCommandResponse = r.result()?;
         Ok(response)
     }
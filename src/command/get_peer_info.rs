@@ -33,6 +33,8 @@ Result:
     "version" : n,                 (numeric) The peer version, such as 70001
     "subver" : "str",              (string) The string version
     "inbound" : true|false,        (boolean) Inbound (true) or Outbound (false)
+    "bip152_hb_to" : true|false,   (boolean) Whether we selected peer as (compact blocks) high-bandwidth peer
+    "bip152_hb_from" : true|false, (boolean) Whether peer selected us as (compact blocks) high-bandwidth peer
     "addnode" : true|false,        (boolean) Whether connection was due to addnode/-connect or if it was an automatic/inbound connection
                                    (DEPRECATED, returned only if the config option -deprecatedrpc=getpeerinfo_addnode is passed)
     "connection_type" : "str",     (string) Type of connection:
@@ -44,7 +46,13 @@ Result:
                                    feeler (short-lived automatic connection for testing addresses).
                                    Please note this output is unlikely to be stable in upcoming releases as we iterate to
                                    best capture connection behaviors.
+    "transport_protocol_type" : "str", (string) Type of transport protocol:
+                                   detecting (peer could be v1 or v2),
+                                   v1 (plaintext transport protocol),
+                                   v2 (BIP324 encrypted transport protocol).
+    "session_id" : "str",          (string) The session ID for this connection, or "" if there is none ("v2" transport protocol only).
     "startingheight" : n,          (numeric) The starting height (block) of the peer
+    "presynced_headers" : n,       (numeric) The current height of header pre-synchronization with this peer, or -1 if no low-work sync is in progress
     "banscore" : n,                (numeric) The ban score (DEPRECATED, returned only if config option -deprecatedrpc=banscore is passed)
     "synced_headers" : n,          (numeric) The last header we have in common with this peer
     "synced_blocks" : n,           (numeric) The last block we have in common with this peer
@@ -52,6 +60,9 @@ Result:
       n,                           (numeric) The heights of blocks we're currently asking from this peer
       ...
     ],
+    "addr_relay_enabled" : true|false, (boolean) Whether we participate in address relay with this peer
+    "addr_processed" : n,          (numeric) The total number of addresses processed, excluding those dropped due to rate limiting
+    "addr_rate_limited" : n,       (numeric) The total number of addresses dropped due to rate limiting
     "whitelisted" : true|false,    (boolean, optional) Whether the peer is whitelisted with default permissions
                                    (DEPRECATED, returned only if config option -deprecatedrpc=whitelisted is passed)
     "permissions" : [              (json array) Any special permissions that have been granted to this peer
@@ -65,7 +76,7 @@ Result:
 
       ...
     ],
-    "minfeefilter" : n,            (numeric) The minimum fee rate for transactions this peer accepts
+    "minfeefilter" : n,            (numeric) The minimum fee rate for transactions this peer accepts, in BTC/kvB
     "bytessent_per_msg" : {        (json object)
       "msg" : n,                   (numeric) The total bytes sent aggregated by message type
                                    When a message type is not listed in this json object, the bytes sent are 0.
@@ -88,77 +99,215 @@ Examples:
 */
 use crate::command::CallableCommand;
 use crate::{client::Client, command::request::request};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
+use std::fmt;
 
 const GET_PEER_INFO_COMMAND: &str = "getpeerinfo";
 
-#[derive(Serialize, Deserialize, Debug)]
+// The string enums below keep values added by newer versions of bitcoin core in Other.
+macro_rules! peer_string_enum {
+    ($name:ident { $($variant:ident => $value:literal,)* }) => {
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    value => $name::Other(value.to_string()),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                Ok($name::from(value.as_str()))
+            }
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ConnectionType {
+    OutboundFullRelay, // default automatic connections
+    BlockRelayOnly,    // does not relay transactions or addresses
+    Inbound,           // initiated by the peer
+    Manual,            // added via addnode RPC or -addnode/-connect configuration options
+    AddrFetch,         // short-lived automatic connection for soliciting addresses
+    Feeler,            // short-lived automatic connection for testing addresses
+    Other(String),
+}
+
+peer_string_enum!(ConnectionType {
+    OutboundFullRelay => "outbound-full-relay",
+    BlockRelayOnly => "block-relay-only",
+    Inbound => "inbound",
+    Manual => "manual",
+    AddrFetch => "addr-fetch",
+    Feeler => "feeler",
+});
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PeerNetwork {
+    Ipv4,
+    Ipv6,
+    Onion,
+    I2p,
+    Cjdns,
+    NotPubliclyRoutable, // e.g. local or private addresses
+    Other(String),
+}
+
+peer_string_enum!(PeerNetwork {
+    Ipv4 => "ipv4",
+    Ipv6 => "ipv6",
+    Onion => "onion",
+    I2p => "i2p",
+    Cjdns => "cjdns",
+    NotPubliclyRoutable => "not_publicly_routable",
+});
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PeerPermission {
+    BloomFilter, // allow requesting BIP37 filtered blocks and transactions
+    NoBan,       // do not ban for misbehavior; implies download
+    ForceRelay,  // relay transactions that are already in the mempool; implies relay
+    Relay,       // relay even in -blocksonly mode, and unlimited transaction announcements
+    Mempool,     // allow requesting BIP35 mempool contents
+    Download,    // allow getheaders during IBD, no disconnect after maxuploadtarget limit
+    Addr,        // responses to GETADDR avoid hitting the cache
+    Other(String),
+}
+
+peer_string_enum!(PeerPermission {
+    BloomFilter => "bloomfilter",
+    NoBan => "noban",
+    ForceRelay => "forcerelay",
+    Relay => "relay",
+    Mempool => "mempool",
+    Download => "download",
+    Addr => "addr",
+});
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TransportProtocol {
+    Detecting, // the peer could be v1 or v2
+    V1,        // plaintext
+    V2,        // BIP324 encrypted
+    Other(String),
+}
+
+peer_string_enum!(TransportProtocol {
+    Detecting => "detecting",
+    V1 => "v1",
+    V2 => "v2",
+});
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ConnectedNetworkNode {
-    id: u64,                    // Peer index
-    addr: String,               // (host:port) The IP address and port of the peer
-    addrbind: String,           // (ip:port) Bind address of the connection to the peer
-    addrlocal: Option<String>,  // (ip:port) Local address as reported by the peer
-    network: String,            // Network (ipv4, ipv6, or onion) the peer connected through
-    mapped_as: Option<u64>, // The AS in the BGP route to the peer used for diversifying peer selection (only available if the asmap config flag is set)
-    services: String,       // (hex) The services offered
-    servicesnames: Vec<String>, // (json array) the services offered, in human-readable form. Each element of the array is (string) the service name if it is recognised
-    relaytxes: bool,            // Whether peer has asked us to relay transactions to it
-    lastsend: u64,              // (unix) The UNIX epoch time of the last send
-    lastrecv: u64,              // (unix) The UNIX epoch time of the last receive
-    last_transaction: u64, // (unix) The UNIX epoch time of the last valid transaction received from this peer
-    last_block: u64,       // (unix) The UNIX epoch time of the last block received from this peer
-    bytessent: u64,        // The total bytes sent
-    bytesrecv: u64,        // The total bytes received
-    conntime: u64,         // (unix) The UNIX epoch time of the connection
-    timeoffset: i64,       // The time offset in seconds
-    pingtime: f64,         // ping time (if available)
-    minping: f64,          // minimum observed ping time (if any at all)
-    pingwait: Option<u64>, // ping wait (if non-zero)
-    version: u64,          // The peer version, such as 70001
-    subver: String,        // The string version
-    inbound: bool,         // Inbound (true) or Outbound (false)
-    addnode: Option<bool>, // Whether connection was due to addnode/-connect or if it was an automatic/inbound connection. (DEPRECATED, returned only if the config option -deprecatedrpc=getpeerinfo_addnode is passed)
-    //
-    // TODO: Make this an enum
-    connection_type: String, // Type of connection:
-    //outbound-full-relay (default automatic connections),
-    //block-relay-only (does not relay transactions or addresses),
-    //inbound (initiated by the peer),
-    //manual (added via addnode RPC or -addnode/-connect configuration options),
-    //addr-fetch (short-lived automatic connection for soliciting addresses),
-    //feeler (short-lived automatic connection for testing addresses).
-    //Please note this output is unlikely to be stable in upcoming releases as we iterate to
-    //best capture connection behaviors.
-    startingheight: u64,       // The starting height (block) of the peer
-    banscore: Option<u64>, // The ban score (DEPRECATED, returned only if config option -deprecatedrpc=banscore is passed)
-    synced_headers: i64,   // The last header we have in common with this peer
-    synced_blocks: i64,    // The last block we have in common with this peer
-    inflight: Vec<u64>, // (json array) each element of array is (numeric) The heights of blocks we're currently asking from this peer
-    whitelisted: Option<bool>, // (boolean, optional) Whether the peer is whitelisted with default permissions
+    pub id: u64,                                            // Peer index
+    pub addr: String,              // (host:port) The IP address and port of the peer
+    pub addrbind: Option<String>, // (ip:port) Bind address of the connection to the peer (not returned for some connections)
+    pub addrlocal: Option<String>, // (ip:port) Local address as reported by the peer
+    pub network: Option<PeerNetwork>, // Network the peer connected through (added in bitcoin core 0.21)
+    pub mapped_as: Option<u64>, // The AS in the BGP route to the peer used for diversifying peer selection (only available if the asmap config flag is set)
+    pub services: String,       // (hex) The services offered
+    pub servicesnames: Option<Vec<String>>, // (added in bitcoin core 0.19) (json array) the services offered, in human-readable form. Each element of the array is (string) the service name if it is recognised
+    pub relaytxes: Option<bool>, // Whether peer has asked us to relay transactions to it (not returned until the version handshake is done)
+    pub lastsend: u64,           // (unix) The UNIX epoch time of the last send
+    pub lastrecv: u64,           // (unix) The UNIX epoch time of the last receive
+    pub last_transaction: Option<u64>, // (unix) The UNIX epoch time of the last valid transaction received from this peer (added in bitcoin core 0.21)
+    pub last_block: Option<u64>, // (unix) The UNIX epoch time of the last block received from this peer (added in bitcoin core 0.21)
+    pub bytessent: u64,          // The total bytes sent
+    pub bytesrecv: u64,          // The total bytes received
+    pub conntime: u64,           // (unix) The UNIX epoch time of the connection
+    pub timeoffset: i64,         // The time offset in seconds
+    pub pingtime: Option<f64>,   // ping time in seconds (if available)
+    pub minping: Option<f64>,    // minimum observed ping time in seconds (if any at all)
+    pub pingwait: Option<f64>,   // ping wait in seconds (if non-zero)
+    pub version: u64,            // The peer version, such as 70001
+    pub subver: String,          // The string version
+    pub inbound: bool,           // Inbound (true) or Outbound (false)
+    pub bip152_hb_to: Option<bool>, // Whether we selected peer as (compact blocks) high-bandwidth peer (added in bitcoin core 22)
+    pub bip152_hb_from: Option<bool>, // Whether peer selected us as (compact blocks) high-bandwidth peer (added in bitcoin core 22)
+    pub addnode: Option<bool>, // Whether connection was due to addnode/-connect or if it was an automatic/inbound connection. (DEPRECATED, returned only if the config option -deprecatedrpc=getpeerinfo_addnode is passed)
+    pub connection_type: Option<ConnectionType>, // Type of connection (added in bitcoin core 0.21). Please note this output is unlikely to be stable in upcoming releases as we iterate to best capture connection behaviors.
+    pub transport_protocol_type: Option<TransportProtocol>, // Type of transport protocol (added in bitcoin core 26)
+    pub session_id: Option<String>, // (hex) The session ID for this connection, or "" if there is none (v2 transport only, added in bitcoin core 26)
+    pub startingheight: Option<i64>, // The starting height (block) of the peer (DEPRECATED in bitcoin core 28, returned only if config option -deprecatedrpc=startingheight is passed)
+    pub banscore: Option<u64>, // The ban score (DEPRECATED, returned only if config option -deprecatedrpc=banscore is passed)
+    pub presynced_headers: Option<i64>, // The current height of header pre-synchronization with this peer, or -1 if no low-work sync is in progress (added in bitcoin core 24)
+    // The fields down to addr_rate_limited aren't returned for a peer whose state isn't
+    // available yet, e.g. while it's connecting.
+    pub synced_headers: Option<i64>, // The last header we have in common with this peer, or -1
+    pub synced_blocks: Option<i64>,  // The last block we have in common with this peer, or -1
+    pub inflight: Option<Vec<u64>>, // (json array) each element of array is (numeric) The heights of blocks we're currently asking from this peer
+    pub addr_relay_enabled: Option<bool>, // Whether we participate in address relay with this peer (added in bitcoin core 23)
+    pub addr_processed: Option<u64>, // The total number of addresses processed, excluding those dropped due to rate limiting (added in bitcoin core 23)
+    pub addr_rate_limited: Option<u64>, // The total number of addresses dropped due to rate limiting (added in bitcoin core 23)
+    pub whitelisted: Option<bool>, // (boolean, optional) Whether the peer is whitelisted with default permissions
     // (DEPRECATED, returned only if config option -deprecatedrpc=whitelisted is passed)
-    // TODO: Make this an enum
-    permissions: Vec<String>, //(json array) Any special permissions that have been granted to this peer
-    // (string) bloomfilter (allow requesting BIP37 filtered blocks and transactions),
-    // noban (do not ban for misbehavior; implies download),
-    // forcerelay (relay transactions that are already in the mempool; implies relay),
-    // relay (relay even in -blocksonly mode, and unlimited transaction announcements),
-    // mempool (allow requesting BIP35 mempool contents),
-    // download (allow getheaders during IBD, no disconnect after maxuploadtarget limit),
-    // addr (responses to GETADDR avoid hitting the cache and contain random records with the most up-to-date info).
-    minfeefilter: f64, // The minimum fee rate for transactions this peer accepts
-    bytessent_per_msg: HashMap<String, u64>, // The total bytes sent aggregated by message type When a message type is not listed in this json object, the bytes sent are 0. Only known message types can appear as keys in the object.
-    bytesrecv_per_msg: HashMap<String, u64>, //  The total bytes received aggregated by message type
-                                             //When a message type is not listed in this json object, the bytes received are 0.
-                                             //Only known message types can appear as keys in the object and all bytes received
-                                             //of unknown message types are listed under '*other*'.
+    pub permissions: Option<Vec<PeerPermission>>, //(json array) Any special permissions that have been granted to this peer (added in bitcoin core 0.19)
+    pub minfeefilter: Option<f64>, // The minimum fee rate for transactions this peer accepts, in BTC/kvB (added in bitcoin core 0.19)
+    pub bytessent_per_msg: HashMap<String, u64>, // The total bytes sent aggregated by message type When a message type is not listed in this json object, the bytes sent are 0. Only known message types can appear as keys in the object.
+    pub bytesrecv_per_msg: HashMap<String, u64>, //  The total bytes received aggregated by message type
+                                                 //When a message type is not listed in this json object, the bytes received are 0.
+                                                 //Only known message types can appear as keys in the object and all bytes received
+                                                 //of unknown message types are listed under '*other*'.
+}
+impl ConnectedNetworkNode {
+    // Peer index, as taken by disconnectnode
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+    // (host:port) The IP address and port of the peer. See set_ban::Subnet::from_peer_addr to ban it.
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+    // e.g. "outbound-full-relay" or "inbound" (added in bitcoin core 0.21)
+    pub fn connection_type(&self) -> Option<&str> {
+        self.connection_type.as_ref().map(ConnectionType::as_str)
+    }
+    // e.g. "ipv4" or "onion" (added in bitcoin core 0.21)
+    pub fn network(&self) -> Option<&str> {
+        self.network.as_ref().map(PeerNetwork::as_str)
+    }
+    pub fn is_inbound(&self) -> bool {
+        self.inbound
+    }
 }
+
 pub struct GetPeerInfoCommand {}
 impl GetPeerInfoCommand {
     pub fn new() -> Self {
         GetPeerInfoCommand {}
     }
 }
+impl Default for GetPeerInfoCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 // TODO: struct GetPeerInfoCommandResponse(String);
 #[derive(Serialize, Deserialize, Debug)]
@@ -168,7 +317,7 @@ impl CallableCommand for GetPeerInfoCommand {
     type Response = GetPeerInfoCommandResponse;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
         let params = vec![];
-        let r = request(client, GET_PEER_INFO_COMMAND, params);
+        let r = request(client, GET_PEER_INFO_COMMAND, params)?;
         let response: GetPeerInfoCommandResponse = r.result()?;
         Ok(response)
     }
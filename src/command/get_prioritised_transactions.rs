@@ -0,0 +1,67 @@
+/*
+getprioritisedtransactions
+
+Returns a map of all user-created (see prioritisetransaction) fee deltas by txid, and whether the tx is present in mempool.
+
+Result:
+{                                 (json object) prioritisation keyed by txid
+  "<transactionid>" : {           (json object)
+    "fee_delta" : n,              (numeric) transaction fee delta in satoshis
+    "in_mempool" : true|false,    (boolean) whether this transaction is currently in mempool
+    "modified_fee" : n            (numeric, optional) modified fee in satoshis. Only returned if in_mempool=true
+  },
+  ...
+}
+
+Examples:
+> bitcoin-cli getprioritisedtransactions
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getprioritisedtransactions", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::types::transaction::Txid;
+use crate::version::{require_version, NodeVersion};
+
+const GET_PRIORITISED_TRANSACTIONS_COMMAND: &str = "getprioritisedtransactions";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrioritisedTransaction {
+    pub fee_delta: i64,            // transaction fee delta in satoshis
+    pub in_mempool: bool,          // whether this transaction is currently in mempool
+    pub modified_fee: Option<u64>, // modified fee in satoshis. Only returned if in_mempool=true
+}
+
+// prioritisation keyed by txid
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetPrioritisedTransactionsCommandResponse(pub HashMap<Txid, PrioritisedTransaction>);
+
+pub struct GetPrioritisedTransactionsCommand {}
+impl GetPrioritisedTransactionsCommand {
+    pub fn new() -> Self {
+        GetPrioritisedTransactionsCommand {}
+    }
+}
+impl Default for GetPrioritisedTransactionsCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for GetPrioritisedTransactionsCommand {
+    type Response = GetPrioritisedTransactionsCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        require_version(
+            client,
+            GET_PRIORITISED_TRANSACTIONS_COMMAND,
+            NodeVersion::V27,
+        )?;
+        let params = vec![];
+        let r = request(client, GET_PRIORITISED_TRANSACTIONS_COMMAND, params)?;
+        let response: GetPrioritisedTransactionsCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -87,37 +87,9 @@ pub struct TransactionIdsWithSequence {
     pub mempool_sequence: u64,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Fees {
-    pub base: f64,
-    pub modified: f64,
-    pub ancestor: f64,
-    pub descendant: f64,
-}
+pub use crate::types::transaction::MempoolEntry as Transaction;
+pub use crate::types::transaction::MempoolEntryFees as Fees;
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Transaction {
-    pub vsize: u64,
-    pub weight: u64,
-    pub fee: f64,
-    pub modifiedfee: f64,
-    // TODO: Represent using a unix time
-    pub time: u64,
-    pub height: u64,
-    pub descendantcount: u64,
-    pub descendantsize: u64,
-    pub descendantfees: f64,
-    pub ancestorcount: u64,
-    pub ancestorsize: u64,
-    pub ancestorfees: u64,
-    pub wtxid: String,
-    pub fees: Fees,
-    pub depends: Vec<String>,
-    pub spentby: Vec<String>,
-    #[serde(alias = "bip125-replaceable")]
-    pub bip125_replaceable: bool,
-    pub unbroadcast: bool,
-}
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Transactions(pub HashMap<TransactionId, Transaction>);
 
@@ -141,6 +113,11 @@ impl GetRawMempoolCommand {
         self
     }
 }
+impl Default for GetRawMempoolCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl CallableCommand for GetRawMempoolCommand {
     type Response = GetRawMempoolCommandResponse;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
@@ -153,7 +130,7 @@ impl CallableCommand for GetRawMempoolCommand {
         let mempool_sequence_arg_raw_value = to_raw_value(&mempool_sequence_arg).unwrap();
         let command = "getrawmempool";
         let params = vec![verbose_arg_raw_value, mempool_sequence_arg_raw_value];
-        let r = request(client, command, params);
+        let r = request(client, command, params)?;
         let response: GetRawMempoolCommandResponse = r.result()?;
         Ok(response)
     }
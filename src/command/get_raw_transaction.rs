@@ -89,6 +89,11 @@ use crate::{command::CallableCommand, Blockhash};
 
 use crate::command::request::request;
 
+pub use crate::types::transaction::{
+    BitcoinAddress, CoinbaseVin, HexEncodedWitnessData, NonCoinbaseVin, ScriptPubKey, ScriptSig,
+    Transaction, Vin, Vout,
+};
+
 type TxId = String;
 pub struct GetRawTransactionCommand {
     txid: TxId,
@@ -115,126 +120,38 @@ impl GetRawTransactionCommand {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct HexEncodedWitnessData(pub String);
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct BitcoinAddress(pub String);
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ScriptPubKey {
-    pub asm: String, // "asm", NOT A HEX
-    pub hex: String, // the hex
-    // deprecated
-    pub req_sigs: Option<u64>, // The required sigs
-    #[serde(alias = "type")]
-    pub type_: String, // The type, eg 'pubkeyhash'
-    pub address: Option<String>,
-    // deprecated
-    pub addresses: Option<Vec<String>>,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ScriptSig {
-    pub asm: String, // "asm", NOT A HEX
-    pub hex: String, // "hex", hex
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
 #[serde(untagged)]
-pub enum Vin {
-    Coinbase(CoinbaseVin),
-    NonCoinbase(NonCoinbaseVin),
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct CoinbaseVin {
-    pub coinbase: String,
-    pub sequence: u64, // The script sequence number
-    pub txinwitness: Option<Vec<HexEncodedWitnessData>>, // hex-encoded witness data (if any)
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct NonCoinbaseVin {
-    pub txid: String, // "hex" The transaction id
-    pub vout: u64,    // The output number
-    pub script_sig: ScriptSig,
-    pub sequence: u64, // The script sequence number
-    // TODO: Why is this optional?
-    pub txinwitness: Option<Vec<HexEncodedWitnessData>>,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct Vout {
-    pub value: f64, // The value in BTC
-    pub n: u64,     // index
-    pub script_pub_key: ScriptPubKey,
-    // Deprecated
-    pub req_sigs: Option<u64>,
-    pub address: Option<String>,
-    // Deprecated
-    pub addresses: Option<Vec<String>>,
-}
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct Transaction {
-    pub in_active_chain: Option<bool>,
-    pub hex: String,   // "hex" The serialized, hex-encoded data for 'txid'
-    pub txid: String,  // "hex" The transaction id (same as provided)
-    pub hash: String,  // "hex" The transaction hash (differs from txid for witness transactions)
-    pub size: u64,     // The serialized transaction size
-    pub vsize: u64,    // The virtual transaction size (differs from size for witness transactions)
-    pub weight: u64,   //  The transaction's weight (between vsize*4-3 and vsize*4)
-    pub version: u64,  //  The version
-    pub locktime: u64, // The lock time
-    pub vin: Vec<Vin>,
-    pub vout: Vec<Vout>,
-    pub blockhash: String,  // "hex" the block hash
-    pub confirmations: u64, // "hex" The confirmations
-    pub blocktime: u64,     // "unix time" The block time expressed in UNIX epoch time
-    pub time: u64,          // "unix time" Same as "blocktime"
+#[allow(clippy::large_enum_variant)]
+pub enum GetRawTransactionCommandResponse {
+    SerializedHexEncodedData(String),
+    Transaction(Transaction),
 }
 
-// TODO: I don't think this belongs in this package. We should focus on RPC request and responses
-// and abstract a better data layer into another package.
-impl Transaction {
-    pub fn is_coinbase_transaction(&self) -> bool {
-        match self.vin.first().unwrap() {
-            Vin::Coinbase(_x) => true,
-            Vin::NonCoinbase(_x) => false,
+// Fails with the hex when the transaction was requested without verbose output.
+impl TryFrom<GetRawTransactionCommandResponse> for Transaction {
+    type Error = String;
+    fn try_from(response: GetRawTransactionCommandResponse) -> Result<Self, Self::Error> {
+        match response {
+            GetRawTransactionCommandResponse::Transaction(transaction) => Ok(transaction),
+            GetRawTransactionCommandResponse::SerializedHexEncodedData(hex) => Err(hex),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(untagged)]
-pub enum GetRawTransactionCommandResponse {
-    SerializedHexEncodedData(String),
-    Transaction(Transaction),
-}
-
-// TODO: This will only work for GetBlockCommandVerbosity::BlockObjectWithoutTransactionInformation
-//       because the json response has a different structure it returns for each verbosity option.
-//       For example, GetBlockCommandVerbosity::BlockObjectWithTransactionInformation will return
-//       an array for 'tx' field with full transaction structure, instead of only hashes for the
-//       transaction. To accomplish this, we need to figure out how to have serde handle
-//       conditional responses and map them to appropriate structs.
 impl CallableCommand for GetRawTransactionCommand {
     type Response = GetRawTransactionCommandResponse;
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
         let txid_arg = &self.txid;
         let verbose_arg = &self.is_verbose;
-        // TODO: Add blockhas param!
-        //let blockhash_arg = &self.blockhash.0;
         let txid_arg_raw_value = to_raw_value(&txid_arg).unwrap();
         let verbose_arg_raw_value = to_raw_value(&verbose_arg).unwrap();
         let command = "getrawtransaction";
-        let params = vec![txid_arg_raw_value, verbose_arg_raw_value];
-        let r = request(client, command, params);
+        let mut params = vec![txid_arg_raw_value, verbose_arg_raw_value];
+        if let Some(blockhash) = &self.blockhash {
+            let blockhash_arg_raw_value = to_raw_value(&blockhash.0).unwrap();
+            params.push(blockhash_arg_raw_value)
+        }
+        let r = request(client, command, params)?;
         let response: GetRawTransactionCommandResponse = r.result()?;
         Ok(response)
     }
@@ -0,0 +1,64 @@
+/*
+getrpcinfo
+
+Returns details of the RPC server.
+
+Result:
+{                          (json object)
+  "active_commands" : [    (json array) All active commands
+    {                      (json object) Information about an active command
+      "method" : "str",    (string) The name of the RPC command
+      "duration" : n       (numeric) The running time in microseconds
+    },
+    ...
+  ],
+  "logpath" : "str"        (string) The complete file path to the debug log
+}
+
+Examples:
+> bitcoin-cli getrpcinfo
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "getrpcinfo", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::version::{require_version, NodeVersion};
+
+const GET_RPC_INFO_COMMAND: &str = "getrpcinfo";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActiveCommand {
+    pub method: String, // The name of the RPC command
+    pub duration: u64,  // The running time in microseconds
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetRpcInfoCommandResponse {
+    pub active_commands: Vec<ActiveCommand>, // All active commands, including this getrpcinfo
+    pub logpath: String,                     // The complete file path to the debug log
+}
+
+pub struct GetRpcInfoCommand {}
+impl GetRpcInfoCommand {
+    pub fn new() -> Self {
+        GetRpcInfoCommand {}
+    }
+}
+impl Default for GetRpcInfoCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for GetRpcInfoCommand {
+    type Response = GetRpcInfoCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        require_version(client, GET_RPC_INFO_COMMAND, NodeVersion::V0_18)?;
+        let params = vec![];
+        let r = request(client, GET_RPC_INFO_COMMAND, params)?;
+        let response: GetRpcInfoCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
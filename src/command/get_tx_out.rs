@@ -106,7 +106,7 @@ impl CallableCommand for GetTxOutCommand {
             let include_mempool_arg_raw_value = to_raw_value(&include_mempool_arg).unwrap();
             params.push(include_mempool_arg_raw_value)
         }
-        let r = request(client, GET_TX_OUT_COMMAND, params);
+        let r = request(client, GET_TX_OUT_COMMAND, params)?;
         let response: GetTxOutCommandResponse = r.result()?;
         Ok(response)
     }
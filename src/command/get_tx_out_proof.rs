@@ -0,0 +1,72 @@
+/*
+gettxoutproof ["txid",...] ( "blockhash" )
+
+Returns a hex-encoded proof that "txid" was included in a block.
+
+NOTE: By default this function only works sometimes. This is when there is an
+unspent output in the utxo for this transaction. To make it always work,
+you need to maintain a transaction index, using the -txindex command line option or
+specify the block in which the transaction is included manually (by blockhash).
+
+Arguments:
+1. txids          (json array, required) The txids to filter
+     [
+       "txid",    (string) A transaction hash
+       ...
+     ]
+2. blockhash      (string, optional) If specified, looks for txid in the block with this hash
+
+Result:
+"str"    (string) A string that is a serialized, hex-encoded data for the proof.
+
+Examples:
+> bitcoin-cli gettxoutproof '["mytxid",...]'
+> bitcoin-cli gettxoutproof '["mytxid",...]' "blockhash"
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::types::transaction::Txid;
+use crate::Blockhash;
+
+const GET_TX_OUT_PROOF_COMMAND: &str = "gettxoutproof";
+
+pub struct GetTxOutProofCommand {
+    txids: Vec<Txid>,             // (json array, required) The txids to filter
+    blockhash: Option<Blockhash>, // (string, optional) If specified, looks for txid in the block with this hash
+}
+impl GetTxOutProofCommand {
+    pub fn new(txids: Vec<Txid>) -> Self {
+        GetTxOutProofCommand {
+            txids,
+            blockhash: None,
+        }
+    }
+    pub fn set_blockhash(mut self, blockhash: Blockhash) -> Self {
+        self.blockhash = Some(blockhash);
+        self
+    }
+}
+
+// A serialized, hex-encoded CMerkleBlock. See consensus::merkle::MerkleBlock to verify it
+// locally.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GetTxOutProofCommandResponse(pub String);
+
+impl CallableCommand for GetTxOutProofCommand {
+    type Response = GetTxOutProofCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let txids_arg_raw_value = to_raw_value(&self.txids).unwrap();
+        let mut params = vec![txids_arg_raw_value];
+        if let Some(blockhash) = &self.blockhash {
+            let blockhash_arg_raw_value = to_raw_value(&blockhash.0).unwrap();
+            params.push(blockhash_arg_raw_value)
+        }
+        let r = request(client, GET_TX_OUT_PROOF_COMMAND, params)?;
+        let response: GetTxOutProofCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -1,67 +1,250 @@
 /*
-gettxoutsetinfo ( "hash_type" )
+gettxoutsetinfo ( "hash_type" hash_or_height use_index )
 
 Returns statistics about the unspent transaction output set.
-Note this call may take some time.
+Note this call may take some time if you are not using coinstatsindex.
 
 Arguments:
-1. hash_type    (string, optional, default=hash_serialized_2) Which UTXO set hash should be calculated. Options: 'hash_serialized_2' (the legacy algorithm), 'none'.
+1. hash_type         (string, optional, default="hash_serialized_3") Which UTXO set hash should be calculated. Options: 'hash_serialized_3' (the legacy algorithm), 'muhash', 'none'.
+2. hash_or_height    (string or numeric, optional, default=the current best block) The block hash or height of the target height (only available with coinstatsindex).
+3. use_index         (boolean, optional, default=true) Use coinstatsindex, if available.
 
 Result:
-{                                 (json object)
-  "height" : n,                   (numeric) The current block height (index)
-  "bestblock" : "hex",            (string) The hash of the block at the tip of the chain
-  "transactions" : n,             (numeric) The number of transactions with unspent outputs
-  "txouts" : n,                   (numeric) The number of unspent transaction outputs
-  "bogosize" : n,                 (numeric) A meaningless metric for UTXO set size
-  "hash_serialized_2" : "hex",    (string) The serialized hash (only present if 'hash_serialized_2' hash_type is chosen)
-  "disk_size" : n,                (numeric) The estimated size of the chainstate on disk
-  "total_amount" : n              (numeric) The total amount
+{                                     (json object)
+  "height" : n,                       (numeric) The block height (index) of the returned statistics
+  "bestblock" : "hex",                (string) The hash of the block at which these statistics are calculated
+  "txouts" : n,                       (numeric) The number of unspent transaction outputs
+  "bogosize" : n,                     (numeric) Database-independent, meaningless metric indicating the UTXO set size
+  "hash_serialized_3" : "hex",        (string, optional) The serialized hash (only present if 'hash_serialized_3' hash_type is chosen)
+  "muhash" : "hex",                   (string, optional) The serialized hash (only present if 'muhash' hash_type is chosen)
+  "transactions" : n,                 (numeric, optional) The number of transactions with unspent outputs (not available when coinstatsindex is used)
+  "disk_size" : n,                    (numeric, optional) The estimated size of the chainstate on disk (not available when coinstatsindex is used)
+  "total_amount" : n,                 (numeric) The total amount of coins in the UTXO set
+  "total_unspendable_amount" : n,     (numeric, optional) The total amount of coins permanently excluded from the UTXO set (only available if coinstatsindex is used)
+  "block_info" : {                    (json object, optional) Info on amounts in the block at this block height (only available if coinstatsindex is used)
+    "prevout_spent" : n,              (numeric) Total amount of all prevouts spent in this block
+    "coinbase" : n,                   (numeric) Coinbase subsidy amount of this block
+    "new_outputs_ex_coinbase" : n,    (numeric) Total amount of new outputs created by this block
+    "unspendable" : n,                (numeric) Total amount of unspendable outputs created in this block
+    "unspendables" : {                (json object) Detailed view of the unspendable categories
+      "genesis_block" : n,            (numeric) The unspendable amount of the Genesis block subsidy
+      "bip30" : n,                    (numeric) Transactions overridden by duplicates (no longer possible with BIP30)
+      "scripts" : n,                  (numeric) Amounts sent to scripts that are unspendable (for example OP_RETURN outputs)
+      "unclaimed_rewards" : n         (numeric) Fee rewards that miners did not claim in their coinbase transaction
+    }
+  }
 }
 
 Examples:
 > bitcoin-cli gettxoutsetinfo
+> bitcoin-cli gettxoutsetinfo "none"
+> bitcoin-cli gettxoutsetinfo "none" 1000
+> bitcoin-cli gettxoutsetinfo "none" '"00000000c937983704a73af28acdec37b049d214adbda81d7e2a3dd146f6ed09"'
+> bitcoin-cli -named gettxoutsetinfo hash_type='muhash' use_index='false'
 > curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "gettxoutsetinfo", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "gettxoutsetinfo", "params": ["none"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "gettxoutsetinfo", "params": ["none", 1000]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "gettxoutsetinfo", "params": ["none", "00000000c937983704a73af28acdec37b049d214adbda81d7e2a3dd146f6ed09"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
 */
 
 const GET_TX_OUT_SET_INFO_COMMAND: &str = "gettxoutsetinfo";
-const DEFAULT_HASH_TYPE_ARG: &str = "hash_serialized_2";
 
 use crate::client::Client;
+use crate::command::get_block_stats::TargetBlockArgument;
 use crate::command::request::request;
 use crate::command::CallableCommand;
+use crate::version::{require_version, unsupported_error, NodeVersion};
+use crate::Blockhash;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::value::{to_raw_value, RawValue};
 
-pub struct GetTxOutSetInfoCommand {}
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HashType {
+    #[serde(rename = "hash_serialized_2")]
+    HashSerialized2, // the legacy algorithm, up to bitcoin core 25
+    #[serde(rename = "hash_serialized_3")]
+    HashSerialized3, // the legacy algorithm, from bitcoin core 26 on
+    Muhash, // added in bitcoin core 0.21
+    None,
+}
+
+impl HashType {
+    // The hash_type argument itself was added in bitcoin core 0.21.
+    fn minimum_version(&self) -> NodeVersion {
+        match self {
+            HashType::HashSerialized3 => NodeVersion::V26,
+            HashType::HashSerialized2 | HashType::Muhash | HashType::None => NodeVersion::V0_21,
+        }
+    }
+    // The first version that no longer has it.
+    fn removed_in(&self) -> Option<NodeVersion> {
+        match self {
+            HashType::HashSerialized2 => Some(NodeVersion::V26),
+            _ => None,
+        }
+    }
+}
+
+pub struct GetTxOutSetInfoCommand {
+    hash_type: Option<HashType>, // (string, optional, default="hash_serialized_3") Which UTXO set hash should be calculated
+    hash_or_height: Option<TargetBlockArgument>, // (string or numeric, optional, default=the current best block) The block hash or height of the target height (only available with coinstatsindex)
+    use_index: Option<bool>, // (boolean, optional, default=true) Use coinstatsindex, if available
+}
 impl GetTxOutSetInfoCommand {
     pub fn new() -> Self {
-        GetTxOutSetInfoCommand {}
+        GetTxOutSetInfoCommand {
+            hash_type: None,
+            hash_or_height: None,
+            use_index: None,
+        }
     }
+    pub fn set_hash_type(mut self, hash_type: HashType) -> Self {
+        self.hash_type = Some(hash_type);
+        self
+    }
+    // Statistics as of an earlier block. Requires coinstatsindex (bitcoin core 22).
+    pub fn set_hash_or_height(mut self, hash_or_height: TargetBlockArgument) -> Self {
+        self.hash_or_height = Some(hash_or_height);
+        self
+    }
+    pub fn set_use_index(mut self, use_index: bool) -> Self {
+        self.use_index = Some(use_index);
+        self
+    }
+}
+impl Default for GetTxOutSetInfoCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Unspendables {
+    pub genesis_block: f64, // The unspendable amount of the Genesis block subsidy
+    pub bip30: f64,         // Transactions overridden by duplicates (no longer possible with BIP30)
+    pub scripts: f64, // Amounts sent to scripts that are unspendable (for example OP_RETURN outputs)
+    pub unclaimed_rewards: f64, // Fee rewards that miners did not claim in their coinbase transaction
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CoinStatsBlockInfo {
+    pub prevout_spent: f64, // Total amount of all prevouts spent in this block
+    pub coinbase: f64,      // Coinbase subsidy amount of this block
+    pub new_outputs_ex_coinbase: f64, // Total amount of new outputs created by this block
+    pub unspendable: f64,   // Total amount of unspendable outputs created in this block
+    pub unspendables: Unspendables, // Detailed view of the unspendable categories
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetTxOutSetInfoCommandResponse {
-    pub height: u64,               // The current block height (index)
-    pub bestblock: String,         // "hex" The hash of the block at the tip of the chain
-    pub transactions: u64,         // The number of transactions with unspent outputs
+    pub height: u64,               // The block height (index) of the returned statistics
+    pub bestblock: Blockhash, // "hex" The hash of the block at which these statistics are calculated
+    pub transactions: Option<u64>, // The number of transactions with unspent outputs (not available when coinstatsindex is used)
     pub txouts: u64,               // The number of unspent transaction outputs
     pub bogosize: u64,             // A meaningless metric for UTXO set size
-    pub hash_serialized_2: String, // "hex" The serialized hash (only present if 'hash_serialized_2' hash_type is chosen)
-    pub disk_size: u64,            // The estimated size of the chainstate on disk
-    pub total_amount: f64,         // The total amount
+    pub hash_serialized_2: Option<String>, // "hex" The serialized hash (only present if 'hash_serialized_2' hash_type is chosen, up to bitcoin core 25)
+    pub hash_serialized_3: Option<String>, // "hex" The serialized hash (only present if 'hash_serialized_3' hash_type is chosen, from bitcoin core 26 on)
+    pub muhash: Option<String>, // "hex" The serialized hash (only present if 'muhash' hash_type is chosen)
+    pub disk_size: Option<u64>, // The estimated size of the chainstate on disk (not available when coinstatsindex is used)
+    pub total_amount: f64,      // The total amount of coins in the UTXO set
+    pub total_unspendable_amount: Option<f64>, // The total amount of coins permanently excluded from the UTXO set (only available if coinstatsindex is used)
+    pub block_info: Option<CoinStatsBlockInfo>, // Info on amounts in the block at this block height (only available if coinstatsindex is used)
+}
+
+impl GetTxOutSetInfoCommandResponse {
+    // The serialized UTXO set hash, whichever version the node computed.
+    pub fn hash_serialized(&self) -> Option<&str> {
+        self.hash_serialized_3
+            .as_deref()
+            .or(self.hash_serialized_2.as_deref())
+    }
 }
 
 impl CallableCommand for GetTxOutSetInfoCommand {
     type Response = GetTxOutSetInfoCommandResponse;
-    // TODO: This currently fails. Seems realted to this: https://github.com/bitcoin/bitcoin/issues/25724
     fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
         let command = GET_TX_OUT_SET_INFO_COMMAND;
-        // TODO: Implemnt hashtype arg (wasn't an option in bitcoin core v0.20)
-        // let params: Vec<Box<RawValue>> = vec![hash_type_arg_raw_value];
-        let params: Vec<Box<RawValue>> = vec![];
-        let r = request(client, command, params);
+        // hash_or_height and use_index were added with coinstatsindex in bitcoin core 22. Before
+        // that the command takes only hash_type.
+        let minimum_version = if self.hash_or_height.is_some() || self.use_index.is_some() {
+            Some(NodeVersion::V22)
+        } else {
+            None
+        };
+        let minimum_version = minimum_version.max(self.hash_type.map(|h| h.minimum_version()));
+        let removed_in = self.hash_type.and_then(|h| h.removed_in());
+        if removed_in.is_some() {
+            let version = client.node_version()?;
+            if Some(version) >= removed_in {
+                return Err(unsupported_error(command, version));
+            }
+        }
+        if let Some(minimum_version) = minimum_version {
+            require_version(client, command, minimum_version)?;
+        }
+
+        let mut params: Vec<Box<RawValue>> = vec![];
+        if self.hash_type.is_some() || self.hash_or_height.is_some() || self.use_index.is_some() {
+            params.push(to_raw_value(&self.hash_type).unwrap());
+        }
+        if self.hash_or_height.is_some() || self.use_index.is_some() {
+            let hash_or_height_arg_raw_value = match &self.hash_or_height {
+                Some(TargetBlockArgument::Hash(hash)) => to_raw_value(&hash).unwrap(),
+                Some(TargetBlockArgument::Height(height)) => to_raw_value(&height).unwrap(),
+                None => to_raw_value(&()).unwrap(),
+            };
+            params.push(hash_or_height_arg_raw_value);
+        }
+        if let Some(use_index) = self.use_index {
+            params.push(to_raw_value(&use_index).unwrap());
+        }
+        let r = request(client, command, params)?;
         let response: GetTxOutSetInfoCommandResponse = r.result()?;
         Ok(response)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_types_use_the_node_names() {
+        for (hash_type, name) in [
+            (HashType::HashSerialized2, "\"hash_serialized_2\""),
+            (HashType::HashSerialized3, "\"hash_serialized_3\""),
+            (HashType::Muhash, "\"muhash\""),
+            (HashType::None, "\"none\""),
+        ] {
+            assert_eq!(serde_json::to_string(&hash_type).unwrap(), name);
+            assert_eq!(serde_json::from_str::<HashType>(name).unwrap(), hash_type);
+        }
+    }
+
+    #[test]
+    fn hash_serialized_2_was_replaced_in_v26() {
+        assert_eq!(
+            HashType::HashSerialized2.removed_in(),
+            Some(NodeVersion::V26)
+        );
+        assert_eq!(
+            HashType::HashSerialized3.minimum_version(),
+            NodeVersion::V26
+        );
+        assert_eq!(HashType::Muhash.removed_in(), None);
+    }
+
+    #[test]
+    fn every_hash_type_needs_v0_21() {
+        for hash_type in [
+            HashType::HashSerialized2,
+            HashType::HashSerialized3,
+            HashType::Muhash,
+            HashType::None,
+        ] {
+            assert!(hash_type.minimum_version() >= NodeVersion::V0_21);
+        }
+    }
+}
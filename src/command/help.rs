@@ -0,0 +1,234 @@
+/*
+help ( "command" )
+
+List all commands, or get help for a specified command.
+
+Arguments:
+1. command    (string, optional, default=all commands) The command to get help on
+
+Result:
+"str"    (string) The help text
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const HELP_COMMAND: &str = "help";
+
+pub struct HelpCommand {
+    command: Option<String>, // (string, optional, default=all commands) The command to get help on
+}
+impl HelpCommand {
+    pub fn new() -> Self {
+        HelpCommand { command: None }
+    }
+    pub fn set_command(mut self, command: String) -> Self {
+        self.command = Some(command);
+        self
+    }
+}
+impl Default for HelpCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// A numbered entry of the "Arguments:" section, e.g.
+// `2. verbose      (boolean, optional, default=true) true for a json object, ...`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HelpArgument {
+    pub position: u64,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: String, // e.g. "string", "numeric", "json array"
+    pub optional: bool,          // false for "required" arguments
+    pub default: Option<String>, // The text after "default=", if given
+    pub description: String,
+    pub details: Vec<String>, // Indented lines that follow, such as the fields of an options object
+}
+
+// A "Result:" section. Commands whose result depends on their arguments have several, e.g.
+// "Result (for verbose = true):".
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HelpResult {
+    pub condition: Option<String>, // e.g. "for verbose = true"
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HelpSections {
+    pub usage: String, // e.g. `getblockheader "blockhash" ( verbose )`
+    pub description: String,
+    pub arguments: Vec<HelpArgument>,
+    pub results: Vec<HelpResult>,
+    pub examples: Vec<String>, // Example invocations, without the leading "> "
+}
+
+// A "== Category ==" heading of the command list, with the usage line of each of its commands.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HelpCategory {
+    pub name: String,
+    pub commands: Vec<String>,
+}
+
+enum Section {
+    Description,
+    Arguments,
+    Result,
+    Examples,
+}
+
+// The raw help text, exactly as bitcoind returns it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HelpCommandResponse(pub String);
+
+impl HelpCommandResponse {
+    // Parses the help of a single command into its sections.
+    pub fn sections(&self) -> HelpSections {
+        let mut lines = self.0.lines();
+        let usage = lines.next().unwrap_or("").trim().to_string();
+        let mut description: Vec<&str> = vec![];
+        let mut arguments: Vec<HelpArgument> = vec![];
+        let mut results: Vec<HelpResult> = vec![];
+        let mut examples = vec![];
+        let mut section = Section::Description;
+
+        for line in lines {
+            let trimmed = line.trim_end();
+            if trimmed == "Arguments:" {
+                section = Section::Arguments;
+                continue;
+            }
+            if trimmed == "Examples:" {
+                section = Section::Examples;
+                continue;
+            }
+            if let Some(condition) = parse_result_heading(trimmed) {
+                results.push(HelpResult {
+                    condition,
+                    body: String::new(),
+                });
+                section = Section::Result;
+                continue;
+            }
+            match section {
+                Section::Description => description.push(trimmed),
+                Section::Arguments => {
+                    if let Some(argument) = parse_argument(trimmed) {
+                        arguments.push(argument);
+                    } else if let Some(argument) = arguments.last_mut() {
+                        if !trimmed.trim().is_empty() {
+                            argument.details.push(trimmed.trim().to_string());
+                        }
+                    }
+                }
+                Section::Result => {
+                    let result = results.last_mut().expect("a result heading was seen");
+                    if !result.body.is_empty() {
+                        result.body.push('\n');
+                    }
+                    result.body.push_str(trimmed);
+                }
+                Section::Examples => {
+                    if let Some(example) = trimmed.strip_prefix("> ") {
+                        examples.push(example.to_string());
+                    }
+                }
+            }
+        }
+        for result in results.iter_mut() {
+            result.body = result.body.trim_end().to_string();
+        }
+        HelpSections {
+            usage,
+            description: description.join("\n").trim().to_string(),
+            arguments,
+            results,
+            examples,
+        }
+    }
+
+    // Parses the help listing all commands (help called without a command).
+    pub fn command_list(&self) -> Vec<HelpCategory> {
+        let mut categories: Vec<HelpCategory> = vec![];
+        for line in self.0.lines().map(str::trim) {
+            if let Some(name) = line
+                .strip_prefix("== ")
+                .and_then(|line| line.strip_suffix(" =="))
+            {
+                categories.push(HelpCategory {
+                    name: name.to_string(),
+                    commands: vec![],
+                });
+            } else if !line.is_empty() {
+                if let Some(category) = categories.last_mut() {
+                    category.commands.push(line.to_string());
+                }
+            }
+        }
+        categories
+    }
+}
+
+// "Result:" gives Some(None), "Result (for verbose = true):" gives Some(Some("for verbose = true")).
+fn parse_result_heading(line: &str) -> Option<Option<String>> {
+    let heading = line.strip_prefix("Result")?.strip_suffix(':')?.trim();
+    if heading.is_empty() {
+        return Some(None);
+    }
+    let condition = heading.strip_prefix('(')?.strip_suffix(')')?;
+    Some(Some(condition.trim().to_string()))
+}
+
+fn parse_argument(line: &str) -> Option<HelpArgument> {
+    let (position, rest) = line.split_once(". ")?;
+    let position = position.parse().ok()?;
+    let (name, rest) = rest.trim().split_once(char::is_whitespace)?;
+    let rest = rest.trim_start().strip_prefix('(')?;
+    // Find the parenthesis closing the type, allowing for nested ones in the default.
+    let mut depth = 1;
+    let close = rest.char_indices().find_map(|(index, character)| {
+        match character {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        (depth == 0).then_some(index)
+    })?;
+    let mut properties = rest[..close].split(", ");
+    let type_ = properties.next()?.to_string();
+    let optional = properties
+        .next()
+        .is_some_and(|requirement| !requirement.starts_with("required"));
+    let default = properties
+        .collect::<Vec<&str>>()
+        .join(", ")
+        .strip_prefix("default=")
+        .map(str::to_string);
+    Some(HelpArgument {
+        position,
+        name: name.trim_matches('"').to_string(),
+        type_,
+        optional,
+        default,
+        description: rest[close + 1..].trim().to_string(),
+        details: vec![],
+    })
+}
+
+impl CallableCommand for HelpCommand {
+    type Response = HelpCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let mut params = vec![];
+        if let Some(command) = &self.command {
+            let command_arg_raw_value = to_raw_value(&command).unwrap();
+            params.push(command_arg_raw_value)
+        }
+        let r = request(client, HELP_COMMAND, params)?;
+        let response: HelpCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
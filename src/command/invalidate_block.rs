@@ -0,0 +1,50 @@
+/*
+invalidateblock "blockhash"
+
+Permanently marks a block as invalid, as if it violated a consensus rule.
+
+Arguments:
+1. blockhash    (string, required) the hash of the block to mark as invalid
+
+Result:
+null    (json null)
+
+Examples:
+> bitcoin-cli invalidateblock "blockhash"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "invalidateblock", "params": ["blockhash"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::Blockhash;
+
+const INVALIDATE_BLOCK_COMMAND: &str = "invalidateblock";
+
+pub struct InvalidateBlockCommand {
+    blockhash: Blockhash, // (string, required) the hash of the block to mark as invalid
+}
+impl InvalidateBlockCommand {
+    pub fn new(blockhash: Blockhash) -> Self {
+        InvalidateBlockCommand { blockhash }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InvalidateBlockCommandResponse;
+
+impl CallableCommand for InvalidateBlockCommand {
+    type Response = InvalidateBlockCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let blockhash_arg_raw_value = to_raw_value(&self.blockhash.0).unwrap();
+        let params = vec![blockhash_arg_raw_value];
+        let r = request(client, INVALIDATE_BLOCK_COMMAND, params)?;
+        let response: InvalidateBlockCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,63 @@
+/*
+listbanned
+
+List all manually banned IPs/Subnets.
+
+Result:
+[                              (json array)
+  {                            (json object)
+    "address" : "str",         (string) The IP/Subnet of the banned node
+    "ban_created" : xxx,       (numeric) The UNIX epoch time the ban was created
+    "banned_until" : xxx,      (numeric) The UNIX epoch time the ban expires
+    "ban_duration" : xxx,      (numeric) The ban duration, in seconds
+    "time_remaining" : xxx     (numeric) The time remaining until the ban expires, in seconds
+  },
+  ...
+]
+
+Examples:
+> bitcoin-cli listbanned
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "listbanned", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::set_ban::Subnet;
+use crate::command::CallableCommand;
+
+const LIST_BANNED_COMMAND: &str = "listbanned";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BannedSubnet {
+    pub address: Subnet,             // The IP/Subnet of the banned node
+    pub ban_created: u64,            // The UNIX epoch time the ban was created
+    pub banned_until: u64,           // The UNIX epoch time the ban expires
+    pub ban_duration: Option<u64>,   // The ban duration, in seconds (v22.0 and later)
+    pub time_remaining: Option<i64>, // The time remaining until the ban expires, in seconds (v22.0 and later)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListBannedCommandResponse(pub Vec<BannedSubnet>);
+
+pub struct ListBannedCommand {}
+impl ListBannedCommand {
+    pub fn new() -> Self {
+        ListBannedCommand {}
+    }
+}
+impl Default for ListBannedCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for ListBannedCommand {
+    type Response = ListBannedCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let params = vec![];
+        let r = request(client, LIST_BANNED_COMMAND, params)?;
+        let response: ListBannedCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
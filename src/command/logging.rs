@@ -0,0 +1,250 @@
+/*
+logging ( ["include_category",...] ["exclude_category",...] )
+
+Gets and sets the logging configuration.
+When called without an argument, returns the list of categories with status that are currently being debug logged or not.
+When called with arguments, adds or removes categories from debug logging and return the lists above.
+The arguments are evaluated in order "include", "exclude".
+If an item is both included and excluded, it will thus end up being excluded.
+The valid logging categories are: addrman, bench, blockstorage, cmpctblock, coindb, estimatefee, http, i2p, ipc, leveldb, libevent, mempool, mempoolrej, net, proxy, prune, qt, rand, reindex, rpc, scan, selectcoins, tor, txpackages, txreconciliation, validation, walletdb, zmq
+In addition, the following are available as category names with special meanings:
+  - "all",  "1" : represent all logging categories.
+  - "none", "0" : even if other logging categories are specified, ignore all of them.
+
+Arguments:
+1. include                    (json array, optional) The categories to add to debug logging
+     [
+       "include_category",    (string) the valid logging category
+       ...
+     ]
+2. exclude                    (json array, optional) The categories to remove from debug logging
+     [
+       "exclude_category",    (string) the valid logging category
+       ...
+     ]
+
+Result:
+{                   (json object) keys are the logging categories, and values indicates its status
+  "category" : true|false,    (boolean) if being debug logged or not. false:inactive, true:active
+  ...
+}
+
+Examples:
+> bitcoin-cli logging "[\"all\"]" "[\"http\"]"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "logging", "params": [["all"], ["libevent"]]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::to_raw_value;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const LOGGING_COMMAND: &str = "logging";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LoggingCategory {
+    Addrman,
+    Bench,
+    Blockstorage,
+    Cmpctblock,
+    Coindb,
+    Estimatefee,
+    Http,
+    I2p,
+    Ipc,
+    Leveldb,
+    Libevent,
+    Lock,
+    Mempool,
+    Mempoolrej,
+    Net,
+    Proxy,
+    Prune,
+    Qt,
+    Rand,
+    Reindex,
+    Rpc,
+    Scan,
+    Selectcoins,
+    Tor,
+    Txpackages,
+    Txreconciliation,
+    Validation,
+    Walletdb,
+    Zmq,
+    All,  // Only as an argument: all logging categories
+    None, // Only as an argument: ignore all other categories given with it
+    // A category this version of the crate doesn't know about yet
+    Other(String),
+}
+
+impl LoggingCategory {
+    pub fn as_str(&self) -> &str {
+        match self {
+            LoggingCategory::Addrman => "addrman",
+            LoggingCategory::Bench => "bench",
+            LoggingCategory::Blockstorage => "blockstorage",
+            LoggingCategory::Cmpctblock => "cmpctblock",
+            LoggingCategory::Coindb => "coindb",
+            LoggingCategory::Estimatefee => "estimatefee",
+            LoggingCategory::Http => "http",
+            LoggingCategory::I2p => "i2p",
+            LoggingCategory::Ipc => "ipc",
+            LoggingCategory::Leveldb => "leveldb",
+            LoggingCategory::Libevent => "libevent",
+            LoggingCategory::Lock => "lock",
+            LoggingCategory::Mempool => "mempool",
+            LoggingCategory::Mempoolrej => "mempoolrej",
+            LoggingCategory::Net => "net",
+            LoggingCategory::Proxy => "proxy",
+            LoggingCategory::Prune => "prune",
+            LoggingCategory::Qt => "qt",
+            LoggingCategory::Rand => "rand",
+            LoggingCategory::Reindex => "reindex",
+            LoggingCategory::Rpc => "rpc",
+            LoggingCategory::Scan => "scan",
+            LoggingCategory::Selectcoins => "selectcoins",
+            LoggingCategory::Tor => "tor",
+            LoggingCategory::Txpackages => "txpackages",
+            LoggingCategory::Txreconciliation => "txreconciliation",
+            LoggingCategory::Validation => "validation",
+            LoggingCategory::Walletdb => "walletdb",
+            LoggingCategory::Zmq => "zmq",
+            LoggingCategory::All => "all",
+            LoggingCategory::None => "none",
+            LoggingCategory::Other(category) => category,
+        }
+    }
+}
+
+impl FromStr for LoggingCategory {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let category = match s {
+            "addrman" => LoggingCategory::Addrman,
+            "bench" => LoggingCategory::Bench,
+            "blockstorage" => LoggingCategory::Blockstorage,
+            "cmpctblock" => LoggingCategory::Cmpctblock,
+            "coindb" => LoggingCategory::Coindb,
+            "estimatefee" => LoggingCategory::Estimatefee,
+            "http" => LoggingCategory::Http,
+            "i2p" => LoggingCategory::I2p,
+            "ipc" => LoggingCategory::Ipc,
+            "leveldb" => LoggingCategory::Leveldb,
+            "libevent" => LoggingCategory::Libevent,
+            "lock" => LoggingCategory::Lock,
+            "mempool" => LoggingCategory::Mempool,
+            "mempoolrej" => LoggingCategory::Mempoolrej,
+            "net" => LoggingCategory::Net,
+            "proxy" => LoggingCategory::Proxy,
+            "prune" => LoggingCategory::Prune,
+            "qt" => LoggingCategory::Qt,
+            "rand" => LoggingCategory::Rand,
+            "reindex" => LoggingCategory::Reindex,
+            "rpc" => LoggingCategory::Rpc,
+            "scan" => LoggingCategory::Scan,
+            "selectcoins" => LoggingCategory::Selectcoins,
+            "tor" => LoggingCategory::Tor,
+            "txpackages" => LoggingCategory::Txpackages,
+            "txreconciliation" => LoggingCategory::Txreconciliation,
+            "validation" => LoggingCategory::Validation,
+            "walletdb" => LoggingCategory::Walletdb,
+            "zmq" => LoggingCategory::Zmq,
+            "all" | "1" => LoggingCategory::All,
+            "none" | "0" => LoggingCategory::None,
+            "" => return Err("empty logging category".to_string()),
+            other => LoggingCategory::Other(other.to_string()),
+        };
+        Ok(category)
+    }
+}
+
+impl fmt::Display for LoggingCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for LoggingCategory {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for LoggingCategory {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let category = String::deserialize(deserializer)?;
+        category.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+// Keys are the logging categories, and values indicate whether they're being debug logged.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LoggingCommandResponse(pub HashMap<LoggingCategory, bool>);
+
+impl LoggingCommandResponse {
+    pub fn is_active(&self, category: &LoggingCategory) -> bool {
+        self.0.get(category).copied().unwrap_or(false)
+    }
+    pub fn active_categories(&self) -> Vec<&LoggingCategory> {
+        let mut active: Vec<&LoggingCategory> = self
+            .0
+            .iter()
+            .filter(|(_, active)| **active)
+            .map(|(category, _)| category)
+            .collect();
+        active.sort_by_key(|category| category.as_str());
+        active
+    }
+}
+
+pub struct LoggingCommand {
+    include: Vec<LoggingCategory>, // (json array, optional) The categories to add to debug logging
+    exclude: Vec<LoggingCategory>, // (json array, optional) The categories to remove from debug logging
+}
+impl LoggingCommand {
+    // Without categories, this just returns the current logging configuration.
+    pub fn new() -> Self {
+        LoggingCommand {
+            include: vec![],
+            exclude: vec![],
+        }
+    }
+    pub fn set_include(mut self, include: Vec<LoggingCategory>) -> Self {
+        self.include = include;
+        self
+    }
+    pub fn set_exclude(mut self, exclude: Vec<LoggingCategory>) -> Self {
+        self.exclude = exclude;
+        self
+    }
+}
+impl Default for LoggingCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for LoggingCommand {
+    type Response = LoggingCommandResponse;
+    // Without categories the call only reports the logging state.
+    fn is_idempotent(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let mut params = vec![];
+        if !self.include.is_empty() || !self.exclude.is_empty() {
+            let include_arg_raw_value = to_raw_value(&self.include).unwrap();
+            let exclude_arg_raw_value = to_raw_value(&self.exclude).unwrap();
+            params.push(include_arg_raw_value);
+            params.push(exclude_arg_raw_value);
+        }
+        let r = request(client, LOGGING_COMMAND, params)?;
+        let response: LoggingCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -1,26 +1,71 @@
+pub mod add_node;
+pub mod clear_banned;
+pub mod create_multisig;
+pub mod decode_raw_transaction;
+pub mod derive_addresses;
+pub mod disconnect_node;
+pub mod generate_block;
+pub mod generate_to_address;
+pub mod generate_to_descriptor;
+pub mod get_added_node_info;
 pub mod get_best_block_hash;
 pub mod get_block;
 pub mod get_block_count;
+pub mod get_block_filter;
 pub mod get_block_hash;
 pub mod get_block_header;
 pub mod get_block_stats;
+pub mod get_block_template;
 pub mod get_blockchain_info;
 pub mod get_chain_tips;
 pub mod get_chain_tx_stats;
 pub mod get_connection_count;
+pub mod get_deployment_info;
+pub mod get_descriptor_info;
 pub mod get_difficulty;
+pub mod get_index_info;
+pub mod get_memory_info;
 pub mod get_mempool_entry;
 pub mod get_mempool_info;
 pub mod get_mining_info;
+pub mod get_net_totals;
 pub mod get_network_hash_ps;
 pub mod get_network_info;
 pub mod get_node_addresses;
 pub mod get_peer_info;
+pub mod get_prioritised_transactions;
 pub mod get_raw_mempool;
 pub mod get_raw_transaction;
+pub mod get_rpc_info;
 pub mod get_tx_out;
+pub mod get_tx_out_proof;
 pub mod get_tx_out_set_info;
+pub mod help;
+pub mod invalidate_block;
+pub mod list_banned;
+pub mod logging;
+pub mod ping;
+pub mod precious_block;
+pub mod prioritise_transaction;
+pub mod prune_blockchain;
+pub mod reconsider_block;
 pub mod request;
+pub mod save_mempool;
+pub mod scan_tx_out_set;
+pub mod set_ban;
+pub mod set_network_active;
+pub mod sign_message_with_priv_key;
+pub mod stop;
+pub mod submit_block;
+pub mod submit_header;
+pub mod uptime;
+pub mod validate_address;
+pub mod verify_chain;
+pub mod verify_message;
+pub mod verify_tx_out_proof;
+pub mod wait_for_block;
+pub mod wait_for_block_height;
+pub mod wait_for_new_block;
 
 mod callable_command;
 pub use callable_command::CallableCommand;
@@ -31,12 +76,13 @@ pub use callable_command::CallableCommand;
 > getblock "blockhash" ( verbosity )
 > getblockchaininfo
 > getblockcount
-getblockfilter "blockhash" ( "filtertype" )
+> getblockfilter "blockhash" ( "filtertype" )
 > getblockhash height
 > getblockheader "blockhash" ( verbose )
 > getblockstats hash_or_height ( stats )
 > getchaintips
 > getchaintxstats ( nblocks "blockhash" )
+> getdeploymentinfo ( "blockhash" )
 > getdifficulty
 getmempoolancestors "txid" ( verbose )
 getmempooldescendants "txid" ( verbose )
@@ -44,50 +90,56 @@ getmempooldescendants "txid" ( verbose )
 > getmempoolinfo
 > getrawmempool ( verbose mempool_sequence )
 > gettxout "txid" n ( include_mempool )
-gettxoutproof ["txid",...] ( "blockhash" )
+> gettxoutproof ["txid",...] ( "blockhash" )
 > gettxoutsetinfo ( "hash_type" hash_or_height use_index )
-preciousblock "blockhash"
-pruneblockchain height
-savemempool
-scantxoutset "action" ( [scanobjects,...] )
-verifychain ( checklevel nblocks )
-verifytxoutproof "proof"
+> invalidateblock "blockhash"
+> preciousblock "blockhash"
+> pruneblockchain height
+> reconsiderblock "blockhash"
+> savemempool
+> scantxoutset "action" ( [scanobjects,...] )
+> verifychain ( checklevel nblocks )
+> verifytxoutproof "proof"
+> waitforblock "blockhash" ( timeout )
+> waitforblockheight height ( timeout )
+> waitfornewblock ( timeout )
 
 == Control ==
-getmemoryinfo ( "mode" )
-getrpcinfo
-help ( "command" )
-logging ( ["include_category",...] ["exclude_category",...] )
-stop
-uptime
+> getmemoryinfo ( "mode" )
+> getrpcinfo
+> help ( "command" )
+> logging ( ["include_category",...] ["exclude_category",...] )
+> stop
+> uptime
 
 == Generating ==
-generateblock "output" ["rawtx/txid",...]
-generatetoaddress nblocks "address" ( maxtries )
-generatetodescriptor num_blocks "descriptor" ( maxtries )
+> generateblock "output" ["rawtx/txid",...]
+> generatetoaddress nblocks "address" ( maxtries )
+> generatetodescriptor num_blocks "descriptor" ( maxtries )
 
 == Mining ==
-getblocktemplate ( "template_request" )
+> getblocktemplate ( "template_request" )
 > getmininginfo
 > getnetworkhashps ( nblocks height )
-prioritisetransaction "txid" ( dummy ) fee_delta
-submitblock "hexdata" ( "dummy" )
-submitheader "hexdata"
+> getprioritisedtransactions
+> prioritisetransaction "txid" ( dummy ) fee_delta
+> submitblock "hexdata" ( "dummy" )
+> submitheader "hexdata"
 
 == Network ==
-addnode "node" "command"
-clearbanned
-disconnectnode ( "address" nodeid )
-* getaddednodeinfo ( "node" )
+> addnode "node" "command"
+> clearbanned
+> disconnectnode ( "address" nodeid )
+> getaddednodeinfo ( "node" )
 > getconnectioncount
-* getnettotals
+> getnettotals
 > getnetworkinfo
 > getnodeaddresses ( count "network" )
 > getpeerinfo
-* listbanned
-ping
-setban "subnet" "command" ( bantime absolute )
-setnetworkactive state
+> listbanned
+> ping
+> setban "subnet" "command" ( bantime absolute )
+> setnetworkactive state
 
 == Rawtransactions ==
 analyzepsbt "psbt"
@@ -97,7 +149,7 @@ converttopsbt "hexstring" ( permitsigdata iswitness )
 createpsbt [{"txid":"hex","vout":n,"sequence":n},...] [{"address":amount,...},{"data":"hex"},...] ( locktime replaceable )
 createrawtransaction [{"txid":"hex","vout":n,"sequence":n},...] [{"address":amount,...},{"data":"hex"},...] ( locktime replaceable )
 decodepsbt "psbt"
-decoderawtransaction "hexstring" ( iswitness )
+> decoderawtransaction "hexstring" ( iswitness )
 decodescript "hexstring"
 finalizepsbt "psbt" ( extract )
 fundrawtransaction "hexstring" ( options iswitness )
@@ -112,14 +164,14 @@ utxoupdatepsbt "psbt" ( ["",{"desc":"str","range":n or [n,n]},...] )
 enumeratesigners
 
 == Util ==
-createmultisig nrequired ["key",...] ( "address_type" )
-deriveaddresses "descriptor" ( range )
+> createmultisig nrequired ["key",...] ( "address_type" )
+> deriveaddresses "descriptor" ( range )
 estimatesmartfee conf_target ( "estimate_mode" )
-getdescriptorinfo "descriptor"
-getindexinfo ( "index_name" )
-signmessagewithprivkey "privkey" "message"
-validateaddress "address"
-verifymessage "address" "signature" "message"
+> getdescriptorinfo "descriptor"
+> getindexinfo ( "index_name" )
+> signmessagewithprivkey "privkey" "message"
+> validateaddress "address"
+> verifymessage "address" "signature" "message"
 
 == Wallet ==
 abandontransaction "txid"
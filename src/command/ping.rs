@@ -0,0 +1,46 @@
+/*
+ping
+
+Requests that a ping be sent to all other nodes, to measure ping time.
+Results provided in getpeerinfo, pingtime and pingwait fields are decimal seconds.
+Ping command is handled in queue with all other commands, so it measures processing backlog, not just network ping.
+
+Result:
+null    (json null)
+
+Examples:
+> bitcoin-cli ping
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "ping", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const PING_COMMAND: &str = "ping";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PingCommandResponse;
+
+pub struct PingCommand {}
+impl PingCommand {
+    pub fn new() -> Self {
+        PingCommand {}
+    }
+}
+impl Default for PingCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for PingCommand {
+    type Response = PingCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let params = vec![];
+        let r = request(client, PING_COMMAND, params)?;
+        let response: PingCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
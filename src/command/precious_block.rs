@@ -0,0 +1,54 @@
+/*
+preciousblock "blockhash"
+
+Treats a block as if it were received before others with the same work.
+
+A later preciousblock call can override the effect of an earlier one.
+
+The effects of preciousblock are not retained across restarts.
+
+Arguments:
+1. blockhash    (string, required) the hash of the block to mark as precious
+
+Result:
+null    (json null)
+
+Examples:
+> bitcoin-cli preciousblock "blockhash"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "preciousblock", "params": ["blockhash"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::Blockhash;
+
+const PRECIOUS_BLOCK_COMMAND: &str = "preciousblock";
+
+pub struct PreciousBlockCommand {
+    blockhash: Blockhash, // (string, required) the hash of the block to mark as precious
+}
+impl PreciousBlockCommand {
+    pub fn new(blockhash: Blockhash) -> Self {
+        PreciousBlockCommand { blockhash }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PreciousBlockCommandResponse;
+
+impl CallableCommand for PreciousBlockCommand {
+    type Response = PreciousBlockCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let blockhash_arg_raw_value = to_raw_value(&self.blockhash.0).unwrap();
+        let params = vec![blockhash_arg_raw_value];
+        let r = request(client, PRECIOUS_BLOCK_COMMAND, params)?;
+        let response: PreciousBlockCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
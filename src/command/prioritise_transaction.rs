@@ -0,0 +1,65 @@
+/*
+prioritisetransaction "txid" ( dummy ) fee_delta
+
+Accepts the transaction into mined blocks at a higher (or lower) priority
+
+Arguments:
+1. txid         (string, required) The transaction id.
+2. dummy        (numeric, optional) API-Compatibility for previous API. Must be zero or null.
+                DEPRECATED. For forward compatibility use named arguments and omit this parameter.
+3. fee_delta    (numeric, required) The fee value (in satoshis) to add (or subtract, if negative).
+                Note, that this value is not a fee rate. It is a value to modify absolute fee of the TX.
+                The fee is not actually paid, only the algorithm for selecting transactions into a block
+                considers the transaction as it would have paid a higher (or lower) fee.
+
+Result:
+true|false    (boolean) Returns true
+
+Examples:
+> bitcoin-cli prioritisetransaction "txid" 0.0 10000
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "prioritisetransaction", "params": ["txid", 0.0, 10000]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::types::transaction::Txid;
+
+const PRIORITISE_TRANSACTION_COMMAND: &str = "prioritisetransaction";
+
+pub struct PrioritiseTransactionCommand {
+    txid: Txid,     // (string, required) The transaction id.
+    fee_delta: i64, // (numeric, required) The fee value (in satoshis) to add (or subtract, if negative).
+}
+impl PrioritiseTransactionCommand {
+    pub fn new(txid: Txid, fee_delta: i64) -> Self {
+        PrioritiseTransactionCommand { txid, fee_delta }
+    }
+}
+
+// Returns true
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PrioritiseTransactionCommandResponse(pub bool);
+
+impl CallableCommand for PrioritiseTransactionCommand {
+    type Response = PrioritiseTransactionCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let txid_arg_raw_value = to_raw_value(&self.txid).unwrap();
+        // The deprecated dummy argument must be zero.
+        let dummy_arg_raw_value = to_raw_value(&0).unwrap();
+        let fee_delta_arg_raw_value = to_raw_value(&self.fee_delta).unwrap();
+        let params = vec![
+            txid_arg_raw_value,
+            dummy_arg_raw_value,
+            fee_delta_arg_raw_value,
+        ];
+        let r = request(client, PRIORITISE_TRANSACTION_COMMAND, params)?;
+        let response: PrioritiseTransactionCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
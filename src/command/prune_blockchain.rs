@@ -0,0 +1,89 @@
+/*
+pruneblockchain height
+
+Attempts to delete block and undo data up to a specified height or timestamp, if eligible for pruning.
+Requires `-prune` to be enabled at startup. While pruned data may be re-fetched in some cases (e.g., via `getblockfrompeer`), local deletion is irreversible.
+
+Arguments:
+1. height    (numeric, required) The block height to prune up to. May be set to a discrete height, or to a UNIX epoch time
+             to prune blocks whose block time is at least 2 hours older than the provided timestamp.
+
+Result:
+n    (numeric) Height of the last block pruned
+
+Examples:
+> bitcoin-cli pruneblockchain 1000
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "pruneblockchain", "params": [1000]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const PRUNE_BLOCKCHAIN_COMMAND: &str = "pruneblockchain";
+// bitcoind treats arguments above this as timestamps rather than heights.
+const MIN_TIMESTAMP: u64 = 1_000_000_000;
+
+pub enum PruneBlockchainTarget {
+    Height(u64), // Prune up to this block height
+    Time(u64), // Prune blocks whose block time is at least 2 hours older than this UNIX epoch time
+}
+
+pub struct PruneBlockchainCommand {
+    target: PruneBlockchainTarget, // (numeric, required) The block height to prune up to, or a UNIX epoch time
+}
+impl PruneBlockchainCommand {
+    pub fn new(target: PruneBlockchainTarget) -> Self {
+        PruneBlockchainCommand { target }
+    }
+    fn height_arg(&self) -> u64 {
+        match self.target {
+            PruneBlockchainTarget::Height(height) => height,
+            // Timestamps too small to be told apart from heights would prune up to that height.
+            PruneBlockchainTarget::Time(time) => time.max(MIN_TIMESTAMP + 1),
+        }
+    }
+}
+
+// Height of the last block pruned
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PruneBlockchainCommandResponse(pub u64);
+
+impl CallableCommand for PruneBlockchainCommand {
+    type Response = PruneBlockchainCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let height_arg_raw_value = to_raw_value(&self.height_arg()).unwrap();
+        let params = vec![height_arg_raw_value];
+        let r = request(client, PRUNE_BLOCKCHAIN_COMMAND, params)?;
+        let response: PruneBlockchainCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn height_arg(target: PruneBlockchainTarget) -> u64 {
+        PruneBlockchainCommand::new(target).height_arg()
+    }
+
+    #[test]
+    fn small_timestamps_are_still_sent_as_timestamps() {
+        assert_eq!(height_arg(PruneBlockchainTarget::Time(0)), 1_000_000_001);
+        assert_eq!(
+            height_arg(PruneBlockchainTarget::Time(1_000_000_000)),
+            1_000_000_001
+        );
+        assert_eq!(
+            height_arg(PruneBlockchainTarget::Time(1_700_000_000)),
+            1_700_000_000
+        );
+        assert_eq!(height_arg(PruneBlockchainTarget::Height(1000)), 1000);
+    }
+}
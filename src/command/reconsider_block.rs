@@ -0,0 +1,51 @@
+/*
+reconsiderblock "blockhash"
+
+Removes invalidity status of a block, its ancestors and its descendants, reconsider them for activation.
+This can be used to undo the effects of invalidateblock.
+
+Arguments:
+1. blockhash    (string, required) the hash of the block to reconsider
+
+Result:
+null    (json null)
+
+Examples:
+> bitcoin-cli reconsiderblock "blockhash"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "reconsiderblock", "params": ["blockhash"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::Blockhash;
+
+const RECONSIDER_BLOCK_COMMAND: &str = "reconsiderblock";
+
+pub struct ReconsiderBlockCommand {
+    blockhash: Blockhash, // (string, required) the hash of the block to reconsider
+}
+impl ReconsiderBlockCommand {
+    pub fn new(blockhash: Blockhash) -> Self {
+        ReconsiderBlockCommand { blockhash }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReconsiderBlockCommandResponse;
+
+impl CallableCommand for ReconsiderBlockCommand {
+    type Response = ReconsiderBlockCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let blockhash_arg_raw_value = to_raw_value(&self.blockhash.0).unwrap();
+        let params = vec![blockhash_arg_raw_value];
+        let r = request(client, RECONSIDER_BLOCK_COMMAND, params)?;
+        let response: ReconsiderBlockCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
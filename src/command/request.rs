@@ -2,14 +2,57 @@
 use crate::client::Client;
 use jsonrpc::Response;
 use serde_json::value::RawValue;
+use std::time::Duration;
 
 // Note: Callers must convert their paramaters to a serde_json Raw value:
 //    let blockhash = "839832983298"
 //    let blockhash_arg_raw_value = to_raw_value(&blockhash).unwrap();
 //    let params = vec![blockhash_arg_raw_value]
-pub fn request(client: &Client, command: &str, params: Vec<Box<RawValue>>) -> Response {
+pub fn request(
+    client: &Client,
+    command: &str,
+    params: Vec<Box<RawValue>>,
+) -> Result<Response, jsonrpc::Error> {
     let request = client.build_request(command, &params);
-    let error_message = format!("{}_failed", command);
-    let response = client.send_request(request).expect(&error_message);
-    response
+    client.send_request(request)
+}
+
+// Calls the same command once per set of params, in a single batch. Fails if the node leaves any
+// of them unanswered; errors of individual calls are left in their responses.
+pub fn request_batch(
+    client: &Client,
+    command: &str,
+    params: &[Vec<Box<RawValue>>],
+) -> Result<Vec<Response>, jsonrpc::Error> {
+    if params.is_empty() {
+        return Ok(vec![]);
+    }
+    let requests = params
+        .iter()
+        .map(|params| client.build_request(command, params))
+        .collect();
+    client
+        .send_batch(requests)?
+        .into_iter()
+        .map(|response| response.ok_or(jsonrpc::Error::WrongBatchResponseSize))
+        .collect()
+}
+
+// A timeout argument in milliseconds for the wait commands. bitcoind reads 0 as no timeout, so
+// anything under a millisecond, zero included, is rounded up to 1.
+pub fn timeout_millis(timeout: Duration) -> u64 {
+    timeout.as_millis().clamp(1, u64::MAX as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeout_millis_never_sends_no_timeout() {
+        assert_eq!(timeout_millis(Duration::ZERO), 1);
+        assert_eq!(timeout_millis(Duration::from_micros(300)), 1);
+        assert_eq!(timeout_millis(Duration::from_millis(1500)), 1500);
+        assert_eq!(timeout_millis(Duration::MAX), u64::MAX);
+    }
 }
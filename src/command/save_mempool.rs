@@ -0,0 +1,55 @@
+/*
+savemempool
+
+Dumps the mempool to disk. It will fail until the previous dump is fully loaded.
+
+Result:
+{                      (json object)
+  "filename" : "str"   (string) the directory and file where the mempool was saved
+}
+
+Examples:
+> bitcoin-cli savemempool
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "savemempool", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const SAVE_MEMPOOL_COMMAND: &str = "savemempool";
+
+pub struct SaveMempoolCommand {}
+impl SaveMempoolCommand {
+    pub fn new() -> Self {
+        SaveMempoolCommand {}
+    }
+}
+impl Default for SaveMempoolCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SavedMempool {
+    pub filename: String, // the directory and file where the mempool was saved
+}
+
+// Older versions of bitcoin core return null.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SaveMempoolCommandResponse(pub Option<SavedMempool>);
+
+impl CallableCommand for SaveMempoolCommand {
+    type Response = SaveMempoolCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let params = vec![];
+        let r = request(client, SAVE_MEMPOOL_COMMAND, params)?;
+        let response: SaveMempoolCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
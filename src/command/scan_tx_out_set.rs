@@ -0,0 +1,238 @@
+/*
+scantxoutset "action" ( [scanobjects,...] )
+
+Scans the unspent transaction output set for entries that match certain output descriptors.
+Examples of output descriptors are:
+    addr(<address>)                      Outputs whose output script corresponds to the specified address (does not include P2PK)
+    raw(<hex script>)                    Outputs whose output script equals the specified hex-encoded bytes
+    combo(<pubkey>)                      P2PK, P2PKH, P2WPKH, and P2SH-P2WPKH outputs for the given pubkey
+    pkh(<pubkey>)                        P2PKH outputs for the given pubkey
+    sh(multi(<n>,<pubkey>,<pubkey>,...)) P2SH-multisig outputs for the given threshold and pubkeys
+    tr(<pubkey>)                         P2TR
+    tr(<pubkey>,{pk(<pubkey>)})          P2TR with single fallback pubkey in tapscript
+    rawtr(<pubkey>)                      P2TR with the specified key as output key rather than inner
+    wsh(and_v(v:pk(<pubkey>),after(2)))  P2WSH miniscript with mandatory pubkey and a timelock
+
+In the above, <pubkey> either refers to a fixed public key in hexadecimal notation, or to an xpub/xprv optionally followed by one
+or more path elements separated by "/", and optionally ending in "/\*" (unhardened), or "/\*'" or "/\*h" (hardened) to specify all
+unhardened or hardened child keys.
+In the latter case, a range needs to be specified by below if different from 1000.
+For more information on output descriptors, see the documentation in the doc/descriptors.md file.
+
+Arguments:
+1. action                        (string, required) The action to execute
+                                 "start" for starting a scan
+                                 "abort" for aborting the current scan (returns true when abort was successful)
+                                 "status" for progress report (in %) of the current scan
+2. scanobjects                   (json array, optional) Array of scan objects. Required for "start" action
+                                 Every scan object is either a string descriptor or an object:
+     [
+       "descriptor",             (string) An output descriptor
+       {                         (json object) An object with output descriptor and metadata
+         "desc": "str",          (string, required) An output descriptor
+         "range": n or [n,n],    (numeric or array, optional, default=1000) The range of HD chain indexes to explore (either end or [begin,end])
+       },
+       ...
+     ]
+
+Result (when action=='start'; only returns after scan completes):
+{                                (json object)
+  "success" : true|false,        (boolean) Whether the scan was completed
+  "txouts" : n,                  (numeric) The number of unspent transaction outputs scanned
+  "height" : n,                  (numeric) The block height at which the scan was done
+  "bestblock" : "hex",           (string) The hash of the block at the tip of the chain
+  "unspents" : [                 (json array)
+    {                            (json object)
+      "txid" : "hex",            (string) The transaction id
+      "vout" : n,                (numeric) The vout value
+      "scriptPubKey" : "hex",    (string) The output script
+      "desc" : "str",            (string) A specialized descriptor for the matched output script
+      "amount" : n,              (numeric) The total amount in BTC of the unspent output
+      "coinbase" : true|false,   (boolean) Whether this is a coinbase output
+      "height" : n,              (numeric) Height of the unspent transaction output
+      "blockhash" : "hex"        (string) Blockhash of the unspent transaction output
+    },
+    ...
+  ],
+  "total_amount" : n             (numeric) The total amount of all found unspent outputs in BTC
+}
+
+Result (when action=='abort'):
+true|false    (boolean) True if scan will be aborted (not necessarily before this RPC returns), or false if there is no scan to abort
+
+Result (when action=='status' and a scan is currently in progress):
+{                    (json object)
+  "progress" : n     (numeric) Approximate percent complete
+}
+
+Result (when action=='status' and no scan is in progress - possibly already completed):
+null    (json null)
+
+Examples:
+> bitcoin-cli scantxoutset start '["raw(76a91411b366edfc0a8b66feebae5c2e25a7b6a5d1cf3188ac)#fm24fxxy"]'
+> bitcoin-cli scantxoutset status
+> bitcoin-cli scantxoutset abort
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "scantxoutset", "params": ["start", ["raw(76a91411b366edfc0a8b66feebae5c2e25a7b6a5d1cf3188ac)#fm24fxxy"]]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::value::to_raw_value;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::client::Client;
+use crate::command::derive_addresses::DescriptorRange;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::types::transaction::Txid;
+use crate::Blockhash;
+
+const SCAN_TX_OUT_SET_COMMAND: &str = "scantxoutset";
+// How often scan_with_progress checks whether the scan has finished between status polls.
+const SCAN_FINISHED_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+pub enum ScanTxOutSetAction {
+    Start(Vec<ScanObject>), // Scan for outputs matching the scan objects. Returns after the scan completes.
+    Abort,                  // Abort the current scan
+    Status,                 // Progress report (in %) of the current scan
+}
+
+// An output descriptor to scan for. Ranged descriptors (ending in "/*") are derived from index 0
+// to 1000 unless a range is given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanObject {
+    Descriptor(String),
+    RangedDescriptor {
+        desc: String,
+        range: DescriptorRange,
+    },
+}
+
+impl Serialize for ScanObject {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct RangedDescriptor<'a> {
+            desc: &'a str,
+            range: &'a DescriptorRange,
+        }
+        match self {
+            ScanObject::Descriptor(desc) => serializer.serialize_str(desc),
+            ScanObject::RangedDescriptor { desc, range } => {
+                RangedDescriptor { desc, range }.serialize(serializer)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScanUnspent {
+    pub txid: Txid, // The transaction id
+    pub vout: u32,  // The vout value
+    #[serde(rename = "scriptPubKey")]
+    pub script_pub_key: String, // The output script
+    pub desc: String, // A specialized descriptor for the matched output script
+    pub amount: f64, // The total amount in BTC of the unspent output
+    pub coinbase: Option<bool>, // Whether this is a coinbase output (only returned by newer versions of bitcoin core)
+    pub height: u64,            // Height of the unspent transaction output
+    pub blockhash: Option<Blockhash>, // Blockhash of the unspent transaction output (only returned by newer versions of bitcoin core)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScanTxOutSetResult {
+    pub success: bool, // Whether the scan was completed. False if it was aborted.
+    pub txouts: u64,   // The number of unspent transaction outputs scanned
+    pub height: u64,   // The block height at which the scan was done
+    pub bestblock: Blockhash, // The hash of the block at the tip of the chain
+    pub unspents: Vec<ScanUnspent>,
+    pub total_amount: f64, // The total amount of all found unspent outputs in BTC
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ScanProgress {
+    pub progress: f64, // Approximate percent complete
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ScanTxOutSetCommandResponse {
+    Completed(Box<ScanTxOutSetResult>), // action=start
+    InProgress(ScanProgress),           // action=status while a scan is running
+    Aborted(bool), // action=abort: true if the scan will be aborted, false if there was no scan to abort
+    NoScanInProgress(()), // action=status otherwise, possibly because the scan already completed
+}
+
+impl ScanTxOutSetCommandResponse {
+    pub fn progress(&self) -> Option<f64> {
+        match self {
+            ScanTxOutSetCommandResponse::InProgress(status) => Some(status.progress),
+            _ => None,
+        }
+    }
+}
+
+pub struct ScanTxOutSetCommand {
+    action: ScanTxOutSetAction, // (string, required) The action to execute
+}
+impl ScanTxOutSetCommand {
+    pub fn new(action: ScanTxOutSetAction) -> Self {
+        ScanTxOutSetCommand { action }
+    }
+}
+
+impl CallableCommand for ScanTxOutSetCommand {
+    type Response = ScanTxOutSetCommandResponse;
+    // Starting or aborting a scan changes the node's scan state.
+    fn is_idempotent(&self) -> bool {
+        matches!(self.action, ScanTxOutSetAction::Status)
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let action_arg = match self.action {
+            ScanTxOutSetAction::Start(_) => "start",
+            ScanTxOutSetAction::Abort => "abort",
+            ScanTxOutSetAction::Status => "status",
+        };
+        let action_arg_raw_value = to_raw_value(action_arg).unwrap();
+        let mut params = vec![action_arg_raw_value];
+        if let ScanTxOutSetAction::Start(scan_objects) = &self.action {
+            let scan_objects_arg_raw_value = to_raw_value(scan_objects).unwrap();
+            params.push(scan_objects_arg_raw_value);
+        }
+        let r = request(client, SCAN_TX_OUT_SET_COMMAND, params)?;
+        let response: ScanTxOutSetCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
+
+// Runs a scan on another thread and calls on_progress with the percentage from a status poll
+// every poll_interval until it completes. The scan call blocks until the whole UTXO set has been
+// scanned, which can take minutes, so the client's timeout has to allow for it (see
+// Client::with_timeout). Only one scan can run on a node at a time.
+pub fn scan_with_progress<F: FnMut(f64)>(
+    client: &Client,
+    scan_objects: Vec<ScanObject>,
+    poll_interval: Duration,
+    mut on_progress: F,
+) -> Result<ScanTxOutSetResult, jsonrpc::Error> {
+    let command = ScanTxOutSetCommand::new(ScanTxOutSetAction::Start(scan_objects));
+    let response = thread::scope(|scope| {
+        let scan = scope.spawn(|| command.call(client));
+        let mut next_poll = Instant::now() + poll_interval;
+        while !scan.is_finished() {
+            if Instant::now() >= next_poll {
+                let status = ScanTxOutSetCommand::new(ScanTxOutSetAction::Status).call(client);
+                // A failed status poll only means a missed progress report.
+                if let Some(progress) = status.ok().and_then(|status| status.progress()) {
+                    on_progress(progress);
+                }
+                next_poll = Instant::now() + poll_interval;
+            }
+            thread::sleep(SCAN_FINISHED_CHECK_INTERVAL.min(poll_interval));
+        }
+        scan.join().expect("scantxoutset thread panicked")
+    })?;
+    match response {
+        ScanTxOutSetCommandResponse::Completed(result) => Ok(*result),
+        _ => Err(jsonrpc::Error::Json(serde::de::Error::custom(
+            "scantxoutset start didn't return a scan result",
+        ))),
+    }
+}
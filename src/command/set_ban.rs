@@ -0,0 +1,223 @@
+/*
+setban "subnet" "command" ( bantime absolute )
+
+Attempts to add or remove an IP/Subnet from the banned list.
+
+Arguments:
+1. subnet      (string, required) The IP/Subnet (see getpeerinfo for nodes IP) with an optional netmask (default is /32 = single IP)
+2. command     (string, required) 'add' to add an IP/Subnet to the list, 'remove' to remove an IP/Subnet from the list
+3. bantime     (numeric, optional, default=0) time in seconds how long (or until when if [absolute] is set) the IP is banned (0 or empty means using the default time of 24h which can also be overwritten by the -bantime startup argument)
+4. absolute    (boolean, optional, default=false) If set, the bantime must be an absolute timestamp expressed in UNIX epoch time
+
+Result:
+null    (json null)
+
+Examples:
+> bitcoin-cli setban "192.168.0.6" "add" 86400
+> bitcoin-cli setban "192.168.0.0/24" "add"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "setban", "params": ["192.168.0.6", "add", 86400]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::to_raw_value;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const SET_BAN_COMMAND: &str = "setban";
+
+// A banned IP range, or a single onion, i2p or cjdns address (which can't be banned as a range).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Subnet {
+    Ip { network: IpAddr, prefix_len: u8 },
+    Host(String),
+}
+
+impl Subnet {
+    // The range of addresses sharing the first `prefix_len` bits of `address`. Like core, the
+    // address is masked down to the start of the range.
+    pub fn ip(address: IpAddr, prefix_len: u8) -> Result<Self, String> {
+        let network = match address {
+            IpAddr::V4(address) => {
+                if prefix_len > 32 {
+                    return Err(format!("invalid IPv4 prefix length: {}", prefix_len));
+                }
+                let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+                IpAddr::V4(Ipv4Addr::from(u32::from(address) & mask))
+            }
+            IpAddr::V6(address) => {
+                if prefix_len > 128 {
+                    return Err(format!("invalid IPv6 prefix length: {}", prefix_len));
+                }
+                let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+                IpAddr::V6(Ipv6Addr::from(u128::from(address) & mask))
+            }
+        };
+        Ok(Subnet::Ip {
+            network,
+            prefix_len,
+        })
+    }
+    // A subnet containing just this address.
+    pub fn single(address: IpAddr) -> Self {
+        let prefix_len = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        Subnet::Ip {
+            network: address,
+            prefix_len,
+        }
+    }
+    // The subnet for a peer's "addr" from getpeerinfo ("host:port"), so the peer can be banned.
+    pub fn from_peer_addr(addr: &str) -> Result<Self, String> {
+        if let Ok(socket_address) = addr.parse::<SocketAddr>() {
+            return Ok(Subnet::single(socket_address.ip()));
+        }
+        let host = match addr.rsplit_once(':') {
+            Some((host, port)) if port.parse::<u16>().is_ok() => host,
+            _ => addr,
+        };
+        host.parse()
+    }
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        match self {
+            Subnet::Ip { prefix_len, .. } => Subnet::ip(*address, *prefix_len).as_ref() == Ok(self),
+            Subnet::Host(_) => false,
+        }
+    }
+}
+
+// Netmasks like 255.255.255.0 are converted to a prefix length, as long as they're contiguous.
+fn parse_prefix_len(suffix: &str) -> Result<u8, String> {
+    if let Ok(prefix_len) = suffix.parse::<u8>() {
+        return Ok(prefix_len);
+    }
+    let ones = match suffix.parse::<IpAddr>() {
+        Ok(IpAddr::V4(mask)) => {
+            let mask = u32::from(mask);
+            (mask.leading_ones(), mask.count_ones())
+        }
+        Ok(IpAddr::V6(mask)) => {
+            let mask = u128::from(mask);
+            (mask.leading_ones(), mask.count_ones())
+        }
+        Err(_) => return Err(format!("invalid netmask: {}", suffix)),
+    };
+    match ones {
+        (leading, total) if leading == total => Ok(leading as u8),
+        _ => Err(format!("non-contiguous netmask: {}", suffix)),
+    }
+}
+
+impl FromStr for Subnet {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, suffix) = match s.split_once('/') {
+            Some((address, suffix)) => (address, Some(suffix)),
+            None => (s, None),
+        };
+        match address.parse::<IpAddr>() {
+            Ok(address) => match suffix {
+                Some(suffix) => Subnet::ip(address, parse_prefix_len(suffix)?),
+                None => Ok(Subnet::single(address)),
+            },
+            Err(_) if address.is_empty() => Err(format!("invalid subnet: {}", s)),
+            // Onion, i2p and cjdns addresses are only banned individually.
+            Err(_) => match suffix {
+                None => Ok(Subnet::Host(address.to_string())),
+                Some(_) => Err(format!("invalid subnet: {}", s)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Subnet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Subnet::Ip {
+                network,
+                prefix_len,
+            } => write!(f, "{}/{}", network, prefix_len),
+            Subnet::Host(host) => write!(f, "{}", host),
+        }
+    }
+}
+
+impl Serialize for Subnet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Subnet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let subnet = String::deserialize(deserializer)?;
+        subnet.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+pub enum BanDuration {
+    Default,       // 24h, unless overwritten by the -bantime startup argument
+    For(Duration), // banned for this long, to the second
+    Until(u64),    // banned until this UNIX epoch time
+}
+
+pub enum SetBanCommandArg {
+    Add,    // add an IP/Subnet to the banned list
+    Remove, // remove an IP/Subnet from the banned list
+}
+
+pub struct SetBanCommand {
+    subnet: Subnet,            // (string, required) The IP/Subnet with an optional netmask
+    command: SetBanCommandArg, // (string, required) Whether to add or remove the IP/Subnet
+    ban_duration: BanDuration, // How long (or until when) the IP/Subnet is banned. Ignored for 'remove'
+}
+impl SetBanCommand {
+    pub fn new(subnet: Subnet, command: SetBanCommandArg) -> Self {
+        SetBanCommand {
+            subnet,
+            command,
+            ban_duration: BanDuration::Default,
+        }
+    }
+    pub fn set_ban_duration(mut self, ban_duration: BanDuration) -> Self {
+        self.ban_duration = ban_duration;
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetBanCommandResponse;
+
+impl CallableCommand for SetBanCommand {
+    type Response = SetBanCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let command_arg = match self.command {
+            SetBanCommandArg::Add => "add",
+            SetBanCommandArg::Remove => "remove",
+        };
+        let subnet_arg_raw_value = to_raw_value(&self.subnet).unwrap();
+        let command_arg_raw_value = to_raw_value(command_arg).unwrap();
+        let mut params = vec![subnet_arg_raw_value, command_arg_raw_value];
+        if let SetBanCommandArg::Add = self.command {
+            let (bantime_arg, absolute_arg) = match &self.ban_duration {
+                BanDuration::Default => (0, false),
+                BanDuration::For(duration) => (duration.as_secs(), false),
+                BanDuration::Until(timestamp) => (*timestamp, true),
+            };
+            params.push(to_raw_value(&bantime_arg).unwrap());
+            params.push(to_raw_value(&absolute_arg).unwrap());
+        }
+        let r = request(client, SET_BAN_COMMAND, params)?;
+        let response: SetBanCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,49 @@
+/*
+setnetworkactive state
+
+Disable/enable all p2p network activity.
+
+Arguments:
+1. state    (boolean, required) true to enable networking, false to disable
+
+Result:
+true|false    (boolean) The value that was passed in
+
+Examples:
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "setnetworkactive", "params": [true]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const SET_NETWORK_ACTIVE_COMMAND: &str = "setnetworkactive";
+
+pub struct SetNetworkActiveCommand {
+    state: bool, // (boolean, required) true to enable networking, false to disable
+}
+impl SetNetworkActiveCommand {
+    pub fn new(state: bool) -> Self {
+        SetNetworkActiveCommand { state }
+    }
+}
+
+// The value that was passed in
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetNetworkActiveCommandResponse(pub bool);
+
+impl CallableCommand for SetNetworkActiveCommand {
+    type Response = SetNetworkActiveCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let state_arg_raw_value = to_raw_value(&self.state).unwrap();
+        let params = vec![state_arg_raw_value];
+        let r = request(client, SET_NETWORK_ACTIVE_COMMAND, params)?;
+        let response: SetNetworkActiveCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,56 @@
+/*
+signmessagewithprivkey "privkey" "message"
+
+Sign a message with the private key of an address
+
+Arguments:
+1. privkey    (string, required) The private key to sign the message with.
+2. message    (string, required) The message to create a signature of.
+
+Result:
+"str"    (string) The signature of the message encoded in base 64
+
+Examples:
+Create the signature
+> bitcoin-cli signmessagewithprivkey "privkey" "my message"
+Verify the signature
+> bitcoin-cli verifymessage "1D1ZrZNe3JUo7ZycKEYQQiQAWd9y54F4XX" "signature" "my message"
+As a JSON-RPC call
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "signmessagewithprivkey", "params": ["privkey", "my message"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const SIGN_MESSAGE_WITH_PRIV_KEY_COMMAND: &str = "signmessagewithprivkey";
+
+pub struct SignMessageWithPrivKeyCommand {
+    privkey: String, // (string, required) The private key to sign the message with.
+    message: String, // (string, required) The message to create a signature of.
+}
+impl SignMessageWithPrivKeyCommand {
+    // The private key is sent to the node in the clear, so only use this with a trusted node over
+    // a trusted connection.
+    pub fn new(privkey: String, message: String) -> Self {
+        SignMessageWithPrivKeyCommand { privkey, message }
+    }
+}
+
+// The signature of the message encoded in base 64
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SignMessageWithPrivKeyCommandResponse(pub String);
+
+impl CallableCommand for SignMessageWithPrivKeyCommand {
+    type Response = SignMessageWithPrivKeyCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let privkey_arg_raw_value = to_raw_value(&self.privkey).unwrap();
+        let message_arg_raw_value = to_raw_value(&self.message).unwrap();
+        let params = vec![privkey_arg_raw_value, message_arg_raw_value];
+        let r = request(client, SIGN_MESSAGE_WITH_PRIV_KEY_COMMAND, params)?;
+        let response: SignMessageWithPrivKeyCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
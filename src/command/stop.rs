@@ -0,0 +1,44 @@
+/*
+stop
+
+Request a graceful shutdown of Bitcoin Core.
+
+Result:
+"str"    (string) A string with the content 'Bitcoin Core stopping'
+*/
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const STOP_COMMAND: &str = "stop";
+
+// A string with the content 'Bitcoin Core stopping'
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StopCommandResponse(pub String);
+
+pub struct StopCommand {}
+impl StopCommand {
+    pub fn new() -> Self {
+        StopCommand {}
+    }
+}
+impl Default for StopCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for StopCommand {
+    type Response = StopCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let params = vec![];
+        let r = request(client, STOP_COMMAND, params)?;
+        let response: StopCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,214 @@
+/*
+submitblock "hexdata" ( "dummy" )
+
+Attempts to submit new block to network.
+See https://en.bitcoin.it/wiki/BIP_0022 for full specification.
+
+Arguments:
+1. hexdata    (string, required) the hex-encoded block data to submit
+2. dummy      (string, optional, default=ignored) dummy value, for compatibility with BIP22. This value is ignored.
+
+Result (If the block was accepted):
+null    (json null)
+
+Result (Otherwise):
+"str"    (string) According to BIP22
+
+Examples:
+> bitcoin-cli submitblock "mydata"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "submitblock", "params": ["mydata"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::to_raw_value;
+use std::fmt;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const SUBMIT_BLOCK_COMMAND: &str = "submitblock";
+
+// Why bitcoind rejected a block, from submitblock or a getblocktemplate proposal. These are the
+// BIP22 strings and bitcoin core's block validation reject reasons.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BlockRejectionReason {
+    Rejected,                     // "rejected": no specific reason given
+    HighHash,                     // "high-hash": proof of work failed
+    BadDiffBits,                  // "bad-diffbits": incorrect proof of work
+    TimeTooOld,                   // "time-too-old": block's timestamp is too early
+    TimeTooNew,                   // "time-too-new": block timestamp too far in the future
+    TimeTimewarpAttack, // "time-timewarp-attack": block's timestamp is too early on diff adjustment block
+    BadVersion(String), // "bad-version(0x...)": rejected nVersion block
+    BadPrevBlock,       // "bad-prevblk": previous block is invalid
+    PrevBlockNotFound,  // "prev-blk-not-found"
+    InconclusiveNotBestPrevBlock, // "inconclusive-not-best-prevblk": proposal doesn't build on the tip
+    BadMerkleRoot,                // "bad-txnmrklroot": hashMerkleRoot mismatch
+    BadTxnsDuplicate,             // "bad-txns-duplicate": duplicate transaction
+    BadBlockLength,               // "bad-blk-length": size limits failed
+    BadBlockWeight,               // "bad-blk-weight": weight limit failed
+    BadBlockSigops,               // "bad-blk-sigops": out-of-bounds SigOpCount
+    BadCoinbaseMissing,           // "bad-cb-missing": first tx is not coinbase
+    BadCoinbaseMultiple,          // "bad-cb-multiple": more than one coinbase
+    BadCoinbaseHeight,            // "bad-cb-height": block height mismatch in coinbase
+    BadCoinbaseAmount,            // "bad-cb-amount": coinbase pays too much
+    BadTxnsNonFinal,              // "bad-txns-nonfinal": contains a non-final transaction
+    BadWitnessNonceSize,          // "bad-witness-nonce-size"
+    BadWitnessMerkleMatch,        // "bad-witness-merkle-match": witness merkle commitment mismatch
+    UnexpectedWitness,            // "unexpected-witness": witness data without a commitment
+    Other(String),                // Any other reject reason, e.g. from transaction validation
+}
+
+impl BlockRejectionReason {
+    pub fn as_str(&self) -> &str {
+        match self {
+            BlockRejectionReason::Rejected => "rejected",
+            BlockRejectionReason::HighHash => "high-hash",
+            BlockRejectionReason::BadDiffBits => "bad-diffbits",
+            BlockRejectionReason::TimeTooOld => "time-too-old",
+            BlockRejectionReason::TimeTooNew => "time-too-new",
+            BlockRejectionReason::TimeTimewarpAttack => "time-timewarp-attack",
+            BlockRejectionReason::BadVersion(reason) => reason,
+            BlockRejectionReason::BadPrevBlock => "bad-prevblk",
+            BlockRejectionReason::PrevBlockNotFound => "prev-blk-not-found",
+            BlockRejectionReason::InconclusiveNotBestPrevBlock => "inconclusive-not-best-prevblk",
+            BlockRejectionReason::BadMerkleRoot => "bad-txnmrklroot",
+            BlockRejectionReason::BadTxnsDuplicate => "bad-txns-duplicate",
+            BlockRejectionReason::BadBlockLength => "bad-blk-length",
+            BlockRejectionReason::BadBlockWeight => "bad-blk-weight",
+            BlockRejectionReason::BadBlockSigops => "bad-blk-sigops",
+            BlockRejectionReason::BadCoinbaseMissing => "bad-cb-missing",
+            BlockRejectionReason::BadCoinbaseMultiple => "bad-cb-multiple",
+            BlockRejectionReason::BadCoinbaseHeight => "bad-cb-height",
+            BlockRejectionReason::BadCoinbaseAmount => "bad-cb-amount",
+            BlockRejectionReason::BadTxnsNonFinal => "bad-txns-nonfinal",
+            BlockRejectionReason::BadWitnessNonceSize => "bad-witness-nonce-size",
+            BlockRejectionReason::BadWitnessMerkleMatch => "bad-witness-merkle-match",
+            BlockRejectionReason::UnexpectedWitness => "unexpected-witness",
+            BlockRejectionReason::Other(reason) => reason,
+        }
+    }
+}
+
+impl From<&str> for BlockRejectionReason {
+    fn from(reason: &str) -> Self {
+        match reason {
+            "rejected" => BlockRejectionReason::Rejected,
+            "high-hash" => BlockRejectionReason::HighHash,
+            "bad-diffbits" => BlockRejectionReason::BadDiffBits,
+            "time-too-old" => BlockRejectionReason::TimeTooOld,
+            "time-too-new" => BlockRejectionReason::TimeTooNew,
+            "time-timewarp-attack" => BlockRejectionReason::TimeTimewarpAttack,
+            "bad-prevblk" => BlockRejectionReason::BadPrevBlock,
+            "prev-blk-not-found" => BlockRejectionReason::PrevBlockNotFound,
+            "inconclusive-not-best-prevblk" => BlockRejectionReason::InconclusiveNotBestPrevBlock,
+            "bad-txnmrklroot" => BlockRejectionReason::BadMerkleRoot,
+            "bad-txns-duplicate" => BlockRejectionReason::BadTxnsDuplicate,
+            "bad-blk-length" => BlockRejectionReason::BadBlockLength,
+            "bad-blk-weight" => BlockRejectionReason::BadBlockWeight,
+            "bad-blk-sigops" => BlockRejectionReason::BadBlockSigops,
+            "bad-cb-missing" => BlockRejectionReason::BadCoinbaseMissing,
+            "bad-cb-multiple" => BlockRejectionReason::BadCoinbaseMultiple,
+            "bad-cb-height" => BlockRejectionReason::BadCoinbaseHeight,
+            "bad-cb-amount" => BlockRejectionReason::BadCoinbaseAmount,
+            "bad-txns-nonfinal" => BlockRejectionReason::BadTxnsNonFinal,
+            "bad-witness-nonce-size" => BlockRejectionReason::BadWitnessNonceSize,
+            "bad-witness-merkle-match" => BlockRejectionReason::BadWitnessMerkleMatch,
+            "unexpected-witness" => BlockRejectionReason::UnexpectedWitness,
+            reason if reason.starts_with("bad-version") => {
+                BlockRejectionReason::BadVersion(reason.to_string())
+            }
+            reason => BlockRejectionReason::Other(reason.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for BlockRejectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for BlockRejectionReason {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockRejectionReason {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let reason = String::deserialize(deserializer)?;
+        Ok(BlockRejectionReason::from(reason.as_str()))
+    }
+}
+
+// The BIP22 result of submitting a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitBlockCommandResponse {
+    Accepted,                       // null
+    Duplicate,                      // "duplicate": the block was already accepted
+    DuplicateInvalid,               // "duplicate-invalid": the block was already rejected
+    DuplicateInconclusive, // "duplicate-inconclusive": already have the block, but not its validity
+    Inconclusive,          // "inconclusive": valid, but not on the best chain (yet)
+    Rejected(BlockRejectionReason), // The block is invalid
+}
+
+impl SubmitBlockCommandResponse {
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, SubmitBlockCommandResponse::Accepted)
+    }
+}
+
+impl<'de> Deserialize<'de> for SubmitBlockCommandResponse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let result = Option::<String>::deserialize(deserializer)?;
+        let response = match result.as_deref() {
+            None => SubmitBlockCommandResponse::Accepted,
+            Some("duplicate") => SubmitBlockCommandResponse::Duplicate,
+            Some("duplicate-invalid") => SubmitBlockCommandResponse::DuplicateInvalid,
+            Some("duplicate-inconclusive") => SubmitBlockCommandResponse::DuplicateInconclusive,
+            Some("inconclusive") => SubmitBlockCommandResponse::Inconclusive,
+            Some(reason) => SubmitBlockCommandResponse::Rejected(reason.into()),
+        };
+        Ok(response)
+    }
+}
+
+impl Serialize for SubmitBlockCommandResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            SubmitBlockCommandResponse::Accepted => serializer.serialize_none(),
+            SubmitBlockCommandResponse::Duplicate => serializer.serialize_str("duplicate"),
+            SubmitBlockCommandResponse::DuplicateInvalid => {
+                serializer.serialize_str("duplicate-invalid")
+            }
+            SubmitBlockCommandResponse::DuplicateInconclusive => {
+                serializer.serialize_str("duplicate-inconclusive")
+            }
+            SubmitBlockCommandResponse::Inconclusive => serializer.serialize_str("inconclusive"),
+            SubmitBlockCommandResponse::Rejected(reason) => reason.serialize(serializer),
+        }
+    }
+}
+
+pub struct SubmitBlockCommand {
+    hexdata: String, // (string, required) the hex-encoded block data to submit
+}
+impl SubmitBlockCommand {
+    pub fn new(hexdata: String) -> Self {
+        SubmitBlockCommand { hexdata }
+    }
+}
+
+impl CallableCommand for SubmitBlockCommand {
+    type Response = SubmitBlockCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let hexdata_arg_raw_value = to_raw_value(&self.hexdata).unwrap();
+        let params = vec![hexdata_arg_raw_value];
+        let r = request(client, SUBMIT_BLOCK_COMMAND, params)?;
+        let response: SubmitBlockCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
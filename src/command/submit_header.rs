@@ -0,0 +1,53 @@
+/*
+submitheader "hexdata"
+
+Decode the given hexdata as a header and submit it as a candidate chain tip if valid.
+Throws when the header is invalid.
+
+Arguments:
+1. hexdata    (string, required) the hex-encoded block header data
+
+Result:
+null    (json null) None
+
+Examples:
+> bitcoin-cli submitheader "aabbcc"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "submitheader", "params": ["aabbcc"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::version::{require_version, NodeVersion};
+
+const SUBMIT_HEADER_COMMAND: &str = "submitheader";
+
+pub struct SubmitHeaderCommand {
+    hexdata: String, // (string, required) the hex-encoded block header data
+}
+impl SubmitHeaderCommand {
+    pub fn new(hexdata: String) -> Self {
+        SubmitHeaderCommand { hexdata }
+    }
+}
+
+// An invalid header is reported as an RPC error rather than a result.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubmitHeaderCommandResponse;
+
+impl CallableCommand for SubmitHeaderCommand {
+    type Response = SubmitHeaderCommandResponse;
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        require_version(client, SUBMIT_HEADER_COMMAND, NodeVersion::V0_18)?;
+        let hexdata_arg_raw_value = to_raw_value(&self.hexdata).unwrap();
+        let params = vec![hexdata_arg_raw_value];
+        let r = request(client, SUBMIT_HEADER_COMMAND, params)?;
+        let response: SubmitHeaderCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
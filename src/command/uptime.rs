@@ -0,0 +1,45 @@
+/*
+uptime
+
+Returns the total uptime of the server.
+
+Result:
+n    (numeric) The number of seconds that the server has been running
+
+Examples:
+> bitcoin-cli uptime
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "uptime", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const UPTIME_COMMAND: &str = "uptime";
+
+// The number of seconds that the server has been running
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UptimeCommandResponse(pub u64);
+
+pub struct UptimeCommand {}
+impl UptimeCommand {
+    pub fn new() -> Self {
+        UptimeCommand {}
+    }
+}
+impl Default for UptimeCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for UptimeCommand {
+    type Response = UptimeCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let params = vec![];
+        let r = request(client, UPTIME_COMMAND, params)?;
+        let response: UptimeCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
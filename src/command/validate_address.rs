@@ -0,0 +1,94 @@
+/*
+validateaddress "address"
+
+Return information about the given bitcoin address.
+
+Arguments:
+1. address    (string, required) The bitcoin address to validate
+
+Result:
+{                               (json object)
+  "isvalid" : true|false,       (boolean) If the address is valid or not
+  "address" : "str",            (string, optional) The bitcoin address validated
+  "scriptPubKey" : "hex",       (string, optional) The hex-encoded scriptPubKey generated by the address
+  "isscript" : true|false,      (boolean, optional) If the key is a script
+  "iswitness" : true|false,     (boolean, optional) If the address is a witness address
+  "witness_version" : n,        (numeric, optional) The version number of the witness program
+  "witness_program" : "hex",    (string, optional) The hex value of the witness program
+  "error" : "str",              (string, optional) Error message, if any
+  "error_locations" : [         (json array, optional) Indices of likely error locations in address, if known (e.g. Bech32 errors)
+    n,                          (numeric) index of a potential error
+    ...
+  ]
+}
+
+Examples:
+> bitcoin-cli validateaddress "bc1q09vm5lfy0j5reeulh4x5752q25uqqvz34hufdl"
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "validateaddress", "params": ["bc1q09vm5lfy0j5reeulh4x5752q25uqqvz34hufdl"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::consensus::encode::decode_hex;
+use crate::consensus::script::{classify, ScriptType};
+use crate::types::transaction::BitcoinAddress;
+
+const VALIDATE_ADDRESS_COMMAND: &str = "validateaddress";
+
+pub struct ValidateAddressCommand {
+    address: String, // (string, required) The bitcoin address to validate
+}
+impl ValidateAddressCommand {
+    pub fn new(address: String) -> Self {
+        ValidateAddressCommand { address }
+    }
+}
+
+// A segwit output's witness version and program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitnessProgram {
+    pub version: u8,
+    pub program: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValidateAddressCommandResponse {
+    pub isvalid: bool,                   // If the address is valid or not
+    pub address: Option<BitcoinAddress>, // The bitcoin address validated
+    #[serde(rename = "scriptPubKey")]
+    pub script_pub_key: Option<String>, // The hex-encoded scriptPubKey generated by the address
+    pub isscript: Option<bool>,          // If the key is a script
+    pub iswitness: Option<bool>,         // If the address is a witness address
+    pub witness_version: Option<u8>,     // The version number of the witness program
+    pub witness_program: Option<String>, // The hex value of the witness program
+    pub error: Option<String>,           // Error message, if any
+    pub error_locations: Option<Vec<u64>>, // Indices of likely error locations in address, if known (e.g. Bech32 errors)
+}
+
+impl ValidateAddressCommandResponse {
+    // The type of output the address pays to, as getrawtransaction would report it.
+    pub fn script_type(&self) -> Option<ScriptType> {
+        let script = decode_hex(self.script_pub_key.as_ref()?).ok()?;
+        Some(classify(&script))
+    }
+    // None for invalid and non-segwit addresses.
+    pub fn witness_program(&self) -> Option<WitnessProgram> {
+        let version = self.witness_version?;
+        let program = decode_hex(self.witness_program.as_ref()?).ok()?;
+        Some(WitnessProgram { version, program })
+    }
+}
+
+impl CallableCommand for ValidateAddressCommand {
+    type Response = ValidateAddressCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let address_arg_raw_value = to_raw_value(&self.address).unwrap();
+        let params = vec![address_arg_raw_value];
+        let r = request(client, VALIDATE_ADDRESS_COMMAND, params)?;
+        let response: ValidateAddressCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
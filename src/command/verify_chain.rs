@@ -0,0 +1,98 @@
+/*
+verifychain ( checklevel nblocks )
+
+Verifies blockchain database.
+
+Arguments:
+1. checklevel    (numeric, optional, default=3, range=0-4) How thorough the block verification is:
+                 - level 0 reads the blocks from disk
+                 - level 1 verifies block validity
+                 - level 2 verifies undo data
+                 - level 3 checks disconnection of tip blocks
+                 - level 4 tries to reconnect the blocks
+                 - each level includes the checks of the previous levels
+2. nblocks       (numeric, optional, default=6, 0=all) The number of blocks to check.
+
+Result:
+true|false    (boolean) Verification finished successfully. If false, check debug.log for reason.
+
+Examples:
+> bitcoin-cli verifychain
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "verifychain", "params": []}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const VERIFY_CHAIN_COMMAND: &str = "verifychain";
+const DEFAULT_N_BLOCKS: u64 = 6;
+const N_BLOCKS_ARGUMENT_FOR_ALL_BLOCKS: u64 = 0;
+
+// How thorough the block verification is. Each level includes the checks of the previous levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckLevel {
+    ReadBlocks = 0,          // reads the blocks from disk
+    BlockValidity = 1,       // verifies block validity
+    UndoData = 2,            // verifies undo data
+    DisconnectTipBlocks = 3, // checks disconnection of tip blocks
+    ReconnectBlocks = 4,     // tries to reconnect the blocks
+}
+
+pub enum VerifyChainBlocksArg {
+    NBlocks(u64),
+    All,
+}
+
+pub struct VerifyChainCommand {
+    checklevel: CheckLevel, // (numeric, optional, default=3, range=0-4) How thorough the block verification is
+    nblocks: VerifyChainBlocksArg, // (numeric, optional, default=6, 0=all) The number of blocks to check.
+}
+impl VerifyChainCommand {
+    pub fn new() -> Self {
+        VerifyChainCommand {
+            checklevel: CheckLevel::DisconnectTipBlocks,
+            nblocks: VerifyChainBlocksArg::NBlocks(DEFAULT_N_BLOCKS),
+        }
+    }
+    pub fn set_checklevel(mut self, checklevel: CheckLevel) -> Self {
+        self.checklevel = checklevel;
+        self
+    }
+    // Checking all blocks at a high level can take hours on mainnet.
+    pub fn set_nblocks(mut self, nblocks: VerifyChainBlocksArg) -> Self {
+        self.nblocks = nblocks;
+        self
+    }
+}
+impl Default for VerifyChainCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Verification finished successfully. If false, check debug.log for reason.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VerifyChainCommandResponse(pub bool);
+
+impl CallableCommand for VerifyChainCommand {
+    type Response = VerifyChainCommandResponse;
+    // Levels 3 and 4 disconnect and reconnect tip blocks.
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let nblocks_arg = match self.nblocks {
+            VerifyChainBlocksArg::NBlocks(nblocks) => nblocks,
+            VerifyChainBlocksArg::All => N_BLOCKS_ARGUMENT_FOR_ALL_BLOCKS,
+        };
+        let checklevel_arg_raw_value = to_raw_value(&(self.checklevel as u8)).unwrap();
+        let nblocks_arg_raw_value = to_raw_value(&nblocks_arg).unwrap();
+        let params = vec![checklevel_arg_raw_value, nblocks_arg_raw_value];
+        let r = request(client, VERIFY_CHAIN_COMMAND, params)?;
+        let response: VerifyChainCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,67 @@
+/*
+verifymessage "address" "signature" "message"
+
+Verify a signed message.
+
+Arguments:
+1. address      (string, required) The bitcoin address to use for the signature.
+2. signature    (string, required) The signature provided by the signer in base 64 encoding (see signmessage).
+3. message      (string, required) The message that was signed.
+
+Result:
+true|false    (boolean) If the signature is verified or not.
+
+Examples:
+Unlock the wallet for 30 seconds
+> bitcoin-cli walletpassphrase "mypassphrase" 30
+Create the signature
+> bitcoin-cli signmessage "1D1ZrZNe3JUo7ZycKEYQQiQAWd9y54F4XX" "my message"
+Verify the signature
+> bitcoin-cli verifymessage "1D1ZrZNe3JUo7ZycKEYQQiQAWd9y54F4XX" "signature" "my message"
+As a JSON-RPC call
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "verifymessage", "params": ["1D1ZrZNe3JUo7ZycKEYQQiQAWd9y54F4XX", "signature", "my message"]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+
+const VERIFY_MESSAGE_COMMAND: &str = "verifymessage";
+
+pub struct VerifyMessageCommand {
+    address: String,   // (string, required) The bitcoin address to use for the signature.
+    signature: String, // (string, required) The signature provided by the signer in base 64 encoding (see signmessage).
+    message: String,   // (string, required) The message that was signed.
+}
+impl VerifyMessageCommand {
+    pub fn new(address: String, signature: String, message: String) -> Self {
+        VerifyMessageCommand {
+            address,
+            signature,
+            message,
+        }
+    }
+}
+
+// If the signature is verified or not. Only legacy (P2PKH) addresses can be used.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VerifyMessageCommandResponse(pub bool);
+
+impl CallableCommand for VerifyMessageCommand {
+    type Response = VerifyMessageCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let address_arg_raw_value = to_raw_value(&self.address).unwrap();
+        let signature_arg_raw_value = to_raw_value(&self.signature).unwrap();
+        let message_arg_raw_value = to_raw_value(&self.message).unwrap();
+        let params = vec![
+            address_arg_raw_value,
+            signature_arg_raw_value,
+            message_arg_raw_value,
+        ];
+        let r = request(client, VERIFY_MESSAGE_COMMAND, params)?;
+        let response: VerifyMessageCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,48 @@
+/*
+verifytxoutproof "proof"
+
+Verifies that a proof points to a transaction in a block, returning the transaction it commits to
+and throwing an RPC error if the block is not in our best chain
+
+Arguments:
+1. proof    (string, required) The hex-encoded proof generated by gettxoutproof
+
+Result:
+[           (json array)
+  "hex",    (string) The txid(s) which the proof commits to, or empty array if the proof can not be validated.
+  ...
+]
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+
+use crate::client::Client;
+use crate::command::request::request;
+use crate::command::CallableCommand;
+use crate::types::transaction::Txid;
+
+const VERIFY_TX_OUT_PROOF_COMMAND: &str = "verifytxoutproof";
+
+pub struct VerifyTxOutProofCommand {
+    proof: String, // (string, required) The hex-encoded proof generated by gettxoutproof
+}
+impl VerifyTxOutProofCommand {
+    pub fn new(proof: String) -> Self {
+        VerifyTxOutProofCommand { proof }
+    }
+}
+
+// The txids the proof commits to, or empty if the proof can not be validated.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerifyTxOutProofCommandResponse(pub Vec<Txid>);
+
+impl CallableCommand for VerifyTxOutProofCommand {
+    type Response = VerifyTxOutProofCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let proof_arg_raw_value = to_raw_value(&self.proof).unwrap();
+        let params = vec![proof_arg_raw_value];
+        let r = request(client, VERIFY_TX_OUT_PROOF_COMMAND, params)?;
+        let response: VerifyTxOutProofCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,73 @@
+/*
+waitforblock "blockhash" ( timeout )
+
+Waits for a specific new block and returns useful info about it.
+
+Returns the current block on timeout or exit.
+
+Make sure to use no RPC timeout (bitcoin-cli -rpcclienttimeout=0)
+
+Arguments:
+1. blockhash    (string, required) Block hash to wait for.
+2. timeout      (numeric, optional, default=0) Time in milliseconds to wait for a response. 0 indicates no timeout.
+
+Result:
+{                       (json object)
+  "hash" : "hex",       (string) The blockhash
+  "height" : n          (numeric) Block height
+}
+
+Examples:
+> bitcoin-cli waitforblock "0000000000079f8ef3d2c688c244eb7a4570b24c9ed7b4a8c619eb02596f8862" 1000
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "waitforblock", "params": ["0000000000079f8ef3d2c688c244eb7a4570b24c9ed7b4a8c619eb02596f8862", 1000]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+use std::time::Duration;
+
+use crate::client::Client;
+use crate::command::request::{request, timeout_millis};
+use crate::command::CallableCommand;
+use crate::Blockhash;
+
+const WAIT_FOR_BLOCK_COMMAND: &str = "waitforblock";
+
+// The node's tip when the wait ended. It's a different block than the one waited for if the call
+// timed out.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WaitForBlockCommandResponse {
+    pub hash: Blockhash, // The blockhash
+    pub height: u64,     // Block height
+}
+
+pub struct WaitForBlockCommand {
+    blockhash: Blockhash,      // (string, required) Block hash to wait for.
+    timeout: Option<Duration>, // (numeric, optional, default=0) Time in milliseconds to wait for a response. 0 indicates no timeout.
+}
+impl WaitForBlockCommand {
+    pub fn new(blockhash: Blockhash) -> Self {
+        WaitForBlockCommand {
+            blockhash,
+            timeout: None,
+        }
+    }
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl CallableCommand for WaitForBlockCommand {
+    type Response = WaitForBlockCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let blockhash_arg_raw_value = to_raw_value(&self.blockhash.0).unwrap();
+        let mut params = vec![blockhash_arg_raw_value];
+        if let Some(timeout) = self.timeout {
+            let timeout_arg_raw_value = to_raw_value(&timeout_millis(timeout)).unwrap();
+            params.push(timeout_arg_raw_value);
+        }
+        let r = request(client, WAIT_FOR_BLOCK_COMMAND, params)?;
+        let response: WaitForBlockCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
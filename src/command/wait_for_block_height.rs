@@ -0,0 +1,74 @@
+/*
+waitforblockheight height ( timeout )
+
+Waits for (at least) block height and returns the height and hash
+of the current tip.
+
+Returns the current block on timeout or exit.
+
+Make sure to use no RPC timeout (bitcoin-cli -rpcclienttimeout=0)
+
+Arguments:
+1. height     (numeric, required) Block height to wait for.
+2. timeout    (numeric, optional, default=0) Time in milliseconds to wait for a response. 0 indicates no timeout.
+
+Result:
+{                       (json object)
+  "hash" : "hex",       (string) The blockhash
+  "height" : n          (numeric) Block height
+}
+
+Examples:
+> bitcoin-cli waitforblockheight 100 1000
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "waitforblockheight", "params": [100, 1000]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+use std::time::Duration;
+
+use crate::client::Client;
+use crate::command::request::{request, timeout_millis};
+use crate::command::CallableCommand;
+use crate::Blockhash;
+
+const WAIT_FOR_BLOCK_HEIGHT_COMMAND: &str = "waitforblockheight";
+
+// The node's tip when the wait ended. Its height is below the one waited for if the call timed
+// out.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WaitForBlockHeightCommandResponse {
+    pub hash: Blockhash, // The blockhash
+    pub height: u64,     // Block height
+}
+
+pub struct WaitForBlockHeightCommand {
+    height: u64,               // (numeric, required) Block height to wait for.
+    timeout: Option<Duration>, // (numeric, optional, default=0) Time in milliseconds to wait for a response. 0 indicates no timeout.
+}
+impl WaitForBlockHeightCommand {
+    pub fn new(height: u64) -> Self {
+        WaitForBlockHeightCommand {
+            height,
+            timeout: None,
+        }
+    }
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl CallableCommand for WaitForBlockHeightCommand {
+    type Response = WaitForBlockHeightCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let height_arg_raw_value = to_raw_value(&self.height).unwrap();
+        let mut params = vec![height_arg_raw_value];
+        if let Some(timeout) = self.timeout {
+            let timeout_arg_raw_value = to_raw_value(&timeout_millis(timeout)).unwrap();
+            params.push(timeout_arg_raw_value);
+        }
+        let r = request(client, WAIT_FOR_BLOCK_HEIGHT_COMMAND, params)?;
+        let response: WaitForBlockHeightCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
@@ -0,0 +1,73 @@
+/*
+waitfornewblock ( timeout )
+
+Waits for a specific new block and returns useful info about it.
+
+Returns the current block on timeout or exit.
+
+Make sure to use no RPC timeout (bitcoin-cli -rpcclienttimeout=0)
+
+Arguments:
+1. timeout    (numeric, optional, default=0) Time in milliseconds to wait for a response. 0 indicates no timeout.
+
+Result:
+{                       (json object)
+  "hash" : "hex",       (string) The blockhash
+  "height" : n          (numeric) Block height
+}
+
+Examples:
+> bitcoin-cli waitfornewblock 1000
+> curl --user myusername --data-binary '{"jsonrpc": "1.0", "id": "curltest", "method": "waitfornewblock", "params": [1000]}' -H 'content-type: text/plain;' http://127.0.0.1:8332/
+*/
+use serde::{Deserialize, Serialize};
+use serde_json::value::to_raw_value;
+use std::time::Duration;
+
+use crate::client::Client;
+use crate::command::request::{request, timeout_millis};
+use crate::command::CallableCommand;
+use crate::Blockhash;
+
+const WAIT_FOR_NEW_BLOCK_COMMAND: &str = "waitfornewblock";
+
+// The node's tip when the wait ended.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WaitForNewBlockCommandResponse {
+    pub hash: Blockhash, // The blockhash
+    pub height: u64,     // Block height
+}
+
+pub struct WaitForNewBlockCommand {
+    timeout: Option<Duration>, // (numeric, optional, default=0) Time in milliseconds to wait for a response. 0 indicates no timeout.
+}
+impl WaitForNewBlockCommand {
+    pub fn new() -> Self {
+        WaitForNewBlockCommand { timeout: None }
+    }
+    // Without a timeout the call only returns once a new block arrives, so it's only safe with a
+    // client whose own timeout is long enough.
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+impl Default for WaitForNewBlockCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallableCommand for WaitForNewBlockCommand {
+    type Response = WaitForNewBlockCommandResponse;
+    fn call(&self, client: &Client) -> Result<Self::Response, jsonrpc::Error> {
+        let mut params = vec![];
+        if let Some(timeout) = self.timeout {
+            let timeout_arg_raw_value = to_raw_value(&timeout_millis(timeout)).unwrap();
+            params.push(timeout_arg_raw_value);
+        }
+        let r = request(client, WAIT_FOR_NEW_BLOCK_COMMAND, params)?;
+        let response: WaitForNewBlockCommandResponse = r.result()?;
+        Ok(response)
+    }
+}
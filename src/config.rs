@@ -0,0 +1,397 @@
+// Connection settings for a node, found the way bitcoin-cli finds them: an explicit rpcuser and
+// rpcpassword, or else the cookie file bitcoind writes to its datadir, with the host, port and
+// chain coming from bitcoin.conf when they aren't given.
+//
+// Settings given directly take precedence over those from the conf file:
+//
+//     let settings = ConnectionSettings::new().set_chain(Network::Regtest);
+//     let conf = ConnectionSettings::from_conf_file(&settings.conf_file()?, settings.chain)?;
+//     let client = settings.with_fallback(conf).connect(DEFAULT_TIMEOUT)?;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use jsonrpc::simple_http;
+
+use crate::client::Client;
+use crate::consensus::params::Network;
+
+pub const CONF_FILENAME: &str = "bitcoin.conf";
+pub const COOKIE_FILENAME: &str = ".cookie";
+const DEFAULT_RPCCONNECT: &str = "127.0.0.1";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    // A line of a conf file that isn't a comment, a [section] or a key=value pair, or a value
+    // that can't be used.
+    Parse {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    // No rpcpassword was given and the cookie file couldn't be read.
+    NoCredentials {
+        cookie_file: PathBuf,
+        error: io::Error,
+    },
+    // The cookie file isn't "user:password".
+    InvalidCookie(PathBuf),
+    // No datadir was given and there is no home directory to find the default one.
+    NoDataDir,
+    Client(simple_http::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            ConfigError::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path.display(), line, message),
+            ConfigError::NoCredentials { cookie_file, error } => write!(
+                f,
+                "no rpcpassword given and the cookie file {} couldn't be read ({})",
+                cookie_file.display(),
+                error
+            ),
+            ConfigError::InvalidCookie(cookie_file) => {
+                write!(f, "invalid cookie file {}", cookie_file.display())
+            }
+            ConfigError::NoDataDir => write!(f, "no datadir given and no home directory found"),
+            ConfigError::Client(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<simple_http::Error> for ConfigError {
+    fn from(error: simple_http::Error) -> Self {
+        ConfigError::Client(error)
+    }
+}
+
+// The RPC port bitcoind listens on by default.
+pub fn default_rpc_port(chain: Network) -> u16 {
+    match chain {
+        Network::Main => 8332,
+        Network::Test => 18332,
+        Network::Testnet4 => 48332,
+        Network::Signet => 38332,
+        Network::Regtest => 18443,
+    }
+}
+
+// The subdirectory of the datadir a chain's files (including the cookie) are in.
+fn chain_subdir(chain: Network) -> &'static str {
+    match chain {
+        Network::Main => "",
+        Network::Test => "testnet3",
+        Network::Testnet4 => "testnet4",
+        Network::Signet => "signet",
+        Network::Regtest => "regtest",
+    }
+}
+
+// The datadir bitcoind uses when none is given.
+pub fn default_datadir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") {
+        return std::env::var_os("APPDATA").map(|appdata| PathBuf::from(appdata).join("Bitcoin"));
+    }
+    let home = PathBuf::from(std::env::var_os("HOME")?);
+    if cfg!(target_os = "macos") {
+        Some(home.join("Library/Application Support/Bitcoin"))
+    } else {
+        Some(home.join(".bitcoin"))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionSettings {
+    pub chain: Option<Network>,     // default main
+    pub url: Option<String>, // the full address of the RPC server, e.g. "http://127.0.0.1:8332". Overrides rpcconnect and rpcport
+    pub rpcconnect: Option<String>, // default 127.0.0.1
+    pub rpcport: Option<u16>, // default the chain's port
+    pub rpcuser: Option<String>,
+    pub rpcpassword: Option<String>, // without it the cookie file is used
+    pub rpccookiefile: Option<PathBuf>, // default <datadir>/<chain>/.cookie, relative paths are in the chain's directory
+    pub datadir: Option<PathBuf>,       // default ~/.bitcoin, or the platform's equivalent
+}
+
+impl ConnectionSettings {
+    pub fn new() -> Self {
+        ConnectionSettings::default()
+    }
+    pub fn set_chain(mut self, chain: Network) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+    pub fn set_url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
+    pub fn set_rpcconnect(mut self, rpcconnect: String) -> Self {
+        self.rpcconnect = Some(rpcconnect);
+        self
+    }
+    pub fn set_rpcport(mut self, rpcport: u16) -> Self {
+        self.rpcport = Some(rpcport);
+        self
+    }
+    pub fn set_rpcuser(mut self, rpcuser: String) -> Self {
+        self.rpcuser = Some(rpcuser);
+        self
+    }
+    pub fn set_rpcpassword(mut self, rpcpassword: String) -> Self {
+        self.rpcpassword = Some(rpcpassword);
+        self
+    }
+    pub fn set_rpccookiefile(mut self, rpccookiefile: PathBuf) -> Self {
+        self.rpccookiefile = Some(rpccookiefile);
+        self
+    }
+    pub fn set_datadir(mut self, datadir: PathBuf) -> Self {
+        self.datadir = Some(datadir);
+        self
+    }
+
+    // Settings missing here are taken from `fallback`.
+    pub fn with_fallback(self, fallback: ConnectionSettings) -> Self {
+        ConnectionSettings {
+            chain: self.chain.or(fallback.chain),
+            url: self.url.or(fallback.url),
+            rpcconnect: self.rpcconnect.or(fallback.rpcconnect),
+            rpcport: self.rpcport.or(fallback.rpcport),
+            rpcuser: self.rpcuser.or(fallback.rpcuser),
+            rpcpassword: self.rpcpassword.or(fallback.rpcpassword),
+            rpccookiefile: self.rpccookiefile.or(fallback.rpccookiefile),
+            datadir: self.datadir.or(fallback.datadir),
+        }
+    }
+
+    // Reads the settings of a bitcoin.conf. Options in a [section] only apply to that chain, and
+    // override the ones outside of sections. The chain is the one given, or else the one the
+    // file selects.
+    pub fn from_conf_file(path: &Path, chain: Option<Network>) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|error| ConfigError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        let parse_error = |line: usize, message: String| ConfigError::Parse {
+            path: path.to_path_buf(),
+            line,
+            message,
+        };
+
+        // (section, key, value, line number), with section None outside of sections.
+        let mut options: Vec<(Option<String>, String, String, usize)> = vec![];
+        let mut section: Option<String> = None;
+        for (index, line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let line = match line.find('#') {
+                Some(comment_start) => &line[..comment_start],
+                None => line,
+            }
+            .trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = Some(name.trim().to_string());
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| parse_error(line_number, format!("expected key=value: {}", line)))?;
+            let (key, value) = (key.trim(), value.trim().to_string());
+            // "regtest.rpcport=1" is the same as rpcport=1 in a [regtest] section.
+            match key.split_once('.') {
+                Some((key_section, key)) => options.push((
+                    Some(key_section.to_string()),
+                    key.to_string(),
+                    value,
+                    line_number,
+                )),
+                None => options.push((section.clone(), key.to_string(), value, line_number)),
+            }
+        }
+
+        let chain = match chain {
+            Some(chain) => chain,
+            None => {
+                let mut selected = Network::Main;
+                for (section, key, value, line_number) in options.iter() {
+                    if section.is_some() {
+                        continue;
+                    }
+                    selected = match (key.as_str(), value.as_str()) {
+                        ("chain", name) => name
+                            .parse()
+                            .map_err(|message| parse_error(*line_number, message))?,
+                        ("testnet", "1") => Network::Test,
+                        ("testnet4", "1") => Network::Testnet4,
+                        ("signet", "1") => Network::Signet,
+                        ("regtest", "1") => Network::Regtest,
+                        _ => continue,
+                    };
+                }
+                selected
+            }
+        };
+
+        let mut settings = ConnectionSettings::new().set_chain(chain);
+        let chain_section = chain.to_string();
+        let global_options = options.iter().filter(|option| option.0.is_none());
+        let chain_options = options
+            .iter()
+            .filter(|option| option.0.as_deref() == Some(chain_section.as_str()));
+        for (_, key, value, line_number) in global_options.chain(chain_options) {
+            match key.as_str() {
+                "rpcconnect" => settings.rpcconnect = Some(value.clone()),
+                "rpcport" => {
+                    let rpcport = value.parse().map_err(|_| {
+                        parse_error(*line_number, format!("invalid rpcport: {}", value))
+                    })?;
+                    settings.rpcport = Some(rpcport);
+                }
+                "rpcuser" => settings.rpcuser = Some(value.clone()),
+                "rpcpassword" => settings.rpcpassword = Some(value.clone()),
+                "rpccookiefile" => settings.rpccookiefile = Some(PathBuf::from(value)),
+                "datadir" => settings.datadir = Some(PathBuf::from(value)),
+                _ => {}
+            }
+        }
+        Ok(settings)
+    }
+
+    pub fn chain(&self) -> Network {
+        self.chain.unwrap_or(Network::Main)
+    }
+    pub fn datadir(&self) -> Result<PathBuf, ConfigError> {
+        match &self.datadir {
+            Some(datadir) => Ok(datadir.clone()),
+            None => default_datadir().ok_or(ConfigError::NoDataDir),
+        }
+    }
+    // The bitcoin.conf in the datadir.
+    pub fn conf_file(&self) -> Result<PathBuf, ConfigError> {
+        Ok(self.datadir()?.join(CONF_FILENAME))
+    }
+    pub fn cookie_file(&self) -> Result<PathBuf, ConfigError> {
+        let chain_dir = self.datadir()?.join(chain_subdir(self.chain()));
+        match &self.rpccookiefile {
+            Some(rpccookiefile) => Ok(chain_dir.join(rpccookiefile)),
+            None => Ok(chain_dir.join(COOKIE_FILENAME)),
+        }
+    }
+    pub fn url(&self) -> String {
+        if let Some(url) = &self.url {
+            return url.clone();
+        }
+        let rpcconnect = self.rpcconnect.as_deref().unwrap_or(DEFAULT_RPCCONNECT);
+        let rpcport = self
+            .rpcport
+            .unwrap_or_else(|| default_rpc_port(self.chain()));
+        format!("http://{}:{}", rpcconnect, rpcport)
+    }
+    // The user and password: rpcuser and rpcpassword if a password was given, the cookie's
+    // otherwise. The cookie is rewritten every time bitcoind starts, so it's read on each call.
+    pub fn credentials(&self) -> Result<(String, String), ConfigError> {
+        if let Some(rpcpassword) = &self.rpcpassword {
+            let rpcuser = self.rpcuser.clone().unwrap_or_default();
+            return Ok((rpcuser, rpcpassword.clone()));
+        }
+        let cookie_file = self.cookie_file()?;
+        let cookie = match fs::read_to_string(&cookie_file) {
+            Ok(cookie) => cookie,
+            Err(error) => return Err(ConfigError::NoCredentials { cookie_file, error }),
+        };
+        match cookie.trim_end().split_once(':') {
+            Some((user, password)) => Ok((user.to_string(), password.to_string())),
+            None => Err(ConfigError::InvalidCookie(cookie_file)),
+        }
+    }
+    pub fn connect(&self, timeout: Duration) -> Result<Client, ConfigError> {
+        let (user, password) = self.credentials()?;
+        let client = Client::with_timeout(&self.url(), &user, &password, timeout)?;
+        Ok(client)
+    }
+}
+
+// The connection flags of the crate's binaries. Settings given here take precedence over those
+// of bitcoin.conf.
+#[cfg(feature = "cli")]
+#[derive(clap::Args, Debug)]
+pub struct ConnectionArgs {
+    /// The chain the node runs: main, test, testnet4, signet or regtest
+    #[arg(long, env = "BITCOIND_CHAIN", global = true)]
+    pub chain: Option<Network>,
+    /// The address of the RPC server, e.g. http://127.0.0.1:8332 (overrides --rpcconnect and --rpcport)
+    #[arg(long, env = "BITCOIND_URL", global = true)]
+    pub url: Option<String>,
+    /// The host of the RPC server
+    #[arg(long, global = true)]
+    pub rpcconnect: Option<String>,
+    /// The port of the RPC server
+    #[arg(long, global = true)]
+    pub rpcport: Option<u16>,
+    #[arg(long, env = "BITCOIND_USERNAME", global = true)]
+    pub rpcuser: Option<String>,
+    /// Without it the cookie file is used
+    #[arg(long, env = "BITCOIND_PASSWORD", hide_env_values = true, global = true)]
+    pub rpcpassword: Option<String>,
+    /// Relative paths are in the chain's directory of the datadir
+    #[arg(long, env = "BITCOIND_COOKIEFILE", global = true)]
+    pub rpccookiefile: Option<PathBuf>,
+    /// Where bitcoin.conf and the cookie file are, ~/.bitcoin by default
+    #[arg(long, env = "BITCOIND_DATADIR", global = true)]
+    pub datadir: Option<PathBuf>,
+    /// The bitcoin.conf to read, <datadir>/bitcoin.conf by default
+    #[arg(long, env = "BITCOIND_CONF", global = true)]
+    pub conf: Option<PathBuf>,
+    /// Seconds to wait for a response
+    #[arg(long, default_value_t = crate::client::DEFAULT_TIMEOUT.as_secs(), global = true)]
+    pub timeout: u64,
+}
+
+#[cfg(feature = "cli")]
+impl ConnectionArgs {
+    pub fn settings(&self) -> ConnectionSettings {
+        ConnectionSettings {
+            chain: self.chain,
+            url: self.url.clone(),
+            rpcconnect: self.rpcconnect.clone(),
+            rpcport: self.rpcport,
+            rpcuser: self.rpcuser.clone(),
+            rpcpassword: self.rpcpassword.clone(),
+            rpccookiefile: self.rpccookiefile.clone(),
+            datadir: self.datadir.clone(),
+        }
+    }
+    // The flags, with what they leave out taken from bitcoin.conf. A missing bitcoin.conf is
+    // only an error when it was asked for with --conf.
+    pub fn resolve(&self) -> Result<ConnectionSettings, ConfigError> {
+        let settings = self.settings();
+        let conf = match &self.conf {
+            Some(conf) => conf.clone(),
+            None => match settings.conf_file() {
+                Ok(conf) if conf.exists() => conf,
+                _ => return Ok(settings),
+            },
+        };
+        let conf_settings = ConnectionSettings::from_conf_file(&conf, settings.chain)?;
+        Ok(settings.with_fallback(conf_settings))
+    }
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+}
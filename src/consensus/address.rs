@@ -0,0 +1,248 @@
+// Address encoding for output scripts: base58check for legacy outputs, bech32 (BIP173) for
+// segwit v0 and bech32m (BIP350) for segwit v1+ outputs.
+use crate::consensus::hash::sha256d;
+use crate::consensus::params::Network;
+use crate::consensus::script::{classify, witness_program, ScriptType};
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BECH32_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+pub fn base58check_encode(payload: &[u8]) -> String {
+    let checksum = sha256d(payload);
+    let mut data = payload.to_vec();
+    data.extend_from_slice(&checksum[..4]);
+
+    let leading_zeros = data.iter().take_while(|byte| **byte == 0).count();
+    // Repeatedly divide the big-endian number by 58, collecting remainders.
+    let mut digits: Vec<u8> = vec![];
+    for byte in &data[leading_zeros..] {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let mut encoded = String::with_capacity(leading_zeros + digits.len());
+    for _ in 0..leading_zeros {
+        encoded.push('1');
+    }
+    for digit in digits.iter().rev() {
+        encoded.push(BASE58_ALPHABET[*digit as usize] as char);
+    }
+    encoded
+}
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    let mut checksum: u32 = 1;
+    for value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x01ff_ffff) << 5) ^ (*value as u32);
+        for (index, generator) in GENERATOR.iter().enumerate() {
+            if (top >> index) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+fn bech32_hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut expanded: Vec<u8> = hrp.bytes().map(|byte| byte >> 5).collect();
+    expanded.push(0);
+    expanded.extend(hrp.bytes().map(|byte| byte & 31));
+    expanded
+}
+
+// Regroups 8-bit bytes into 5-bit groups, padding the final group with zeros.
+fn to_five_bit_groups(data: &[u8]) -> Vec<u8> {
+    let mut accumulator: u32 = 0;
+    let mut bits = 0;
+    let mut groups = vec![];
+    for byte in data {
+        accumulator = (accumulator << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            groups.push(((accumulator >> bits) & 31) as u8);
+        }
+    }
+    if bits > 0 {
+        groups.push(((accumulator << (5 - bits)) & 31) as u8);
+    }
+    groups
+}
+
+pub fn segwit_address_encode(hrp: &str, witness_version: u8, program: &[u8]) -> String {
+    let mut data = vec![witness_version];
+    data.extend(to_five_bit_groups(program));
+    let checksum_const = if witness_version == 0 {
+        BECH32_CONST
+    } else {
+        BECH32M_CONST
+    };
+    let mut values = bech32_hrp_expand(hrp);
+    values.extend(&data);
+    values.extend([0; 6]);
+    let polymod = bech32_polymod(&values) ^ checksum_const;
+    for index in 0..6 {
+        data.push(((polymod >> (5 * (5 - index))) & 31) as u8);
+    }
+    let mut encoded = String::from(hrp);
+    encoded.push('1');
+    for value in data {
+        encoded.push(BECH32_CHARSET[value as usize] as char);
+    }
+    encoded
+}
+
+// Returns the address bitcoin core would report for an output script, if it has one. Like core,
+// bare pubkey, multisig, nulldata and nonstandard outputs have no address.
+pub fn from_script_pubkey(script: &[u8], network: Network) -> Option<String> {
+    match classify(script) {
+        ScriptType::PubKeyHash => {
+            let mut payload = vec![network.p2pkh_prefix()];
+            payload.extend_from_slice(&script[3..23]);
+            Some(base58check_encode(&payload))
+        }
+        ScriptType::ScriptHash => {
+            let mut payload = vec![network.p2sh_prefix()];
+            payload.extend_from_slice(&script[2..22]);
+            Some(base58check_encode(&payload))
+        }
+        ScriptType::WitnessV0KeyHash
+        | ScriptType::WitnessV0ScriptHash
+        | ScriptType::WitnessV1Taproot
+        | ScriptType::Anchor
+        | ScriptType::WitnessUnknown => {
+            let (version, program) = witness_program(script)?;
+            Some(segwit_address_encode(
+                network.bech32_hrp(),
+                version,
+                program,
+            ))
+        }
+        ScriptType::PubKey
+        | ScriptType::Multisig
+        | ScriptType::NullData
+        | ScriptType::NonStandard => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::encode::decode_hex;
+
+    fn address(script_hex: &str, network: Network) -> Option<String> {
+        from_script_pubkey(&decode_hex(script_hex).unwrap(), network)
+    }
+
+    #[test]
+    fn base58check() {
+        // The genesis block's key, paid to its hash.
+        assert_eq!(
+            address(
+                "76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac",
+                Network::Main
+            )
+            .unwrap(),
+            "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"
+        );
+        assert_eq!(base58check_encode(&[0; 21]), "1111111111111111111114oLvT2");
+    }
+
+    // The valid segwit addresses of BIP173 and BIP350, from their output scripts.
+    #[test]
+    fn bip173_and_bip350_vectors() {
+        let cases = [
+            (
+                "bc",
+                "0014751e76e8199196d454941c45d1b3a323f1433bd6",
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            ),
+            (
+                "tb",
+                "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+                "tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7",
+            ),
+            (
+                "tb",
+                "0020000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433",
+                "tb1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesrxh6hy",
+            ),
+            (
+                "bc",
+                "5128751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6",
+                "bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y",
+            ),
+            ("bc", "6002751e", "bc1sw50qgdz25j"),
+            (
+                "bc",
+                "5210751e76e8199196d454941c45d1b3a323",
+                "bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs",
+            ),
+            (
+                "tb",
+                "5120000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433",
+                "tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c",
+            ),
+            (
+                "bc",
+                "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+            ),
+        ];
+        for (hrp, script_hex, expected) in cases {
+            let script = decode_hex(script_hex).unwrap();
+            let (version, program) = witness_program(&script).unwrap();
+            assert_eq!(segwit_address_encode(hrp, version, program), expected);
+            let network = if hrp == "bc" {
+                Network::Main
+            } else {
+                Network::Test
+            };
+            assert_eq!(
+                from_script_pubkey(&script, network).as_deref(),
+                Some(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn prefixes_follow_the_network() {
+        let p2wpkh = "0014751e76e8199196d454941c45d1b3a323f1433bd6";
+        assert!(address(p2wpkh, Network::Regtest)
+            .unwrap()
+            .starts_with("bcrt1q"));
+        assert!(address(p2wpkh, Network::Signet)
+            .unwrap()
+            .starts_with("tb1q"));
+        let p2pkh = "76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac";
+        assert!(address(p2pkh, Network::Test)
+            .unwrap()
+            .starts_with(['m', 'n']));
+        let p2sh = "a914748284390f9e263a4b766a75d0633c50426eb87587";
+        assert!(address(p2sh, Network::Main).unwrap().starts_with('3'));
+        assert!(address(p2sh, Network::Test).unwrap().starts_with('2'));
+    }
+
+    #[test]
+    fn no_address_without_a_standard_destination() {
+        assert_eq!(address("6a0b68656c6c6f20776f726c64", Network::Main), None);
+        assert_eq!(address("6a76", Network::Main), None);
+    }
+}
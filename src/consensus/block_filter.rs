@@ -0,0 +1,354 @@
+// BIP158 compact block filters. A block's basic filter is a Golomb-coded set of the scriptPubKeys
+// it creates and spends, so a client can test whether a block might pay to or spend from its
+// scripts without downloading it. Filters can have false positives (about 1 in 784931 per script)
+// but no false negatives.
+//
+// Each filter is committed to by a filter header, which chains the filter hash onto the previous
+// block's filter header. Verifying the chain of headers the node reports catches a node that
+// serves filters that don't match its own headers.
+use std::error::Error;
+use std::fmt;
+
+use crate::client::Client;
+use crate::command::get_block_filter::{GetBlockFilterCommand, GetBlockFilterCommandResponse};
+use crate::command::get_block_hash::GetBlockHashCommand;
+use crate::command::CallableCommand;
+use crate::consensus::encode::{decode_hex, DecodeError, Reader};
+use crate::consensus::hash::{siphash24, Hash256};
+use crate::Blockhash;
+
+// Golomb-Rice parameter of the basic filter: remainders are 19 bits.
+pub const BASIC_FILTER_P: u8 = 19;
+// Inverse false positive rate of the basic filter.
+pub const BASIC_FILTER_M: u64 = 784_931;
+
+#[derive(Debug)]
+pub enum BlockFilterError {
+    Decode(DecodeError),
+    // The Golomb-Rice stream ended before all of the filter's elements were read.
+    Truncated,
+    // The header computed from the filter and the previous header doesn't match the header the
+    // node reported.
+    HeaderMismatch {
+        height: u64,
+        computed: Hash256,
+        reported: Hash256,
+    },
+    Rpc(jsonrpc::Error),
+}
+
+impl fmt::Display for BlockFilterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlockFilterError::Decode(error) => write!(f, "{}", error),
+            BlockFilterError::Truncated => write!(f, "filter ended before all elements were read"),
+            BlockFilterError::HeaderMismatch {
+                height,
+                computed,
+                reported,
+            } => write!(
+                f,
+                "filter header {} computed for block {} doesn't match reported header {}",
+                computed, height, reported
+            ),
+            BlockFilterError::Rpc(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for BlockFilterError {}
+
+impl From<DecodeError> for BlockFilterError {
+    fn from(error: DecodeError) -> Self {
+        BlockFilterError::Decode(error)
+    }
+}
+
+impl From<jsonrpc::Error> for BlockFilterError {
+    fn from(error: jsonrpc::Error) -> Self {
+        BlockFilterError::Rpc(error)
+    }
+}
+
+// A serialized basic filter: the number of elements as a compact size, followed by the
+// Golomb-Rice coded differences between the sorted hashed elements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockFilter {
+    pub content: Vec<u8>,
+    n: u64,
+    data_start: usize,
+}
+
+impl BlockFilter {
+    pub fn new(content: Vec<u8>) -> Result<Self, BlockFilterError> {
+        let mut reader = Reader::new(&content);
+        let n = reader.read_compact_size()?;
+        let data_start = reader.position();
+        Ok(BlockFilter {
+            content,
+            n,
+            data_start,
+        })
+    }
+    // Decodes the "filter" field of getblockfilter.
+    pub fn from_hex(hex: &str) -> Result<Self, BlockFilterError> {
+        BlockFilter::new(decode_hex(hex)?)
+    }
+    // Number of elements in the filter.
+    pub fn len(&self) -> u64 {
+        self.n
+    }
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+    pub fn filter_hash(&self) -> Hash256 {
+        Hash256::hash(&self.content)
+    }
+    // The filter header of this filter's block, given the previous block's filter header (all
+    // zeros for the genesis block).
+    pub fn header(&self, previous_header: &Hash256) -> Hash256 {
+        let mut data = [0; 64];
+        data[..32].copy_from_slice(&self.filter_hash().0);
+        data[32..].copy_from_slice(&previous_header.0);
+        Hash256::hash(&data)
+    }
+    // The filter's elements as sorted values in [0, N * M).
+    pub fn decode(&self) -> Result<Vec<u64>, BlockFilterError> {
+        let mut reader = BitReader::new(&self.content[self.data_start..]);
+        // Every element takes at least P + 1 bits, so a bogus count can't force a huge allocation.
+        let mut values = Vec::with_capacity((self.n as usize).min(self.content.len()));
+        let mut value = 0u64;
+        for _ in 0..self.n {
+            let mut quotient = 0u64;
+            while reader.read_bit().ok_or(BlockFilterError::Truncated)? {
+                quotient += 1;
+            }
+            let remainder = reader
+                .read_bits(BASIC_FILTER_P)
+                .ok_or(BlockFilterError::Truncated)?;
+            value = value.wrapping_add((quotient << BASIC_FILTER_P) | remainder);
+            values.push(value);
+        }
+        Ok(values)
+    }
+    // Whether any of the scripts may be in the block's filter. block_hash is the hash of the block
+    // the filter is for, which keys the filter's hash function.
+    pub fn match_any<I, S>(
+        &self,
+        block_hash: &Hash256,
+        scripts: I,
+    ) -> Result<bool, BlockFilterError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<[u8]>,
+    {
+        if self.n == 0 {
+            return Ok(false);
+        }
+        let (k0, k1) = siphash_key(block_hash);
+        let range = self.n * BASIC_FILTER_M;
+        let mut queries: Vec<u64> = scripts
+            .into_iter()
+            .map(|script| hash_to_range(k0, k1, script.as_ref(), range))
+            .collect();
+        if queries.is_empty() {
+            return Ok(false);
+        }
+        queries.sort_unstable();
+        // Both lists are sorted, so walk them together.
+        let elements = self.decode()?;
+        let mut queries = queries.iter().peekable();
+        for element in elements {
+            while let Some(query) = queries.peek() {
+                if **query < element {
+                    queries.next();
+                } else {
+                    break;
+                }
+            }
+            match queries.peek() {
+                Some(query) if **query == element => return Ok(true),
+                Some(_) => {}
+                None => return Ok(false),
+            }
+        }
+        Ok(false)
+    }
+    pub fn matches(&self, block_hash: &Hash256, script: &[u8]) -> Result<bool, BlockFilterError> {
+        self.match_any(block_hash, [script])
+    }
+}
+
+// The first 16 bytes of the block hash, in internal byte order, as two little-endian words.
+fn siphash_key(block_hash: &Hash256) -> (u64, u64) {
+    let k0 = u64::from_le_bytes(block_hash.0[..8].try_into().unwrap());
+    let k1 = u64::from_le_bytes(block_hash.0[8..16].try_into().unwrap());
+    (k0, k1)
+}
+
+// Maps an element uniformly onto [0, range) with a multiply and shift instead of a modulo.
+fn hash_to_range(k0: u64, k1: u64, element: &[u8], range: u64) -> u64 {
+    ((siphash24(k0, k1, element) as u128 * range as u128) >> 64) as u64
+}
+
+// Reads bits most significant first.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        BitReader { data, position: 0 }
+    }
+    fn read_bit(&mut self) -> Option<bool> {
+        let byte = self.data.get(self.position / 8)?;
+        let bit = (byte >> (7 - self.position % 8)) & 1 == 1;
+        self.position += 1;
+        Some(bit)
+    }
+    fn read_bits(&mut self, count: u8) -> Option<u64> {
+        let mut value = 0;
+        for _ in 0..count {
+            value = (value << 1) | self.read_bit()? as u64;
+        }
+        Some(value)
+    }
+}
+
+// Fetches a block's basic filter and tests it against the scripts, e.g. to decide whether the
+// block needs to be downloaded. The filter itself isn't verified (see verify_node_filter_headers).
+pub fn block_filter_matches<I, S>(
+    client: &Client,
+    blockhash: &Blockhash,
+    scripts: I,
+) -> Result<bool, BlockFilterError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<[u8]>,
+{
+    let block_hash: Hash256 = blockhash.0.parse()?;
+    let response = GetBlockFilterCommand::new(Blockhash(blockhash.0.clone())).call(client)?;
+    response.decode_filter()?.match_any(&block_hash, scripts)
+}
+
+// Checks the node's basic filters from `start_height` to `end_height` against its filter
+// headers, returning the verified header at `end_height`. The chain starts from an all zeros
+// header at genesis; otherwise the header the node reports for `start_height - 1` is trusted.
+pub fn verify_node_filter_headers(
+    client: &Client,
+    start_height: u64,
+    end_height: u64,
+) -> Result<Hash256, BlockFilterError> {
+    let mut previous_header = if start_height == 0 {
+        Hash256::all_zeros()
+    } else {
+        fetch_block_filter(client, start_height - 1)?.header
+    };
+    for height in start_height..=end_height {
+        let response = fetch_block_filter(client, height)?;
+        let computed = response.decode_filter()?.header(&previous_header);
+        if computed != response.header {
+            return Err(BlockFilterError::HeaderMismatch {
+                height,
+                computed,
+                reported: response.header,
+            });
+        }
+        previous_header = computed;
+    }
+    Ok(previous_header)
+}
+
+fn fetch_block_filter(
+    client: &Client,
+    height: u64,
+) -> Result<GetBlockFilterCommandResponse, BlockFilterError> {
+    let blockhash = GetBlockHashCommand::new(height).call(client)?.0;
+    Ok(GetBlockFilterCommand::new(blockhash).call(client)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From the BIP158 test vectors, on testnet.
+    const GENESIS_HASH: &str = "000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943";
+    const GENESIS_FILTER: &str = "019dfca8";
+    const GENESIS_FILTER_HEADER: &str =
+        "21584579b7eb08997773e5aeff3a7f932700042d0ed2a6129012b7d7ae81b750";
+    // The genesis coinbase output.
+    const GENESIS_SCRIPT: &str = "4104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac";
+    const BLOCK_1_FILTER_HEADER: &str =
+        "d7bdac13a59d745b1add0d2ce852f1a0442e8945fc1bf3848d3cbffd88c24fe1";
+    const BLOCK_2_FILTER: &str = "0174a170";
+    const BLOCK_2_FILTER_HEADER: &str =
+        "186afd11ef2b5e7e3504f2e8cbf8df28a1fd251fe53d60dff8b1467d1b386cf0";
+    const BLOCK_3_FILTER: &str = "016cf7a0";
+    const BLOCK_3_FILTER_HEADER: &str =
+        "8d63aadf5ab7257cb6d2316a57b16f517bff1c6388f124ec4c04af1212729d2a";
+
+    fn hash(hex: &str) -> Hash256 {
+        hex.parse().unwrap()
+    }
+
+    #[test]
+    fn genesis_filter() {
+        let filter = BlockFilter::from_hex(GENESIS_FILTER).unwrap();
+        assert_eq!(filter.len(), 1);
+        assert_eq!(filter.decode().unwrap().len(), 1);
+        assert_eq!(
+            filter.header(&Hash256::all_zeros()),
+            hash(GENESIS_FILTER_HEADER)
+        );
+    }
+
+    #[test]
+    fn filter_headers_chain() {
+        let filter = BlockFilter::from_hex(BLOCK_2_FILTER).unwrap();
+        let header = filter.header(&hash(BLOCK_1_FILTER_HEADER));
+        assert_eq!(header, hash(BLOCK_2_FILTER_HEADER));
+        let filter = BlockFilter::from_hex(BLOCK_3_FILTER).unwrap();
+        assert_eq!(filter.header(&header), hash(BLOCK_3_FILTER_HEADER));
+        // The wrong previous header gives a different header.
+        assert_ne!(
+            filter.header(&Hash256::all_zeros()),
+            hash(BLOCK_3_FILTER_HEADER)
+        );
+    }
+
+    #[test]
+    fn matches_the_scripts_in_the_block() {
+        let filter = BlockFilter::from_hex(GENESIS_FILTER).unwrap();
+        let block_hash = hash(GENESIS_HASH);
+        let script = decode_hex(GENESIS_SCRIPT).unwrap();
+        assert!(filter.matches(&block_hash, &script).unwrap());
+        assert!(!filter.matches(&block_hash, &[0x00]).unwrap());
+        assert!(filter
+            .match_any(&block_hash, [&[0x00][..], &script])
+            .unwrap());
+        assert!(!filter
+            .match_any(&block_hash, Vec::<Vec<u8>>::new())
+            .unwrap());
+        // The hash function is keyed by the block hash.
+        assert!(!filter
+            .matches(&hash(BLOCK_2_FILTER_HEADER), &script)
+            .unwrap());
+    }
+
+    #[test]
+    fn empty_filter_matches_nothing() {
+        let filter = BlockFilter::from_hex("00").unwrap();
+        assert!(filter.is_empty());
+        assert_eq!(filter.decode().unwrap(), Vec::<u64>::new());
+        assert!(!filter.matches(&hash(GENESIS_HASH), &[0x00]).unwrap());
+    }
+
+    #[test]
+    fn truncated_filter() {
+        // Two elements, but only enough bits for one.
+        let filter = BlockFilter::from_hex("029dfca8").unwrap();
+        assert!(matches!(filter.decode(), Err(BlockFilterError::Truncated)));
+        assert!(BlockFilter::from_hex("").is_err());
+    }
+}
@@ -0,0 +1,670 @@
+// Deserialization of consensus-encoded blocks, block headers and transactions, as returned by
+// getblock (verbosity 0), getblockheader (verbose=false) and getrawtransaction (verbose=false).
+//
+// Decoded transactions use the same typed model as the verbose RPC responses, with txid, hash
+// (wtxid), size, vsize and weight computed locally so they can be checked against the node.
+use std::error::Error;
+use std::fmt;
+
+use crate::consensus::address;
+use crate::consensus::hash::Hash256;
+use crate::consensus::params::Network;
+use crate::consensus::script;
+use crate::types::transaction::{
+    CoinbaseVin, HexEncodedWitnessData, NonCoinbaseVin, ScriptPubKey, ScriptSig, Transaction, Vin,
+    Vout,
+};
+
+pub const BLOCK_HEADER_SIZE: usize = 80;
+pub const WITNESS_SCALE_FACTOR: u64 = 4;
+const MAX_SIZE: u64 = 0x0200_0000;
+const SATOSHIS_PER_BITCOIN: f64 = 100_000_000.0;
+const SEGWIT_MARKER: u8 = 0x00;
+const SEGWIT_FLAG: u8 = 0x01;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    InvalidHex,
+    InvalidLength { expected: usize, actual: usize },
+    UnexpectedEnd,
+    NonCanonicalCompactSize,
+    OversizedCompactSize(u64),
+    UnknownTransactionOptionalData(u8),
+    SuperfluousWitnessRecord,
+    TrailingData(usize),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidHex => write!(f, "invalid hex string"),
+            DecodeError::InvalidLength { expected, actual } => {
+                write!(f, "expected {} bytes, got {}", expected, actual)
+            }
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of data"),
+            DecodeError::NonCanonicalCompactSize => write!(f, "non-canonical compact size"),
+            DecodeError::OversizedCompactSize(size) => {
+                write!(f, "compact size {} exceeds maximum of {}", size, MAX_SIZE)
+            }
+            DecodeError::UnknownTransactionOptionalData(flags) => {
+                write!(
+                    f,
+                    "unknown transaction optional data (flags {:#04x})",
+                    flags
+                )
+            }
+            DecodeError::SuperfluousWitnessRecord => write!(f, "superfluous witness record"),
+            DecodeError::TrailingData(count) => {
+                write!(f, "{} unexpected bytes after end of data", count)
+            }
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    const HEX_CHARS: &[u8] = b"0123456789abcdef";
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(HEX_CHARS[(byte >> 4) as usize] as char);
+        hex.push(HEX_CHARS[(byte & 0x0f) as usize] as char);
+    }
+    hex
+}
+
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>, DecodeError> {
+    fn nibble(character: u8) -> Result<u8, DecodeError> {
+        match character {
+            b'0'..=b'9' => Ok(character - b'0'),
+            b'a'..=b'f' => Ok(character - b'a' + 10),
+            b'A'..=b'F' => Ok(character - b'A' + 10),
+            _ => Err(DecodeError::InvalidHex),
+        }
+    }
+    let hex = hex.trim().as_bytes();
+    if !hex.len().is_multiple_of(2) {
+        return Err(DecodeError::InvalidHex);
+    }
+    hex.chunks(2)
+        .map(|pair| Ok((nibble(pair[0])? << 4) | nibble(pair[1])?))
+        .collect()
+}
+
+// Cursor over consensus-encoded bytes.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data, position: 0 }
+    }
+    pub fn position(&self) -> usize {
+        self.position
+    }
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+    pub fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], DecodeError> {
+        if count > self.remaining() {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let bytes = &self.data[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+    pub fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.read_array::<1>()?[0])
+    }
+    pub fn read_u16(&mut self) -> Result<u16, DecodeError> {
+        Ok(u16::from_le_bytes(self.read_array()?))
+    }
+    pub fn read_u32(&mut self) -> Result<u32, DecodeError> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+    pub fn read_i32(&mut self) -> Result<i32, DecodeError> {
+        Ok(i32::from_le_bytes(self.read_array()?))
+    }
+    pub fn read_u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+    pub fn read_hash(&mut self) -> Result<Hash256, DecodeError> {
+        Ok(Hash256(self.read_array()?))
+    }
+    // Reads a CompactSize, rejecting non-minimal encodings and sizes above 32MiB like core does.
+    pub fn read_compact_size(&mut self) -> Result<u64, DecodeError> {
+        let size = match self.read_u8()? {
+            0xfd => {
+                let size = self.read_u16()? as u64;
+                if size < 0xfd {
+                    return Err(DecodeError::NonCanonicalCompactSize);
+                }
+                size
+            }
+            0xfe => {
+                let size = self.read_u32()? as u64;
+                if size < 0x1_0000 {
+                    return Err(DecodeError::NonCanonicalCompactSize);
+                }
+                size
+            }
+            0xff => {
+                let size = self.read_u64()?;
+                if size < 0x1_0000_0000 {
+                    return Err(DecodeError::NonCanonicalCompactSize);
+                }
+                size
+            }
+            size => size as u64,
+        };
+        if size > MAX_SIZE {
+            return Err(DecodeError::OversizedCompactSize(size));
+        }
+        Ok(size)
+    }
+    pub fn read_var_bytes(&mut self) -> Result<&'a [u8], DecodeError> {
+        let length = self.read_compact_size()? as usize;
+        self.read_bytes(length)
+    }
+    pub fn finish(self) -> Result<(), DecodeError> {
+        match self.remaining() {
+            0 => Ok(()),
+            count => Err(DecodeError::TrailingData(count)),
+        }
+    }
+}
+
+pub fn write_compact_size(buffer: &mut Vec<u8>, size: u64) {
+    match size {
+        0..=0xfc => buffer.push(size as u8),
+        0xfd..=0xffff => {
+            buffer.push(0xfd);
+            buffer.extend_from_slice(&(size as u16).to_le_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            buffer.push(0xfe);
+            buffer.extend_from_slice(&(size as u32).to_le_bytes());
+        }
+        _ => {
+            buffer.push(0xff);
+            buffer.extend_from_slice(&size.to_le_bytes());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodedBlockHeader {
+    pub version: i32,
+    pub previousblockhash: Hash256,
+    pub merkleroot: Hash256,
+    pub time: u32,
+    pub bits: u32,
+    pub nonce: u32,
+}
+
+impl DecodedBlockHeader {
+    pub fn decode(reader: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(DecodedBlockHeader {
+            version: reader.read_i32()?,
+            previousblockhash: reader.read_hash()?,
+            merkleroot: reader.read_hash()?,
+            time: reader.read_u32()?,
+            bits: reader.read_u32()?,
+            nonce: reader.read_u32()?,
+        })
+    }
+    pub fn serialize(&self) -> [u8; BLOCK_HEADER_SIZE] {
+        let mut bytes = [0; BLOCK_HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&self.version.to_le_bytes());
+        bytes[4..36].copy_from_slice(&self.previousblockhash.0);
+        bytes[36..68].copy_from_slice(&self.merkleroot.0);
+        bytes[68..72].copy_from_slice(&self.time.to_le_bytes());
+        bytes[72..76].copy_from_slice(&self.bits.to_le_bytes());
+        bytes[76..80].copy_from_slice(&self.nonce.to_le_bytes());
+        bytes
+    }
+    pub fn hash(&self) -> Hash256 {
+        Hash256::hash(&self.serialize())
+    }
+    // Formatted like the "versionHex" field of getblockheader
+    pub fn version_hex(&self) -> String {
+        format!("{:08x}", self.version)
+    }
+    // Formatted like the "bits" field of getblockheader
+    pub fn bits_hex(&self) -> String {
+        format!("{:08x}", self.bits)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedBlock {
+    pub header: DecodedBlockHeader,
+    pub size: u64,         // The block size
+    pub strippedsize: u64, // The block size excluding witness data
+    pub weight: u64,       // The block weight as defined in BIP 141
+    pub tx: Vec<Transaction>,
+}
+
+impl DecodedBlock {
+    pub fn hash(&self) -> Hash256 {
+        self.header.hash()
+    }
+}
+
+struct TxIn<'a> {
+    prevout_txid: Hash256,
+    prevout_vout: u32,
+    script_sig: &'a [u8],
+    sequence: u32,
+    witness: Vec<&'a [u8]>,
+}
+
+impl TxIn<'_> {
+    fn is_null_prevout(&self) -> bool {
+        self.prevout_txid.is_all_zeros() && self.prevout_vout == u32::MAX
+    }
+}
+
+struct TxOut<'a> {
+    value: u64,
+    script_pubkey: &'a [u8],
+}
+
+fn decode_inputs<'a>(reader: &mut Reader<'a>, count: u64) -> Result<Vec<TxIn<'a>>, DecodeError> {
+    let mut inputs = vec![];
+    for _ in 0..count {
+        inputs.push(TxIn {
+            prevout_txid: reader.read_hash()?,
+            prevout_vout: reader.read_u32()?,
+            script_sig: reader.read_var_bytes()?,
+            sequence: reader.read_u32()?,
+            witness: vec![],
+        });
+    }
+    Ok(inputs)
+}
+
+fn decode_outputs<'a>(reader: &mut Reader<'a>) -> Result<Vec<TxOut<'a>>, DecodeError> {
+    let count = reader.read_compact_size()?;
+    let mut outputs = vec![];
+    for _ in 0..count {
+        outputs.push(TxOut {
+            value: reader.read_u64()?,
+            script_pubkey: reader.read_var_bytes()?,
+        });
+    }
+    Ok(outputs)
+}
+
+// Decodes one transaction starting at the reader's position, following the same rules as core's
+// UnserializeTransaction (with witness serialization allowed).
+fn decode_transaction_at(
+    reader: &mut Reader,
+    network: Network,
+) -> Result<Transaction, DecodeError> {
+    let start = reader.position();
+    let version = reader.read_i32()?;
+    let mut flags = 0;
+    let mut input_count = reader.read_compact_size()?;
+    let mut inputs = vec![];
+    let mut outputs = vec![];
+    if input_count == SEGWIT_MARKER as u64 {
+        // Either a transaction without inputs, or the segwit marker followed by the flag byte.
+        flags = reader.read_u8()?;
+        if flags != 0 {
+            input_count = reader.read_compact_size()?;
+            inputs = decode_inputs(reader, input_count)?;
+            outputs = decode_outputs(reader)?;
+        }
+    } else {
+        inputs = decode_inputs(reader, input_count)?;
+        outputs = decode_outputs(reader)?;
+    }
+    let mut witness_bytes = 0;
+    if flags & SEGWIT_FLAG != 0 {
+        flags ^= SEGWIT_FLAG;
+        let witness_start = reader.position();
+        for input in inputs.iter_mut() {
+            let item_count = reader.read_compact_size()?;
+            for _ in 0..item_count {
+                input.witness.push(reader.read_var_bytes()?);
+            }
+        }
+        witness_bytes = reader.position() - witness_start;
+        if inputs.iter().all(|input| input.witness.is_empty()) {
+            return Err(DecodeError::SuperfluousWitnessRecord);
+        }
+    }
+    if flags != 0 {
+        return Err(DecodeError::UnknownTransactionOptionalData(flags));
+    }
+    let lock_time = reader.read_u32()?;
+    let end = reader.position();
+    let raw = &reader.data[start..end];
+
+    let has_witness = witness_bytes > 0;
+    let stripped = if has_witness {
+        // version || inputs and outputs (without marker and flag) || lock time
+        let mut stripped = Vec::with_capacity(raw.len() - witness_bytes - 2);
+        stripped.extend_from_slice(&raw[..4]);
+        stripped.extend_from_slice(&raw[6..raw.len() - 4 - witness_bytes]);
+        stripped.extend_from_slice(&raw[raw.len() - 4..]);
+        stripped
+    } else {
+        raw.to_vec()
+    };
+    let size = raw.len() as u64;
+    let stripped_size = stripped.len() as u64;
+    let weight = stripped_size * (WITNESS_SCALE_FACTOR - 1) + size;
+
+    let is_coinbase = inputs.len() == 1 && inputs[0].is_null_prevout();
+    let vin = inputs
+        .into_iter()
+        .map(|input| {
+            let txinwitness = if input.witness.is_empty() {
+                None
+            } else {
+                Some(
+                    input
+                        .witness
+                        .iter()
+                        .map(|item| HexEncodedWitnessData(encode_hex(item)))
+                        .collect(),
+                )
+            };
+            if is_coinbase {
+                Vin::Coinbase(CoinbaseVin {
+                    coinbase: encode_hex(input.script_sig),
+                    sequence: input.sequence as u64,
+                    txinwitness,
+                })
+            } else {
+                Vin::NonCoinbase(NonCoinbaseVin {
+                    txid: input.prevout_txid.to_rpc_hex(),
+                    vout: input.prevout_vout as u64,
+                    script_sig: ScriptSig {
+                        asm: script::to_asm(input.script_sig, true),
+                        hex: encode_hex(input.script_sig),
+                    },
+                    sequence: input.sequence as u64,
+                    txinwitness,
+                    prevout: None,
+                })
+            }
+        })
+        .collect();
+    let vout = outputs
+        .into_iter()
+        .enumerate()
+        .map(|(n, output)| Vout {
+            value: output.value as f64 / SATOSHIS_PER_BITCOIN,
+            n: n as u64,
+            script_pub_key: ScriptPubKey {
+                asm: script::to_asm(output.script_pubkey, false),
+                desc: None,
+                hex: encode_hex(output.script_pubkey),
+                req_sigs: None,
+                type_: script::classify(output.script_pubkey).as_str().to_string(),
+                address: address::from_script_pubkey(output.script_pubkey, network),
+                addresses: None,
+            },
+        })
+        .collect();
+
+    Ok(Transaction {
+        in_active_chain: None,
+        hex: Some(encode_hex(raw)),
+        txid: Hash256::hash(&stripped).to_rpc_hex(),
+        hash: Hash256::hash(raw).to_rpc_hex(),
+        size,
+        vsize: weight.div_ceil(WITNESS_SCALE_FACTOR),
+        weight,
+        // core reports the version as an unsigned 32 bit integer from bitcoin core 28 on
+        version: version as u32 as i64,
+        locktime: lock_time as u64,
+        vin,
+        vout,
+        fee: None,
+        blockhash: None,
+        confirmations: None,
+        blocktime: None,
+        time: None,
+    })
+}
+
+// Decodes the hex returned by getblockheader with verbose=false.
+pub fn decode_block_header(hex: &str) -> Result<DecodedBlockHeader, DecodeError> {
+    let bytes = decode_hex(hex)?;
+    if bytes.len() != BLOCK_HEADER_SIZE {
+        return Err(DecodeError::InvalidLength {
+            expected: BLOCK_HEADER_SIZE,
+            actual: bytes.len(),
+        });
+    }
+    let mut reader = Reader::new(&bytes);
+    DecodedBlockHeader::decode(&mut reader)
+}
+
+// Decodes the hex returned by getrawtransaction with verbose=false. The network is needed to
+// encode output addresses.
+pub fn decode_transaction(hex: &str, network: Network) -> Result<Transaction, DecodeError> {
+    let bytes = decode_hex(hex)?;
+    let mut reader = Reader::new(&bytes);
+    let transaction = decode_transaction_at(&mut reader, network)?;
+    reader.finish()?;
+    Ok(transaction)
+}
+
+// Decodes the hex returned by getblock with verbosity 0. The network is needed to encode output
+// addresses.
+pub fn decode_block(hex: &str, network: Network) -> Result<DecodedBlock, DecodeError> {
+    let bytes = decode_hex(hex)?;
+    let mut reader = Reader::new(&bytes);
+    let header = DecodedBlockHeader::decode(&mut reader)?;
+    let transaction_count = reader.read_compact_size()?;
+    let mut tx = vec![];
+    for _ in 0..transaction_count {
+        tx.push(decode_transaction_at(&mut reader, network)?);
+    }
+    reader.finish()?;
+
+    let size = bytes.len() as u64;
+    // A transaction's weight is 3 * stripped size + size, which gives us its witness bytes.
+    let witness_size: u64 = tx
+        .iter()
+        .map(|transaction| {
+            let stripped_size =
+                (transaction.weight - transaction.size) / (WITNESS_SCALE_FACTOR - 1);
+            transaction.size - stripped_size
+        })
+        .sum();
+    let strippedsize = size - witness_size;
+    Ok(DecodedBlock {
+        header,
+        size,
+        strippedsize,
+        weight: strippedsize * (WITNESS_SCALE_FACTOR - 1) + size,
+        tx,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENESIS_BLOCK: &str = "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c0101000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+    const BLOCK_1: &str = "010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e362990101000000010000000000000000000000000000000000000000000000000000000000000000ffffffff0704ffff001d0104ffffffff0100f2052a0100000043410496b538e853519c726a2c91e61ec11600ae1390813a627c66fb8be7947be63c52da7589379515d4e0a604f8141781e62294721166bf621e73a82cbf2342c858eeac00000000";
+    // The signed native P2WPKH example from BIP143: a legacy input and a segwit input.
+    const SEGWIT_TRANSACTION: &str = "01000000000102fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f00000000494830450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac000247304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee0121025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee635711000000";
+
+    #[test]
+    fn genesis_block() {
+        let block = decode_block(GENESIS_BLOCK, Network::Main).unwrap();
+        assert_eq!(
+            block.hash().to_rpc_hex(),
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        );
+        assert!(block.header.previousblockhash.is_all_zeros());
+        assert_eq!(
+            block.header.merkleroot.to_rpc_hex(),
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"
+        );
+        assert_eq!(block.header.version_hex(), "00000001");
+        assert_eq!(block.header.bits_hex(), "1d00ffff");
+        assert_eq!(block.header.time, 1231006505);
+        assert_eq!(block.header.nonce, 2083236893);
+        assert_eq!(
+            (block.size, block.strippedsize, block.weight),
+            (285, 285, 1140)
+        );
+
+        assert_eq!(block.tx.len(), 1);
+        let coinbase = &block.tx[0];
+        assert!(coinbase.is_coinbase());
+        assert_eq!(coinbase.txid, block.header.merkleroot.to_rpc_hex());
+        assert_eq!(coinbase.hash, coinbase.txid);
+        assert_eq!(
+            (coinbase.size, coinbase.vsize, coinbase.weight),
+            (204, 204, 816)
+        );
+        assert_eq!(coinbase.vout[0].value, 50.0);
+        assert_eq!(coinbase.vout[0].script_pub_key.type_, "pubkey");
+        assert_eq!(coinbase.vout[0].script_pub_key.address, None);
+    }
+
+    #[test]
+    fn block_1() {
+        let block = decode_block(BLOCK_1, Network::Main).unwrap();
+        assert_eq!(
+            block.hash().to_rpc_hex(),
+            "00000000839a8e6886ab5951d76f411475428afc90947ee320161bbf18eb6048"
+        );
+        assert_eq!(
+            block.header.previousblockhash.to_rpc_hex(),
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        );
+        assert_eq!(
+            block.tx[0].txid,
+            "0e3e2357e806b6cdb1f70b54c3a3a17b6714ee1f0e68bebb44a74b1efd512098"
+        );
+        assert_eq!(block.tx[0].txid, block.header.merkleroot.to_rpc_hex());
+        assert_eq!(block.size, 215);
+    }
+
+    #[test]
+    fn block_header_round_trips() {
+        let header = decode_block_header(&BLOCK_1[..160]).unwrap();
+        assert_eq!(encode_hex(&header.serialize()), BLOCK_1[..160]);
+        assert_eq!(
+            decode_block_header(&BLOCK_1[..158]),
+            Err(DecodeError::InvalidLength {
+                expected: 80,
+                actual: 79
+            })
+        );
+    }
+
+    #[test]
+    fn segwit_transaction() {
+        let transaction = decode_transaction(SEGWIT_TRANSACTION, Network::Main).unwrap();
+        assert_eq!(
+            transaction.txid,
+            "e8151a2af31c368a35053ddd4bdb285a8595c769a3ad83e0fa02314a602d4609"
+        );
+        assert_eq!(
+            transaction.hash,
+            "c36c38370907df2324d9ce9d149d191192f338b37665a82e78e76a12c909b762"
+        );
+        assert_ne!(transaction.txid, transaction.hash);
+        // 233 bytes stripped of the witness, 343 with it.
+        assert_eq!(transaction.size, 343);
+        assert_eq!(transaction.weight, 233 * 3 + 343);
+        assert_eq!(transaction.vsize, 261);
+        assert_eq!(transaction.version, 1);
+        assert_eq!(transaction.locktime, 17);
+
+        assert_eq!(transaction.vin.len(), 2);
+        assert_eq!(transaction.vin[0].txinwitness(), None);
+        let witness = transaction.vin[1].txinwitness().unwrap();
+        assert_eq!(witness.len(), 2);
+        assert_eq!(
+            witness[1].0,
+            "025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee6357"
+        );
+        assert_eq!(
+            transaction.vin[1].outpoint().unwrap().txid,
+            "8ac60eb9575db5b2d987e29f301b5b819ea83a5c6579d282d189cc04b8e151ef"
+        );
+        assert_eq!(transaction.vout[0].value, 1.1234);
+        assert_eq!(transaction.vout[1].value, 2.2345);
+    }
+
+    #[test]
+    fn a_legacy_transaction_has_the_same_txid_and_wtxid() {
+        let coinbase = &GENESIS_BLOCK[162..];
+        let transaction = decode_transaction(coinbase, Network::Main).unwrap();
+        assert_eq!(transaction.txid, transaction.hash);
+        assert_eq!(transaction.weight, transaction.size * 4);
+        assert_eq!(transaction.hex.as_deref(), Some(coinbase));
+    }
+
+    #[test]
+    fn rejects_malformed_transactions() {
+        let coinbase = &GENESIS_BLOCK[162..];
+        assert_eq!(
+            decode_transaction(&coinbase[..coinbase.len() - 2], Network::Main),
+            Err(DecodeError::UnexpectedEnd)
+        );
+        assert_eq!(
+            decode_transaction(&format!("{}00", coinbase), Network::Main),
+            Err(DecodeError::TrailingData(1))
+        );
+        // The segwit flag with no witness data.
+        let superfluous = format!(
+            "{}0001{}00{}",
+            &coinbase[..8],
+            &coinbase[8..coinbase.len() - 8],
+            &coinbase[coinbase.len() - 8..]
+        );
+        assert_eq!(
+            decode_transaction(&superfluous, Network::Main),
+            Err(DecodeError::SuperfluousWitnessRecord)
+        );
+        assert_eq!(
+            decode_transaction("0g", Network::Main),
+            Err(DecodeError::InvalidHex)
+        );
+    }
+
+    #[test]
+    fn compact_sizes() {
+        for size in [0, 0xfc, 0xfd, 0xffff, 0x1_0000, MAX_SIZE] {
+            let mut buffer = vec![];
+            write_compact_size(&mut buffer, size);
+            let mut reader = Reader::new(&buffer);
+            assert_eq!(reader.read_compact_size(), Ok(size));
+            reader.finish().unwrap();
+        }
+        let read = |bytes: &[u8]| Reader::new(bytes).read_compact_size();
+        assert_eq!(
+            read(&[0xfd, 0xfc, 0x00]),
+            Err(DecodeError::NonCanonicalCompactSize)
+        );
+        assert_eq!(
+            read(&[0xfe, 0xff, 0xff, 0x00, 0x00]),
+            Err(DecodeError::NonCanonicalCompactSize)
+        );
+        assert_eq!(
+            read(&[0xfe, 0x01, 0x00, 0x00, 0x02]),
+            Err(DecodeError::OversizedCompactSize(MAX_SIZE + 1))
+        );
+        assert_eq!(read(&[0xfd, 0x00]), Err(DecodeError::UnexpectedEnd));
+    }
+}
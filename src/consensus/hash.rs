@@ -0,0 +1,191 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::consensus::encode::{decode_hex, encode_hex, DecodeError};
+
+pub fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+// Bitcoin's double SHA-256, used for txids, block hashes and merkle trees.
+pub fn sha256d(data: &[u8]) -> [u8; 32] {
+    sha256(&sha256(data))
+}
+
+// SipHash-2-4 with the key given as two little-endian words, as used by BIP158 block filters.
+pub fn siphash24(k0: u64, k1: u64, data: &[u8]) -> u64 {
+    let mut v = [
+        k0 ^ 0x736f_6d65_7073_6575,
+        k1 ^ 0x646f_7261_6e64_6f6d,
+        k0 ^ 0x6c79_6765_6e65_7261,
+        k1 ^ 0x7465_6462_7974_6573,
+    ];
+    let chunks = data.chunks_exact(8);
+    let tail = chunks.remainder();
+    for chunk in chunks {
+        let m = u64::from_le_bytes(chunk.try_into().unwrap());
+        v[3] ^= m;
+        sip_round(&mut v);
+        sip_round(&mut v);
+        v[0] ^= m;
+    }
+    // The last word holds the remaining bytes and the message length in its top byte.
+    let mut last = (data.len() as u64) << 56;
+    for (index, byte) in tail.iter().enumerate() {
+        last |= (*byte as u64) << (8 * index);
+    }
+    v[3] ^= last;
+    sip_round(&mut v);
+    sip_round(&mut v);
+    v[0] ^= last;
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13);
+    v[1] ^= v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16);
+    v[3] ^= v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21);
+    v[3] ^= v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17);
+    v[1] ^= v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+// A 32 byte hash stored in internal (little-endian) byte order. Bitcoin Core displays these
+// byte-reversed, so Display/FromStr and serde use the reversed hex seen in RPC responses.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Hash256(pub [u8; 32]);
+
+impl Hash256 {
+    pub fn hash(data: &[u8]) -> Self {
+        Hash256(sha256d(data))
+    }
+    pub fn all_zeros() -> Self {
+        Hash256([0; 32])
+    }
+    pub fn is_all_zeros(&self) -> bool {
+        self.0.iter().all(|byte| *byte == 0)
+    }
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+    pub fn from_rpc_hex(hex: &str) -> Result<Self, DecodeError> {
+        let bytes = decode_hex(hex)?;
+        if bytes.len() != 32 {
+            return Err(DecodeError::InvalidLength {
+                expected: 32,
+                actual: bytes.len(),
+            });
+        }
+        let mut hash = [0; 32];
+        for (index, byte) in bytes.iter().rev().enumerate() {
+            hash[index] = *byte;
+        }
+        Ok(Hash256(hash))
+    }
+    pub fn to_rpc_hex(&self) -> String {
+        let mut bytes = self.0;
+        bytes.reverse();
+        encode_hex(&bytes)
+    }
+}
+
+impl fmt::Display for Hash256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_rpc_hex())
+    }
+}
+
+impl fmt::Debug for Hash256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hash256({})", self.to_rpc_hex())
+    }
+}
+
+impl FromStr for Hash256 {
+    type Err = DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Hash256::from_rpc_hex(s)
+    }
+}
+
+impl Serialize for Hash256 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_rpc_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Hash256 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Hash256::from_rpc_hex(&hex).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_vectors() {
+        assert_eq!(
+            encode_hex(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            encode_hex(&sha256d(b"")),
+            "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456"
+        );
+    }
+
+    // From the reference implementation's vectors: the key is the bytes 0 to 15 and the message
+    // the first n bytes of 0, 1, 2, ...
+    #[test]
+    fn siphash24_reference_vectors() {
+        let k0 = 0x0706_0504_0302_0100;
+        let k1 = 0x0f0e_0d0c_0b0a_0908;
+        let message: Vec<u8> = (0..64).collect();
+        assert_eq!(siphash24(k0, k1, &message[..0]), 0x726f_db47_dd0e_0e31);
+        assert_eq!(siphash24(k0, k1, &message[..1]), 0x74f8_39c5_93dc_67fd);
+        assert_eq!(siphash24(k0, k1, &message[..8]), 0x93f5_f579_9a93_2462);
+        assert_eq!(siphash24(k0, k1, &message[..15]), 0xa129_ca61_49be_45e5);
+        assert_eq!(siphash24(k0, k1, &message[..63]), 0x958a_324c_eb06_4572);
+    }
+
+    #[test]
+    fn rpc_hex_is_byte_reversed() {
+        let hex = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+        let hash: Hash256 = hex.parse().unwrap();
+        assert_eq!(hash.0[0], 0x6f);
+        assert_eq!(hash.0[31], 0x00);
+        assert_eq!(hash.to_string(), hex);
+        assert_eq!(
+            serde_json::to_string(&hash).unwrap(),
+            format!("\"{}\"", hex)
+        );
+        assert_eq!(
+            serde_json::from_str::<Hash256>(&format!("\"{}\"", hex)).unwrap(),
+            hash
+        );
+        assert_eq!(
+            Hash256::from_rpc_hex("00ff"),
+            Err(DecodeError::InvalidLength {
+                expected: 32,
+                actual: 2
+            })
+        );
+    }
+}
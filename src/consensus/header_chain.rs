@@ -0,0 +1,911 @@
+// Verification of a chain of block headers, so a client can audit that a node isn't feeding it a
+// fake chain. Each header is checked the way bitcoin core checks headers it receives from peers:
+// its hash must meet the target encoded by its bits, the bits must follow the difficulty
+// adjustment rules, its time must be after the median time of the previous 11 blocks and it must
+// commit to the hash of the previous header. Chainwork is recomputed along the way so it can be
+// compared with what the node reports.
+//
+// Signet block signatures and the "time too new" rule (which depends on the wall clock) aren't
+// checked.
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+
+use crate::client::Client;
+use crate::command::get_block_hash::GetBlockHashCommand;
+use crate::command::get_block_header::{
+    BlockHeader, GetBlockHeaderCommand, GetBlockHeaderCommandResponse,
+};
+use crate::command::CallableCommand;
+use crate::consensus::encode::{decode_block_header, DecodeError, DecodedBlockHeader};
+use crate::consensus::hash::Hash256;
+use crate::consensus::params::{
+    Network, DIFFICULTY_ADJUSTMENT_INTERVAL, MAX_TIMEWARP, MEDIAN_TIME_SPAN, POW_TARGET_SPACING,
+};
+use crate::consensus::pow::{calculate_next_work_required, check_proof_of_work, target_to_compact};
+use crate::types::work::{Bits, Work};
+
+#[derive(Debug)]
+pub enum HeaderChainError {
+    // The header at height 0 isn't the network's genesis block.
+    BadGenesis {
+        expected: Hash256,
+        actual: Hash256,
+    },
+    // The header doesn't commit to the hash of the header before it.
+    BadPrevBlock {
+        height: u64,
+        expected: Hash256,
+        actual: Hash256,
+    },
+    // The header's hash doesn't meet the target encoded by its bits.
+    HighHash {
+        height: u64,
+        hash: Hash256,
+        bits: u32,
+    },
+    // The header's bits don't follow the difficulty adjustment rules.
+    BadDiffBits {
+        height: u64,
+        expected: u32,
+        actual: u32,
+    },
+    // The header's time isn't after the median time of the previous 11 blocks.
+    TimeTooOld {
+        height: u64,
+        time: u32,
+        median_time_past: u32,
+    },
+    // BIP94: the first block of a retarget period is too far before its parent.
+    TimewarpAttack {
+        height: u64,
+        time: u32,
+        previous_time: u32,
+    },
+    // Verifying the header needs an ancestor the verifier wasn't given.
+    InsufficientContext {
+        height: u64,
+        required_height: u64,
+    },
+    // The recomputed chainwork doesn't match the chainwork the node reported.
+    ChainworkMismatch {
+        height: u64,
+        computed: Work,
+        reported: Work,
+    },
+    // The node's header at a checkpoint's height isn't the checkpoint.
+    CheckpointMismatch {
+        height: u64,
+        checkpoint: Hash256,
+        reported: Hash256,
+    },
+    // The node returned a different header for a height than the one that was verified.
+    HashMismatch {
+        height: u64,
+        verified: Hash256,
+        reported: Hash256,
+    },
+    Decode {
+        height: u64,
+        error: DecodeError,
+    },
+    UnexpectedResponse {
+        height: u64,
+    },
+    Rpc(jsonrpc::Error),
+}
+
+impl fmt::Display for HeaderChainError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HeaderChainError::BadGenesis { expected, actual } => write!(
+                f,
+                "header {} isn't the network's genesis block {}",
+                actual, expected
+            ),
+            HeaderChainError::BadPrevBlock {
+                height,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "header {} builds on {} instead of {}",
+                height, actual, expected
+            ),
+            HeaderChainError::HighHash { height, hash, bits } => write!(
+                f,
+                "header {} hash {} doesn't meet the target of bits {:08x}",
+                height, hash, bits
+            ),
+            HeaderChainError::BadDiffBits {
+                height,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "header {} has bits {:08x}, expected {:08x}",
+                height, actual, expected
+            ),
+            HeaderChainError::TimeTooOld {
+                height,
+                time,
+                median_time_past,
+            } => write!(
+                f,
+                "header {} time {} isn't after the median time past {}",
+                height, time, median_time_past
+            ),
+            HeaderChainError::TimewarpAttack {
+                height,
+                time,
+                previous_time,
+            } => write!(
+                f,
+                "header {} time {} is more than {} seconds before its parent's time {}",
+                height, time, MAX_TIMEWARP, previous_time
+            ),
+            HeaderChainError::InsufficientContext {
+                height,
+                required_height,
+            } => write!(
+                f,
+                "verifying header {} requires header {}, which wasn't provided",
+                height, required_height
+            ),
+            HeaderChainError::ChainworkMismatch {
+                height,
+                computed,
+                reported,
+            } => write!(
+                f,
+                "header {} chainwork is {} but the node reported {}",
+                height, computed, reported
+            ),
+            HeaderChainError::CheckpointMismatch {
+                height,
+                checkpoint,
+                reported,
+            } => write!(
+                f,
+                "the node reported block {} at height {}, but the checkpoint is {}",
+                reported, height, checkpoint
+            ),
+            HeaderChainError::HashMismatch {
+                height,
+                verified,
+                reported,
+            } => write!(
+                f,
+                "the node reported block {} at height {}, but {} was verified",
+                reported, height, verified
+            ),
+            HeaderChainError::Decode { height, error } => {
+                write!(f, "failed to decode header {}: {}", height, error)
+            }
+            HeaderChainError::UnexpectedResponse { height } => {
+                write!(
+                    f,
+                    "unexpected getblockheader response for header {}",
+                    height
+                )
+            }
+            HeaderChainError::Rpc(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for HeaderChainError {}
+
+impl From<jsonrpc::Error> for HeaderChainError {
+    fn from(error: jsonrpc::Error) -> Self {
+        HeaderChainError::Rpc(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifiedHeader {
+    pub height: u64,
+    pub hash: Hash256,
+    pub header: DecodedBlockHeader,
+    pub mediantime: u32, // The median time of this block and the 10 before it, like getblockheader
+    pub chainwork: Work, // Expected number of hashes required to produce the chain up to this block
+}
+
+impl VerifiedHeader {
+    // Compares the recomputed chainwork with the "chainwork" reported by getblockheader.
+    pub fn check_reported_chainwork(&self, reported: Work) -> Result<(), HeaderChainError> {
+        if reported != self.chainwork {
+            return Err(HeaderChainError::ChainworkMismatch {
+                height: self.height,
+                computed: self.chainwork,
+                reported,
+            });
+        }
+        Ok(())
+    }
+}
+
+// Verifies headers one at a time, in height order. It keeps the last 2016 verified headers, which
+// is as far back as the difficulty adjustment and median time rules look.
+pub struct HeaderChainVerifier {
+    network: Network,
+    recent: VecDeque<VerifiedHeader>,
+}
+
+impl HeaderChainVerifier {
+    // A verifier that expects the network's genesis block header first.
+    pub fn new(network: Network) -> Self {
+        HeaderChainVerifier {
+            network,
+            recent: VecDeque::new(),
+        }
+    }
+
+    // A verifier that continues from already trusted headers (e.g. a checkpoint, or headers
+    // audited earlier), starting at `first_height` and ending with a header whose chainwork is
+    // `chainwork`. The trusted headers are only checked for linkage, and a header at height 0 must
+    // be the network's genesis block. Give at least the 2016 headers before the first one to
+    // verify, or verification fails with InsufficientContext when it needs an older ancestor.
+    pub fn from_trusted_headers(
+        network: Network,
+        first_height: u64,
+        chainwork: Work,
+        headers: &[DecodedBlockHeader],
+    ) -> Result<Self, HeaderChainError> {
+        let mut verifier = HeaderChainVerifier::new(network);
+        // Work back from the last header's chainwork to the chainwork of each earlier header.
+        let mut chainworks = vec![chainwork; headers.len()];
+        for index in (1..headers.len()).rev() {
+            chainworks[index - 1] = chainworks[index] - Bits(headers[index].bits).work();
+        }
+        for (index, header) in headers.iter().enumerate() {
+            let height = first_height + index as u64;
+            if height == 0 {
+                check_genesis(&header.hash(), network)?;
+            }
+            if let Some(previous) = verifier.tip() {
+                check_prev_block(height, header, Some(previous))?;
+            }
+            let verified = VerifiedHeader {
+                height,
+                hash: header.hash(),
+                header: *header,
+                mediantime: verifier.median_time_past_with(header.time),
+                chainwork: chainworks[index],
+            };
+            verifier.push(verified);
+        }
+        Ok(verifier)
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    // The last verified (or trusted) header.
+    pub fn tip(&self) -> Option<&VerifiedHeader> {
+        self.recent.back()
+    }
+
+    pub fn verify_hex(&mut self, hex: &str) -> Result<VerifiedHeader, HeaderChainError> {
+        let header = decode_block_header(hex).map_err(|error| HeaderChainError::Decode {
+            height: self.next_height(),
+            error,
+        })?;
+        self.verify(header)
+    }
+
+    // Verifies the header that follows the tip and makes it the new tip.
+    pub fn verify(
+        &mut self,
+        header: DecodedBlockHeader,
+    ) -> Result<VerifiedHeader, HeaderChainError> {
+        let height = self.next_height();
+        let hash = header.hash();
+        if !check_proof_of_work(&hash, header.bits, self.network) {
+            return Err(HeaderChainError::HighHash {
+                height,
+                hash,
+                bits: header.bits,
+            });
+        }
+        let chainwork = match self.tip() {
+            None => {
+                check_genesis(&hash, self.network)?;
+                check_prev_block(height, &header, None)?;
+                Bits(header.bits).work()
+            }
+            Some(previous) => {
+                check_prev_block(height, &header, Some(previous))?;
+                let expected_bits = self.next_work_required(&header)?;
+                if header.bits != expected_bits {
+                    return Err(HeaderChainError::BadDiffBits {
+                        height,
+                        expected: expected_bits,
+                        actual: header.bits,
+                    });
+                }
+                self.check_time(&header)?;
+                previous.chainwork + Bits(header.bits).work()
+            }
+        };
+        let verified = VerifiedHeader {
+            height,
+            hash,
+            header,
+            mediantime: self.median_time_past_with(header.time),
+            chainwork,
+        };
+        self.push(verified);
+        Ok(verified)
+    }
+
+    fn next_height(&self) -> u64 {
+        self.tip().map_or(0, |tip| tip.height + 1)
+    }
+
+    fn push(&mut self, verified: VerifiedHeader) {
+        self.recent.push_back(verified);
+        while self.recent.len() > DIFFICULTY_ADJUSTMENT_INTERVAL as usize {
+            self.recent.pop_front();
+        }
+    }
+
+    fn ancestor(&self, height: u64) -> Result<&VerifiedHeader, HeaderChainError> {
+        let next_height = self.next_height();
+        let first_height = self
+            .recent
+            .front()
+            .map_or(next_height, |first| first.height);
+        if height < first_height || height >= next_height {
+            return Err(HeaderChainError::InsufficientContext {
+                height: next_height,
+                required_height: height,
+            });
+        }
+        Ok(&self.recent[(height - first_height) as usize])
+    }
+
+    fn median_time_past(&self) -> u32 {
+        let mut times: Vec<u32> = self
+            .recent
+            .iter()
+            .rev()
+            .take(MEDIAN_TIME_SPAN)
+            .map(|verified| verified.header.time)
+            .collect();
+        times.sort_unstable();
+        times.get(times.len() / 2).copied().unwrap_or(0)
+    }
+
+    // The median time past of a header about to be added with `time`.
+    fn median_time_past_with(&self, time: u32) -> u32 {
+        let mut times: Vec<u32> = self
+            .recent
+            .iter()
+            .rev()
+            .take(MEDIAN_TIME_SPAN - 1)
+            .map(|verified| verified.header.time)
+            .collect();
+        times.push(time);
+        times.sort_unstable();
+        times[times.len() / 2]
+    }
+
+    fn check_time(&self, header: &DecodedBlockHeader) -> Result<(), HeaderChainError> {
+        let height = self.next_height();
+        // The median needs the previous 11 headers, or all of them near genesis.
+        let required = (MEDIAN_TIME_SPAN as u64).min(height);
+        if (self.recent.len() as u64) < required {
+            return Err(HeaderChainError::InsufficientContext {
+                height,
+                required_height: height - required,
+            });
+        }
+        let median_time_past = self.median_time_past();
+        if header.time <= median_time_past {
+            return Err(HeaderChainError::TimeTooOld {
+                height,
+                time: header.time,
+                median_time_past,
+            });
+        }
+        let previous = self.tip().expect("checked after genesis");
+        if self.network.enforce_bip94()
+            && height.is_multiple_of(DIFFICULTY_ADJUSTMENT_INTERVAL)
+            && (header.time as u64) < (previous.header.time as u64).saturating_sub(MAX_TIMEWARP)
+        {
+            return Err(HeaderChainError::TimewarpAttack {
+                height,
+                time: header.time,
+                previous_time: previous.header.time,
+            });
+        }
+        Ok(())
+    }
+
+    // The bits a header following the tip must have (core's GetNextWorkRequired).
+    fn next_work_required(&self, header: &DecodedBlockHeader) -> Result<u32, HeaderChainError> {
+        let previous = self.tip().expect("checked after genesis");
+        let height = previous.height + 1;
+        let pow_limit_bits = target_to_compact(self.network.pow_limit());
+
+        if !height.is_multiple_of(DIFFICULTY_ADJUSTMENT_INTERVAL) {
+            if self.network.pow_allow_min_difficulty_blocks() {
+                // A block more than 20 minutes after the previous one may be minimum difficulty.
+                if header.time as u64 > previous.header.time as u64 + POW_TARGET_SPACING * 2 {
+                    return Ok(pow_limit_bits);
+                }
+                // Otherwise it has the bits of the last block that wasn't minimum difficulty.
+                let mut last = previous;
+                while !last.height.is_multiple_of(DIFFICULTY_ADJUSTMENT_INTERVAL)
+                    && last.header.bits == pow_limit_bits
+                {
+                    last = self.ancestor(last.height - 1)?;
+                }
+                return Ok(last.header.bits);
+            }
+            return Ok(previous.header.bits);
+        }
+
+        if self.network.pow_no_retargeting() {
+            return Ok(previous.header.bits);
+        }
+        let first = self.ancestor(height - DIFFICULTY_ADJUSTMENT_INTERVAL)?;
+        // BIP94 retargets from the period's first block so the min difficulty exception can't
+        // leak into the next period.
+        let period_bits = if self.network.enforce_bip94() {
+            first.header.bits
+        } else {
+            previous.header.bits
+        };
+        Ok(calculate_next_work_required(
+            period_bits,
+            first.header.time,
+            previous.header.time,
+            self.network,
+        ))
+    }
+}
+
+fn check_genesis(hash: &Hash256, network: Network) -> Result<(), HeaderChainError> {
+    let expected = network.genesis_block_hash();
+    if *hash != expected {
+        return Err(HeaderChainError::BadGenesis {
+            expected,
+            actual: *hash,
+        });
+    }
+    Ok(())
+}
+
+fn check_prev_block(
+    height: u64,
+    header: &DecodedBlockHeader,
+    previous: Option<&VerifiedHeader>,
+) -> Result<(), HeaderChainError> {
+    let expected = previous.map_or(Hash256::all_zeros(), |previous| previous.hash);
+    if header.previousblockhash != expected {
+        return Err(HeaderChainError::BadPrevBlock {
+            height,
+            expected,
+            actual: header.previousblockhash,
+        });
+    }
+    Ok(())
+}
+
+// Fetches a raw header with getblockhash and getblockheader (verbose=false) and decodes it
+// locally.
+pub fn fetch_header(client: &Client, height: u64) -> Result<DecodedBlockHeader, HeaderChainError> {
+    let blockhash = GetBlockHashCommand::new(height).call(client)?.0;
+    let mut command = GetBlockHeaderCommand::new(blockhash);
+    command.verbose(false);
+    match command.call(client)? {
+        GetBlockHeaderCommandResponse::BlockHash(hex) => {
+            decode_block_header(&hex).map_err(|error| HeaderChainError::Decode { height, error })
+        }
+        GetBlockHeaderCommandResponse::BlockHeader(_) => {
+            Err(HeaderChainError::UnexpectedResponse { height })
+        }
+    }
+}
+
+// A block the caller trusts without verifying it, e.g. one hardcoded in the application or the tip
+// of an earlier audit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub height: u64,
+    pub hash: Hash256,
+    pub chainwork: Work,
+}
+
+impl From<VerifiedHeader> for Checkpoint {
+    fn from(verified: VerifiedHeader) -> Self {
+        Checkpoint {
+            height: verified.height,
+            hash: verified.hash,
+            chainwork: verified.chainwork,
+        }
+    }
+}
+
+// Audits the node's chain up to `end_height`, returning the verified header at `end_height`.
+// Without a checkpoint verification starts at the network's genesis block. With one it starts
+// after the checkpoint: the node's header at the checkpoint's height must be the checkpoint, and
+// the node's 2016 headers up to it are trusted for their times and bits because the checkpoint's
+// hash commits to them. Nothing before the checkpoint is verified, so it must come from
+// somewhere other than the node being audited. The node's chainwork and hash for `end_height` are
+// checked against the verified header.
+pub fn verify_node_chain(
+    client: &Client,
+    network: Network,
+    checkpoint: Option<Checkpoint>,
+    end_height: u64,
+) -> Result<VerifiedHeader, HeaderChainError> {
+    let (mut verifier, start_height) = match checkpoint {
+        None => (HeaderChainVerifier::new(network), 0),
+        Some(checkpoint) => {
+            let first_height =
+                (checkpoint.height + 1).saturating_sub(DIFFICULTY_ADJUSTMENT_INTERVAL);
+            let mut headers = vec![];
+            for height in first_height..=checkpoint.height {
+                headers.push(fetch_header(client, height)?);
+            }
+            let reported = headers.last().expect("at least the checkpoint").hash();
+            if reported != checkpoint.hash {
+                return Err(HeaderChainError::CheckpointMismatch {
+                    height: checkpoint.height,
+                    checkpoint: checkpoint.hash,
+                    reported,
+                });
+            }
+            let verifier = HeaderChainVerifier::from_trusted_headers(
+                network,
+                first_height,
+                checkpoint.chainwork,
+                &headers,
+            )?;
+            (verifier, checkpoint.height + 1)
+        }
+    };
+    let mut tip = None;
+    for height in start_height..=end_height {
+        tip = Some(verifier.verify(fetch_header(client, height)?)?);
+    }
+    let tip = match tip {
+        Some(tip) => tip,
+        None => {
+            return Err(HeaderChainError::InsufficientContext {
+                height: start_height,
+                required_height: end_height,
+            })
+        }
+    };
+    let reported = fetch_reported_header(client, end_height)?;
+    if reported.hash != tip.hash.to_rpc_hex() {
+        return Err(HeaderChainError::HashMismatch {
+            height: end_height,
+            verified: tip.hash,
+            reported: reported
+                .hash
+                .parse()
+                .map_err(|error| HeaderChainError::Decode {
+                    height: end_height,
+                    error,
+                })?,
+        });
+    }
+    tip.check_reported_chainwork(reported.chainwork)?;
+    Ok(tip)
+}
+
+fn fetch_reported_header(client: &Client, height: u64) -> Result<BlockHeader, HeaderChainError> {
+    let blockhash = GetBlockHashCommand::new(height).call(client)?.0;
+    match GetBlockHeaderCommand::new(blockhash).call(client)? {
+        GetBlockHeaderCommandResponse::BlockHeader(header) => Ok(header),
+        GetBlockHeaderCommandResponse::BlockHash(_) => {
+            Err(HeaderChainError::UnexpectedResponse { height })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::uint::U256;
+
+    const GENESIS_HEADER: &str = "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c";
+    const BLOCK_1_HEADER: &str = "010000006fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000982051fd1e4ba744bbbe680e1fee14677ba1a3c3540bf7b1cdb606e857233e0e61bc6649ffff001d01e36299";
+    // The time of block 2015 on mainnet, the last of the first retarget period.
+    const BLOCK_2015_TIME: u32 = 1233061996;
+    const DIFFICULTY_1_BITS: u32 = 0x1d00ffff;
+    // Times and nonces of difficulty 1 headers mined on top of the chains built below.
+    const SLOW_PERIOD_HEADER: (u32, u32) = (1233062600, 3880332159);
+    const FAST_PERIOD_HEADER: (u32, u32) = (1231612000, 646582150);
+    const MIN_DIFFICULTY_HEADER: (u32, u32) = (1600007201, 682439041);
+    const AFTER_MIN_DIFFICULTY_HEADER: (u32, u32) = (1600007801, 4292283420);
+
+    fn work(blocks: u64) -> Work {
+        Work(U256::from_u64(blocks * 0x1_0001_0001))
+    }
+
+    // Headers following `first` with the given times and bits. Their hashes don't meet their
+    // targets, so they can only be trusted, not verified.
+    fn chain(
+        first: DecodedBlockHeader,
+        count: u64,
+        time: impl Fn(u64) -> u32,
+        bits: u32,
+    ) -> Vec<DecodedBlockHeader> {
+        let mut headers = vec![first];
+        for index in 1..count {
+            let previous = headers[headers.len() - 1];
+            headers.push(DecodedBlockHeader {
+                version: 1,
+                previousblockhash: previous.hash(),
+                merkleroot: Hash256::hash(&index.to_le_bytes()),
+                time: time(index),
+                bits,
+                nonce: 0,
+            });
+        }
+        headers
+    }
+
+    // A difficulty 1 header on top of `previous`, with a (time, nonce) that was mined for it.
+    fn mined(
+        previous: &DecodedBlockHeader,
+        height: u64,
+        (time, nonce): (u32, u32),
+    ) -> DecodedBlockHeader {
+        DecodedBlockHeader {
+            version: 1,
+            previousblockhash: previous.hash(),
+            merkleroot: Hash256::hash(&height.to_le_bytes()),
+            time,
+            bits: DIFFICULTY_1_BITS,
+            nonce,
+        }
+    }
+
+    // The first retarget period on mainnet, from the real genesis block to a block at the real
+    // time of block 2015, with `timespan` seconds between them.
+    fn first_period(timespan: u64) -> Vec<DecodedBlockHeader> {
+        let genesis = decode_block_header(GENESIS_HEADER).unwrap();
+        chain(
+            genesis,
+            DIFFICULTY_ADJUSTMENT_INTERVAL,
+            |index| genesis.time + (timespan * index / 2015) as u32,
+            DIFFICULTY_1_BITS,
+        )
+    }
+
+    // 11 testnet headers at heights 100,000 to 100,010, 10 minutes apart and above minimum
+    // difficulty.
+    fn testnet_headers() -> Vec<DecodedBlockHeader> {
+        let first = DecodedBlockHeader {
+            version: 1,
+            previousblockhash: Hash256::all_zeros(),
+            merkleroot: Hash256::hash(&0u64.to_le_bytes()),
+            time: 1_600_000_000,
+            bits: 0x1c0ffff0,
+            nonce: 0,
+        };
+        chain(
+            first,
+            11,
+            |index| 1_600_000_000 + 600 * index as u32,
+            0x1c0ffff0,
+        )
+    }
+
+    #[test]
+    fn genesis_and_block_1() {
+        let mut verifier = HeaderChainVerifier::new(Network::Main);
+        let genesis = verifier.verify_hex(GENESIS_HEADER).unwrap();
+        assert_eq!(genesis.height, 0);
+        assert_eq!(
+            genesis.hash.to_rpc_hex(),
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        );
+        assert_eq!(genesis.chainwork, work(1));
+        assert_eq!(genesis.mediantime, 1231006505);
+
+        let block_1 = verifier.verify_hex(BLOCK_1_HEADER).unwrap();
+        assert_eq!(block_1.height, 1);
+        assert_eq!(
+            block_1.hash.to_rpc_hex(),
+            "00000000839a8e6886ab5951d76f411475428afc90947ee320161bbf18eb6048"
+        );
+        assert_eq!(block_1.chainwork, work(2));
+        // getblockheader's mediantime for block 1.
+        assert_eq!(block_1.mediantime, 1231469665);
+        assert_eq!(verifier.tip(), Some(&block_1));
+        assert!(block_1.check_reported_chainwork(work(2)).is_ok());
+        assert!(matches!(
+            block_1.check_reported_chainwork(work(3)),
+            Err(HeaderChainError::ChainworkMismatch { height: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn genesis_of_another_network() {
+        // Mainnet's genesis block meets testnet's proof of work limit too.
+        let mut verifier = HeaderChainVerifier::new(Network::Test);
+        match verifier.verify_hex(GENESIS_HEADER) {
+            Err(HeaderChainError::BadGenesis { expected, actual }) => {
+                assert_eq!(expected, Network::Test.genesis_block_hash());
+                assert_eq!(actual, Network::Main.genesis_block_hash());
+            }
+            result => panic!("{:?}", result),
+        }
+        assert_eq!(verifier.tip(), None);
+    }
+
+    #[test]
+    fn made_up_genesis() {
+        let mut genesis = DecodedBlockHeader {
+            version: 1,
+            previousblockhash: Hash256::all_zeros(),
+            merkleroot: Hash256::hash(b"made up"),
+            time: 1_600_000_000,
+            bits: 0x207fffff,
+            nonce: 0,
+        };
+        // Regtest's target is met by about every other hash.
+        while !check_proof_of_work(&genesis.hash(), genesis.bits, Network::Regtest) {
+            genesis.nonce += 1;
+        }
+        let mut verifier = HeaderChainVerifier::new(Network::Regtest);
+        assert!(matches!(
+            verifier.verify(genesis),
+            Err(HeaderChainError::BadGenesis { .. })
+        ));
+        // Trusted headers starting at genesis are pinned too.
+        assert!(matches!(
+            HeaderChainVerifier::from_trusted_headers(Network::Regtest, 0, work(1), &[genesis]),
+            Err(HeaderChainError::BadGenesis { .. })
+        ));
+
+        // Mainnet's genesis block with regtest's time, bits and nonce.
+        let regtest_genesis = DecodedBlockHeader {
+            time: 1296688602,
+            bits: 0x207fffff,
+            nonce: 2,
+            ..decode_block_header(GENESIS_HEADER).unwrap()
+        };
+        let verified = verifier.verify(regtest_genesis).unwrap();
+        assert_eq!(verified.hash, Network::Regtest.genesis_block_hash());
+        assert_eq!(Checkpoint::from(verified).height, 0);
+    }
+
+    #[test]
+    fn header_not_on_the_tip() {
+        let mut verifier = HeaderChainVerifier::new(Network::Main);
+        assert!(matches!(
+            verifier.verify_hex(BLOCK_1_HEADER),
+            Err(HeaderChainError::BadGenesis { .. })
+        ));
+        let genesis = verifier.verify_hex(GENESIS_HEADER).unwrap();
+        match verifier.verify_hex(GENESIS_HEADER) {
+            Err(HeaderChainError::BadPrevBlock {
+                height,
+                expected,
+                actual,
+            }) => {
+                assert_eq!(height, 1);
+                assert_eq!(expected, genesis.hash);
+                assert!(actual.is_all_zeros());
+            }
+            result => panic!("{:?}", result),
+        }
+    }
+
+    #[test]
+    fn hash_above_the_target() {
+        let mut verifier = HeaderChainVerifier::new(Network::Main);
+        verifier.verify_hex(GENESIS_HEADER).unwrap();
+        let mut block_1 = decode_block_header(BLOCK_1_HEADER).unwrap();
+        block_1.nonce += 1;
+        assert!(matches!(
+            verifier.verify(block_1),
+            Err(HeaderChainError::HighHash { height: 1, .. })
+        ));
+        // Nothing was added.
+        assert_eq!(verifier.tip().unwrap().height, 0);
+    }
+
+    #[test]
+    fn mainnet_retarget() {
+        // The first period took longer than two weeks, so difficulty stays at the minimum, as it
+        // did for block 2016.
+        let headers = first_period((BLOCK_2015_TIME - 1231006505) as u64);
+        assert_eq!(headers[2015].time, BLOCK_2015_TIME);
+        let mut verifier =
+            HeaderChainVerifier::from_trusted_headers(Network::Main, 0, work(2016), &headers)
+                .unwrap();
+        assert_eq!(verifier.tip().unwrap().height, 2015);
+        let header = mined(&headers[2015], 2016, SLOW_PERIOD_HEADER);
+        let verified = verifier.verify(header).unwrap();
+        assert_eq!(verified.height, 2016);
+        assert_eq!(verified.chainwork, work(2017));
+    }
+
+    #[test]
+    fn retarget_with_the_wrong_bits() {
+        // A one week period halves the target.
+        let headers = first_period(604_800);
+        let mut verifier =
+            HeaderChainVerifier::from_trusted_headers(Network::Main, 0, work(2016), &headers)
+                .unwrap();
+        let header = mined(&headers[2015], 2016, FAST_PERIOD_HEADER);
+        assert!(matches!(
+            verifier.verify(header),
+            Err(HeaderChainError::BadDiffBits {
+                height: 2016,
+                expected: 0x1c7fff80,
+                actual: DIFFICULTY_1_BITS,
+            })
+        ));
+    }
+
+    #[test]
+    fn retarget_needs_the_whole_period() {
+        let headers = first_period((BLOCK_2015_TIME - 1231006505) as u64);
+        let mut verifier = HeaderChainVerifier::from_trusted_headers(
+            Network::Main,
+            2005,
+            work(2016),
+            &headers[2005..],
+        )
+        .unwrap();
+        let header = mined(&headers[2015], 2016, SLOW_PERIOD_HEADER);
+        assert!(matches!(
+            verifier.verify(header),
+            Err(HeaderChainError::InsufficientContext {
+                height: 2016,
+                required_height: 0,
+            })
+        ));
+    }
+
+    #[test]
+    fn testnet_min_difficulty() {
+        let headers = testnet_headers();
+        let chainwork = headers.iter().map(|header| Bits(header.bits).work()).sum();
+        // More than 20 minutes after the previous block.
+        let min_difficulty = mined(&headers[10], 100_011, MIN_DIFFICULTY_HEADER);
+        assert!(min_difficulty.time > headers[10].time + 20 * 60);
+        let mut verifier =
+            HeaderChainVerifier::from_trusted_headers(Network::Test, 100_000, chainwork, &headers)
+                .unwrap();
+        let verified = verifier.verify(min_difficulty).unwrap();
+        assert_eq!(verified.chainwork, chainwork + work(1));
+
+        // The next block is back to the difficulty before the min difficulty block.
+        let next = mined(&min_difficulty, 100_012, AFTER_MIN_DIFFICULTY_HEADER);
+        assert!(next.time <= min_difficulty.time + 20 * 60);
+        assert!(matches!(
+            verifier.verify(next),
+            Err(HeaderChainError::BadDiffBits {
+                height: 100_012,
+                expected: 0x1c0ffff0,
+                actual: DIFFICULTY_1_BITS,
+            })
+        ));
+
+        // Mainnet has no such exception.
+        let mut verifier =
+            HeaderChainVerifier::from_trusted_headers(Network::Main, 100_000, chainwork, &headers)
+                .unwrap();
+        assert!(matches!(
+            verifier.verify(min_difficulty),
+            Err(HeaderChainError::BadDiffBits {
+                height: 100_011,
+                expected: 0x1c0ffff0,
+                actual: DIFFICULTY_1_BITS,
+            })
+        ));
+    }
+}
@@ -0,0 +1,413 @@
+// Merkle roots and merkle proofs. MerkleBlock parses the proofs returned by gettxoutproof (core's
+// CMerkleBlock) and checks the partial merkle tree against a block's merkle root, so an SPV proof
+// can be verified without trusting the node that produced it.
+use std::error::Error;
+use std::fmt;
+
+use crate::command::get_block::Block;
+use crate::command::get_block_header::BlockHeader;
+use crate::consensus::encode::{decode_hex, DecodeError, DecodedBlockHeader, Reader};
+use crate::consensus::hash::Hash256;
+
+// Core rejects proofs claiming more transactions than could fit in a block
+// (MAX_BLOCK_WEIGHT / MIN_TRANSACTION_WEIGHT).
+const MAX_TRANSACTIONS: u32 = 4_000_000 / 240;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MerkleError {
+    Decode(DecodeError),
+    NoTransactions,
+    TooManyTransactions(u32),
+    // More hashes than transactions, or fewer flag bits than hashes.
+    TooManyHashes,
+    // The tree needs more flag bits or hashes than the proof contains.
+    Overrun,
+    // Two identical child hashes, which would allow proving a duplicated transaction
+    // (CVE-2012-2459).
+    DuplicateChildHash,
+    // The proof contains flag bits or hashes the tree didn't use.
+    UnusedData,
+    MerkleRootMismatch {
+        computed: Hash256,
+        expected: Hash256,
+    },
+    BlockHashMismatch {
+        proof: Hash256,
+        expected: Hash256,
+    },
+}
+
+impl fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MerkleError::Decode(error) => write!(f, "{}", error),
+            MerkleError::NoTransactions => write!(f, "proof has no transactions"),
+            MerkleError::TooManyTransactions(count) => {
+                write!(
+                    f,
+                    "proof claims {} transactions, more than fit in a block",
+                    count
+                )
+            }
+            MerkleError::TooManyHashes => write!(f, "proof has more hashes than it can use"),
+            MerkleError::Overrun => write!(f, "proof ran out of flag bits or hashes"),
+            MerkleError::DuplicateChildHash => {
+                write!(f, "proof has identical sibling hashes")
+            }
+            MerkleError::UnusedData => write!(f, "proof has unused flag bits or hashes"),
+            MerkleError::MerkleRootMismatch { computed, expected } => write!(
+                f,
+                "computed merkle root {} doesn't match {}",
+                computed, expected
+            ),
+            MerkleError::BlockHashMismatch { proof, expected } => {
+                write!(f, "proof is for block {} instead of {}", proof, expected)
+            }
+        }
+    }
+}
+
+impl Error for MerkleError {}
+
+impl From<DecodeError> for MerkleError {
+    fn from(error: DecodeError) -> Self {
+        MerkleError::Decode(error)
+    }
+}
+
+fn hash_pair(left: &Hash256, right: &Hash256) -> Hash256 {
+    let mut data = [0; 64];
+    data[..32].copy_from_slice(&left.0);
+    data[32..].copy_from_slice(&right.0);
+    Hash256::hash(&data)
+}
+
+// Computes a merkle root the way core does, duplicating the last hash of odd-length levels. An
+// empty list gives an all zeros hash.
+pub fn merkle_root(hashes: &[Hash256]) -> Hash256 {
+    if hashes.is_empty() {
+        return Hash256::all_zeros();
+    }
+    let mut level = hashes.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+            .collect();
+    }
+    level[0]
+}
+
+// Computes a block's merkle root from the "tx" list of getblock, at any verbosity above 0.
+pub fn block_merkle_root(block: &Block) -> Result<Hash256, DecodeError> {
+    let txids = block
+        .tx
+        .iter()
+        .map(|transaction| transaction.txid().parse())
+        .collect::<Result<Vec<Hash256>, DecodeError>>()?;
+    Ok(merkle_root(&txids))
+}
+
+// Checks the "merkleroot" of a getblock response against the root computed from its transactions.
+pub fn verify_block_merkle_root(block: &Block) -> Result<(), MerkleError> {
+    let computed = block_merkle_root(block)?;
+    let expected: Hash256 = block.merkleroot.parse()?;
+    if computed != expected {
+        return Err(MerkleError::MerkleRootMismatch { computed, expected });
+    }
+    Ok(())
+}
+
+// A transaction the proof commits to, with its position in the block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MerkleMatch {
+    pub index: u32,
+    pub txid: Hash256,
+}
+
+// A block header and a partial merkle tree (BIP37) proving some of the block's transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleBlock {
+    pub header: DecodedBlockHeader,
+    pub transactions: u32,    // Number of transactions in the block
+    pub hashes: Vec<Hash256>, // Txids and inner node hashes, in depth-first order
+    pub flags: Vec<u8>,       // One bit per visited node, least significant bit first
+}
+
+impl MerkleBlock {
+    // Decodes the hex returned by gettxoutproof.
+    pub fn from_hex(hex: &str) -> Result<Self, MerkleError> {
+        let bytes = decode_hex(hex)?;
+        let mut reader = Reader::new(&bytes);
+        let header = DecodedBlockHeader::decode(&mut reader)?;
+        let transactions = reader.read_u32()?;
+        let hash_count = reader.read_compact_size()?;
+        let mut hashes = vec![];
+        for _ in 0..hash_count {
+            hashes.push(reader.read_hash()?);
+        }
+        let flags = reader.read_var_bytes()?.to_vec();
+        reader.finish()?;
+        Ok(MerkleBlock {
+            header,
+            transactions,
+            hashes,
+            flags,
+        })
+    }
+
+    // Walks the partial merkle tree, returning its root and the transactions it commits to
+    // (core's CPartialMerkleTree::ExtractMatches). The root still has to be compared with the
+    // block's merkle root.
+    pub fn extract_matches(&self) -> Result<(Hash256, Vec<MerkleMatch>), MerkleError> {
+        if self.transactions == 0 {
+            return Err(MerkleError::NoTransactions);
+        }
+        if self.transactions > MAX_TRANSACTIONS {
+            return Err(MerkleError::TooManyTransactions(self.transactions));
+        }
+        if self.hashes.len() > self.transactions as usize
+            || self.flags.len() * 8 < self.hashes.len()
+        {
+            return Err(MerkleError::TooManyHashes);
+        }
+        let mut height = 0;
+        while self.tree_width(height) > 1 {
+            height += 1;
+        }
+        let mut traversal = Traversal {
+            merkle_block: self,
+            bits_used: 0,
+            hashes_used: 0,
+            matches: vec![],
+        };
+        let root = traversal.traverse(height, 0)?;
+        // All hashes and all bytes of flags must have been used.
+        if traversal.bits_used.div_ceil(8) != self.flags.len()
+            || traversal.hashes_used != self.hashes.len()
+        {
+            return Err(MerkleError::UnusedData);
+        }
+        Ok((root, traversal.matches))
+    }
+
+    // Verifies the proof against the merkle root in its own header, like verifytxoutproof does
+    // before checking the block is in the node's best chain.
+    pub fn verify(&self) -> Result<Vec<MerkleMatch>, MerkleError> {
+        let (root, matches) = self.extract_matches()?;
+        if root != self.header.merkleroot {
+            return Err(MerkleError::MerkleRootMismatch {
+                computed: root,
+                expected: self.header.merkleroot,
+            });
+        }
+        Ok(matches)
+    }
+
+    // Verifies the proof is for a block header obtained separately (e.g. one checked with
+    // consensus::header_chain) and that its partial merkle tree commits to that header's merkle
+    // root.
+    pub fn verify_against(&self, header: &BlockHeader) -> Result<Vec<MerkleMatch>, MerkleError> {
+        let expected_hash: Hash256 = header.hash.parse()?;
+        if self.header.hash() != expected_hash {
+            return Err(MerkleError::BlockHashMismatch {
+                proof: self.header.hash(),
+                expected: expected_hash,
+            });
+        }
+        let expected_root: Hash256 = header.merkleroot.parse()?;
+        let (root, matches) = self.extract_matches()?;
+        if root != expected_root {
+            return Err(MerkleError::MerkleRootMismatch {
+                computed: root,
+                expected: expected_root,
+            });
+        }
+        Ok(matches)
+    }
+
+    // Number of nodes at a height of the tree, where the leaves are at height 0.
+    fn tree_width(&self, height: u32) -> u64 {
+        (self.transactions as u64 + (1 << height) - 1) >> height
+    }
+
+    fn flag(&self, bit: usize) -> bool {
+        (self.flags[bit / 8] >> (bit % 8)) & 1 == 1
+    }
+}
+
+struct Traversal<'a> {
+    merkle_block: &'a MerkleBlock,
+    bits_used: usize,
+    hashes_used: usize,
+    matches: Vec<MerkleMatch>,
+}
+
+impl Traversal<'_> {
+    fn traverse(&mut self, height: u32, position: u64) -> Result<Hash256, MerkleError> {
+        let merkle_block = self.merkle_block;
+        if self.bits_used >= merkle_block.flags.len() * 8 {
+            return Err(MerkleError::Overrun);
+        }
+        let parent_of_match = merkle_block.flag(self.bits_used);
+        self.bits_used += 1;
+        if height == 0 || !parent_of_match {
+            // A leaf, or a subtree without matches: its hash is given directly.
+            let hash = *merkle_block
+                .hashes
+                .get(self.hashes_used)
+                .ok_or(MerkleError::Overrun)?;
+            self.hashes_used += 1;
+            if height == 0 && parent_of_match {
+                self.matches.push(MerkleMatch {
+                    index: position as u32,
+                    txid: hash,
+                });
+            }
+            return Ok(hash);
+        }
+        let left = self.traverse(height - 1, position * 2)?;
+        let right = if position * 2 + 1 < merkle_block.tree_width(height - 1) {
+            let right = self.traverse(height - 1, position * 2 + 1)?;
+            if right == left {
+                return Err(MerkleError::DuplicateChildHash);
+            }
+            right
+        } else {
+            left
+        };
+        Ok(hash_pair(&left, &right))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Block 170 on mainnet, whose second transaction is the first bitcoin payment between people.
+    const BLOCK_170_HASH: &str = "00000000d1145790a8694403d4063f323d499e655c83426834d4ce2f8dd4a2ee";
+    const BLOCK_170_MERKLE_ROOT: &str =
+        "7dac2c5666815c17a3b36427de37bb9d2e2c5ccec3f8633eb91a4205cb4c10ff";
+    const COINBASE_TXID: &str = "b1fea52486ce0c62bb442b530a3f0132b826c74e473d1f2c220bfa78111c5082";
+    const PAYMENT_TXID: &str = "f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16";
+    // gettxoutproof '["f4184fc5..."]': the header, 2 transactions, both txids and the flag bits
+    // 1, 0, 1 (the root, the unmatched coinbase and the matched payment).
+    const PAYMENT_PROOF: &str = "0100000055bd840a78798ad0da853f68974f3d183e2bd1db6a842c1feecf222a00000000ff104ccb05421ab93e63f8c3ce5c2c2e9dbb37de2764b3a3175c8166562cac7d51b96a49ffff001d283e9e70020000000282501c1178fa0b222c1f3d474ec726b832013f0a532b44bb620cce8624a5feb1169e1e83e930853391bc6f35f605c6754cfead57cf8387639d3b4096c54f18f40105";
+
+    fn hash(hex: &str) -> Hash256 {
+        hex.parse().unwrap()
+    }
+
+    // Only the hash and the merkle root are checked against the proof.
+    fn block_170_header() -> BlockHeader {
+        serde_json::from_value(serde_json::json!({
+            "hash": BLOCK_170_HASH,
+            "confirmations": 1,
+            "height": 170,
+            "version": 1,
+            "versionHex": "00000001",
+            "merkleroot": BLOCK_170_MERKLE_ROOT,
+            "time": 1231731025,
+            "mediantime": 1231731025,
+            "nonce": 1889418792,
+            "bits": "1d00ffff",
+            "difficulty": 1,
+            "chainwork": "000000000000000000000000000000000000000000000000000000ab00ab00ab",
+            "nTx": 2,
+            "previousblockhash": "000000002a22cfee1f2c846adbd12b3e183d4f97683f85dad08a79780a84bd55",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn merkle_roots() {
+        let coinbase = hash(COINBASE_TXID);
+        let payment = hash(PAYMENT_TXID);
+        assert_eq!(merkle_root(&[]), Hash256::all_zeros());
+        // A lone coinbase is its own root.
+        assert_eq!(merkle_root(&[coinbase]), coinbase);
+        assert_eq!(
+            merkle_root(&[coinbase, payment]),
+            hash(BLOCK_170_MERKLE_ROOT)
+        );
+        // The last hash of an odd-length level is paired with itself.
+        let third = Hash256::hash(b"third");
+        assert_eq!(
+            merkle_root(&[coinbase, payment, third]),
+            hash_pair(&hash(BLOCK_170_MERKLE_ROOT), &hash_pair(&third, &third))
+        );
+    }
+
+    #[test]
+    fn gettxoutproof() {
+        let proof = MerkleBlock::from_hex(PAYMENT_PROOF).unwrap();
+        assert_eq!(proof.header.hash(), hash(BLOCK_170_HASH));
+        assert_eq!(proof.transactions, 2);
+        assert_eq!(proof.flags, vec![0x05]);
+        let matches = vec![MerkleMatch {
+            index: 1,
+            txid: hash(PAYMENT_TXID),
+        }];
+        assert_eq!(proof.verify().unwrap(), matches);
+        assert_eq!(proof.verify_against(&block_170_header()).unwrap(), matches);
+    }
+
+    #[test]
+    fn proof_of_the_coinbase() {
+        let mut proof = MerkleBlock::from_hex(PAYMENT_PROOF).unwrap();
+        // The root, the matched coinbase and the unmatched payment.
+        proof.flags = vec![0x03];
+        assert_eq!(
+            proof.verify().unwrap(),
+            vec![MerkleMatch {
+                index: 0,
+                txid: hash(COINBASE_TXID),
+            }]
+        );
+    }
+
+    #[test]
+    fn proof_for_another_block() {
+        let proof = MerkleBlock::from_hex(PAYMENT_PROOF).unwrap();
+        let mut header = block_170_header();
+        header.hash =
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f".to_string();
+        assert!(matches!(
+            proof.verify_against(&header),
+            Err(MerkleError::BlockHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn bad_proofs() {
+        let proof = MerkleBlock::from_hex(PAYMENT_PROOF).unwrap();
+
+        let mut tampered = proof.clone();
+        tampered.hashes[1] = Hash256::hash(b"another payment");
+        assert!(matches!(
+            tampered.verify(),
+            Err(MerkleError::MerkleRootMismatch { .. })
+        ));
+
+        let mut duplicated = proof.clone();
+        duplicated.hashes[1] = duplicated.hashes[0];
+        assert_eq!(duplicated.verify(), Err(MerkleError::DuplicateChildHash));
+
+        let mut extra_flags = proof.clone();
+        extra_flags.flags.push(0);
+        assert_eq!(extra_flags.verify(), Err(MerkleError::UnusedData));
+
+        let mut missing_hash = proof.clone();
+        missing_hash.hashes.pop();
+        assert_eq!(missing_hash.verify(), Err(MerkleError::Overrun));
+
+        let mut empty = proof.clone();
+        empty.transactions = 0;
+        assert_eq!(empty.verify(), Err(MerkleError::NoTransactions));
+
+        assert!(matches!(
+            MerkleBlock::from_hex(&format!("{}00", PAYMENT_PROOF)),
+            Err(MerkleError::Decode(_))
+        ));
+    }
+}
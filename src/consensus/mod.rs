@@ -0,0 +1,25 @@
+// Local decoding and verification of consensus-serialized data returned by the node, so callers
+// don't have to trust (or pay for) the verbose RPC output.
+pub mod address;
+pub mod block_filter;
+pub mod encode;
+pub mod hash;
+pub mod header_chain;
+pub mod merkle;
+pub mod params;
+pub mod pow;
+pub mod script;
+pub mod supply_audit;
+pub mod uint;
+
+pub use block_filter::{BlockFilter, BlockFilterError};
+pub use encode::{
+    decode_block, decode_block_header, decode_transaction, DecodeError, DecodedBlock,
+    DecodedBlockHeader,
+};
+pub use hash::Hash256;
+pub use header_chain::{HeaderChainError, HeaderChainVerifier, VerifiedHeader};
+pub use merkle::{merkle_root, MerkleBlock, MerkleError, MerkleMatch};
+pub use params::Network;
+pub use supply_audit::{BlockSupplyAudit, SupplyAuditError, SupplyAuditor, SupplyIssue};
+pub use uint::U256;
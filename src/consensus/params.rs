@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::consensus::hash::Hash256;
+use crate::consensus::uint::U256;
+
+// Blocks between difficulty adjustments.
+pub const DIFFICULTY_ADJUSTMENT_INTERVAL: u64 = 2016;
+// Two weeks, the time the 2016 blocks of a retarget period are expected to take.
+pub const POW_TARGET_TIMESPAN: u64 = 14 * 24 * 60 * 60;
+// Ten minutes, the expected time between blocks.
+pub const POW_TARGET_SPACING: u64 = 10 * 60;
+// Number of previous blocks whose median time a new block's time must exceed.
+pub const MEDIAN_TIME_SPAN: usize = 11;
+// BIP94: the first block of a retarget period may not be more than this before its parent.
+pub const MAX_TIMEWARP: u64 = 600;
+// Satoshis in one bitcoin.
+pub const COIN: u64 = 100_000_000;
+// The subsidy of the first halving era.
+pub const INITIAL_SUBSIDY: u64 = 50 * COIN;
+// After this many halvings the subsidy is zero.
+const MAX_HALVINGS: u64 = 64;
+
+// The chain a node is running on, as reported by the "chain" field of getblockchaininfo.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Network {
+    Main,
+    Test,
+    Testnet4,
+    Signet,
+    Regtest,
+}
+
+impl Network {
+    pub fn p2pkh_prefix(&self) -> u8 {
+        match self {
+            Network::Main => 0x00,
+            Network::Test | Network::Testnet4 | Network::Signet | Network::Regtest => 0x6f,
+        }
+    }
+    pub fn p2sh_prefix(&self) -> u8 {
+        match self {
+            Network::Main => 0x05,
+            Network::Test | Network::Testnet4 | Network::Signet | Network::Regtest => 0xc4,
+        }
+    }
+    // Human readable part of segwit (bech32/bech32m) addresses
+    pub fn bech32_hrp(&self) -> &'static str {
+        match self {
+            Network::Main => "bc",
+            Network::Test | Network::Testnet4 | Network::Signet => "tb",
+            Network::Regtest => "bcrt",
+        }
+    }
+    // The hash of the network's genesis block. Signet is the default signet; custom signets have
+    // their own genesis block.
+    pub fn genesis_block_hash(&self) -> Hash256 {
+        let hash = match self {
+            Network::Main => "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f",
+            Network::Test => "000000000933ea01ad0ee984209779baaec3ced90fa3f408719526f8d77f4943",
+            Network::Testnet4 => "00000000da84f2bafbbc53dee25a72ae507ff4914b867c565be350b0da8bf043",
+            Network::Signet => "00000008819873e925422c1ff0f99f7cc9bbb232af63a077a480a3633bee1ef6",
+            Network::Regtest => "0f9188f13cb7b2c71f2a335e3a4fc328bf5beb436012afca590b1a11466e2206",
+        };
+        Hash256::from_rpc_hex(hash).expect("valid genesis block hash")
+    }
+    // The easiest target a block may have (core's consensus.powLimit)
+    pub fn pow_limit(&self) -> U256 {
+        match self {
+            Network::Main | Network::Test | Network::Testnet4 => {
+                U256([u64::MAX, u64::MAX, u64::MAX, 0x0000_0000_ffff_ffff])
+            }
+            Network::Signet => U256([0, 0, 0, 0x0000_0377_ae00_0000]),
+            Network::Regtest => U256([u64::MAX, u64::MAX, u64::MAX, 0x7fff_ffff_ffff_ffff]),
+        }
+    }
+    // Test networks allow a minimum difficulty block when no block was found for 20 minutes.
+    pub fn pow_allow_min_difficulty_blocks(&self) -> bool {
+        match self {
+            Network::Test | Network::Testnet4 | Network::Regtest => true,
+            Network::Main | Network::Signet => false,
+        }
+    }
+    pub fn pow_no_retargeting(&self) -> bool {
+        matches!(self, Network::Regtest)
+    }
+    // BIP94 timewarp fix and retargeting from the first block of the period, testnet4 only.
+    pub fn enforce_bip94(&self) -> bool {
+        matches!(self, Network::Testnet4)
+    }
+    // Blocks between subsidy halvings.
+    pub fn subsidy_halving_interval(&self) -> u64 {
+        match self {
+            Network::Main | Network::Test | Network::Testnet4 | Network::Signet => 210_000,
+            Network::Regtest => 150,
+        }
+    }
+    // The new coins a block at `height` may create, in satoshis (core's GetBlockSubsidy).
+    pub fn block_subsidy(&self, height: u64) -> u64 {
+        let halvings = height / self.subsidy_halving_interval();
+        if halvings >= MAX_HALVINGS {
+            return 0;
+        }
+        INITIAL_SUBSIDY >> halvings
+    }
+    // The total subsidy of blocks 0 to `height` inclusive, in satoshis. This includes the genesis
+    // block's subsidy, even though its coinbase output can't be spent.
+    pub fn cumulative_subsidy(&self, height: u64) -> u64 {
+        let interval = self.subsidy_halving_interval();
+        let mut total = 0;
+        let mut era_start = 0;
+        while era_start <= height && era_start / interval < MAX_HALVINGS {
+            let era_end = (era_start + interval - 1).min(height);
+            total += (era_end - era_start + 1) * self.block_subsidy(era_start);
+            era_start += interval;
+        }
+        total
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Network::Main => write!(f, "main"),
+            Network::Test => write!(f, "test"),
+            Network::Testnet4 => write!(f, "testnet4"),
+            Network::Signet => write!(f, "signet"),
+            Network::Regtest => write!(f, "regtest"),
+        }
+    }
+}
+
+impl FromStr for Network {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "main" => Ok(Network::Main),
+            "test" => Ok(Network::Test),
+            "testnet4" => Ok(Network::Testnet4),
+            "signet" => Ok(Network::Signet),
+            "regtest" => Ok(Network::Regtest),
+            _ => Err(format!("unknown network: {}", s)),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+// Proof of work rules, following bitcoin core's pow.cpp and arith_uint256's compact encoding.
+use crate::consensus::hash::Hash256;
+use crate::consensus::params::{Network, POW_TARGET_TIMESPAN};
+use crate::consensus::uint::U256;
+
+// The result of expanding the compact "bits" encoding of a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactTarget {
+    pub target: U256,
+    pub negative: bool,
+    pub overflow: bool,
+}
+
+// Expands "bits" into a target, like core's arith_uint256::SetCompact. The encoding is a base 256
+// floating point number: one byte of size followed by a 23 bit mantissa and a sign bit.
+pub fn target_from_compact(bits: u32) -> CompactTarget {
+    let size = bits >> 24;
+    let mut word = bits & 0x007f_ffff;
+    let target = if size <= 3 {
+        word >>= 8 * (3 - size);
+        U256::from_u64(word as u64)
+    } else {
+        U256::from_u64(word as u64) << (8 * (size - 3))
+    };
+    CompactTarget {
+        target,
+        negative: word != 0 && (bits & 0x0080_0000) != 0,
+        overflow: word != 0
+            && (size > 34 || (word > 0xff && size > 33) || (word > 0xffff && size > 32)),
+    }
+}
+
+// Compresses a target into "bits", like core's arith_uint256::GetCompact.
+pub fn target_to_compact(target: U256) -> u32 {
+    let mut size = target.bits().div_ceil(8);
+    let mut compact = if size <= 3 {
+        (target.low_u64() << (8 * (3 - size))) as u32
+    } else {
+        (target >> (8 * (size - 3))).low_u64() as u32
+    };
+    // The 0x00800000 bit is the sign, so a mantissa using it is shifted into the next size.
+    if compact & 0x0080_0000 != 0 {
+        compact >>= 8;
+        size += 1;
+    }
+    compact | (size << 24)
+}
+
+// Returns the target encoded by "bits" if it's usable on the network: positive, not overflowing
+// and no easier than the network's proof of work limit.
+pub fn derive_target(bits: u32, network: Network) -> Option<U256> {
+    let compact = target_from_compact(bits);
+    if compact.negative
+        || compact.overflow
+        || compact.target.is_zero()
+        || compact.target > network.pow_limit()
+    {
+        return None;
+    }
+    Some(compact.target)
+}
+
+// Whether a block hash satisfies the proof of work claimed by "bits" (core's CheckProofOfWork).
+pub fn check_proof_of_work(hash: &Hash256, bits: u32, network: Network) -> bool {
+    match derive_target(bits, network) {
+        Some(target) => U256::from_hash(hash) <= target,
+        None => false,
+    }
+}
+
+// The expected number of hashes needed to find a block with "bits", which is what chainwork sums
+// (core's GetBlockProof). That's 2**256 / (target+1), computed as ~target / (target+1) + 1 since
+// 2**256 doesn't fit in 256 bits.
+pub fn block_proof(bits: u32) -> U256 {
+    let compact = target_from_compact(bits);
+    if compact.negative || compact.overflow || compact.target.is_zero() {
+        return U256::ZERO;
+    }
+    (!compact.target / (compact.target + U256::ONE)) + U256::ONE
+}
+
+// The bits required for the first block of a new retarget period (core's
+// CalculateNextWorkRequired). `period_bits` is the bits of the period's last block, or of its first
+// block under BIP94.
+pub fn calculate_next_work_required(
+    period_bits: u32,
+    first_block_time: u32,
+    last_block_time: u32,
+    network: Network,
+) -> u32 {
+    if network.pow_no_retargeting() {
+        return period_bits;
+    }
+    // Limit the adjustment to a factor of 4 either way.
+    let actual_timespan = (last_block_time as i64 - first_block_time as i64).clamp(
+        POW_TARGET_TIMESPAN as i64 / 4,
+        POW_TARGET_TIMESPAN as i64 * 4,
+    ) as u64;
+    let pow_limit = network.pow_limit();
+    let target = target_from_compact(period_bits).target;
+    let new_target = target.wrapping_mul_u64(actual_timespan) / U256::from_u64(POW_TARGET_TIMESPAN);
+    if new_target > pow_limit {
+        target_to_compact(pow_limit)
+    } else {
+        target_to_compact(new_target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u256(hex: &str) -> U256 {
+        U256::from_hex(hex).unwrap()
+    }
+
+    // The SetCompact/GetCompact cases of core's arith_uint256 tests.
+    #[test]
+    fn compact_encoding() {
+        let expand = |bits| {
+            let compact = target_from_compact(bits);
+            (compact.target, compact.negative, compact.overflow)
+        };
+        assert_eq!(expand(0), (U256::ZERO, false, false));
+        assert_eq!(expand(0x0012_3456), (U256::ZERO, false, false));
+        assert_eq!(expand(0x0100_3456), (U256::ZERO, false, false));
+        assert_eq!(expand(0x0112_3456), (u256("12"), false, false));
+        assert_eq!(expand(0x0200_8000), (u256("80"), false, false));
+        assert_eq!(expand(0x0500_9234), (u256("92340000"), false, false));
+        assert_eq!(expand(0x0412_3456), (u256("12345600"), false, false));
+        // The sign bit: the magnitude is kept and flagged.
+        assert_eq!(expand(0x0492_3456), (u256("12345600"), true, false));
+        assert_eq!(expand(0x01fe_dcba), (u256("7e"), true, false));
+        assert_eq!(
+            expand(0x2012_3456),
+            (
+                u256("1234560000000000000000000000000000000000000000000000000000000000"),
+                false,
+                false
+            )
+        );
+        assert!(expand(0xff12_3456).2);
+        assert!(expand(0x2201_0000).2);
+
+        assert_eq!(target_to_compact(u256("12")), 0x0112_0000);
+        assert_eq!(target_to_compact(u256("80")), 0x0200_8000);
+        assert_eq!(target_to_compact(u256("12345600")), 0x0412_3456);
+        assert_eq!(target_to_compact(U256::ZERO), 0);
+        for bits in [0x1d00_ffff, 0x1b04_04cb, 0x1703_31db, 0x207f_ffff] {
+            assert_eq!(target_to_compact(target_from_compact(bits).target), bits);
+        }
+    }
+
+    #[test]
+    fn genesis_proof_of_work() {
+        let genesis: Hash256 = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+            .parse()
+            .unwrap();
+        assert!(check_proof_of_work(&genesis, 0x1d00_ffff, Network::Main));
+        // A harder target than the hash meets.
+        assert!(!check_proof_of_work(&genesis, 0x1b04_04cb, Network::Main));
+        // Easier than the proof of work limit.
+        assert!(!check_proof_of_work(&genesis, 0x1d01_0000, Network::Main));
+        assert_eq!(derive_target(0x0492_3456, Network::Main), None);
+        assert_eq!(derive_target(0, Network::Regtest), None);
+        // The limit itself isn't expressible in bits, only its top 23 bits.
+        assert_eq!(
+            derive_target(0x207f_ffff, Network::Regtest),
+            Some(Network::Regtest.pow_limit() >> 232 << 232)
+        );
+    }
+
+    #[test]
+    fn block_proof_of_difficulty_1() {
+        assert_eq!(block_proof(0x1d00_ffff), u256("100010001"));
+        assert_eq!(block_proof(0x207f_ffff), u256("2"));
+        assert_eq!(block_proof(0x0492_3456), U256::ZERO);
+    }
+
+    // The cases of core's pow_tests.
+    #[test]
+    fn next_work_required() {
+        assert_eq!(
+            calculate_next_work_required(0x1d00_ffff, 1261130161, 1262152739, Network::Main),
+            0x1d00_d86a
+        );
+        // Limited by the proof of work limit.
+        assert_eq!(
+            calculate_next_work_required(0x1d00_ffff, 1231006505, 1233061996, Network::Main),
+            0x1d00_ffff
+        );
+        // Limited to 4 times harder.
+        assert_eq!(
+            calculate_next_work_required(0x1c05_a3f4, 1279008237, 1279297671, Network::Main),
+            0x1c01_68fd
+        );
+        // Limited to 4 times easier.
+        assert_eq!(
+            calculate_next_work_required(0x1c38_7f6f, 1263163443, 1269211443, Network::Main),
+            0x1d00_e1fd
+        );
+        assert_eq!(
+            calculate_next_work_required(0x207f_ffff, 0, 1, Network::Regtest),
+            0x207f_ffff
+        );
+    }
+}
@@ -0,0 +1,552 @@
+// Script disassembly and output type classification, matching the "asm" and "type" fields bitcoin
+// core returns for scriptSig and scriptPubKey objects.
+use crate::consensus::encode::encode_hex;
+
+pub const OP_0: u8 = 0x00;
+pub const OP_PUSHDATA1: u8 = 0x4c;
+pub const OP_PUSHDATA2: u8 = 0x4d;
+pub const OP_PUSHDATA4: u8 = 0x4e;
+pub const OP_1NEGATE: u8 = 0x4f;
+pub const OP_1: u8 = 0x51;
+pub const OP_16: u8 = 0x60;
+pub const OP_RETURN: u8 = 0x6a;
+pub const OP_DUP: u8 = 0x76;
+pub const OP_EQUAL: u8 = 0x87;
+pub const OP_EQUALVERIFY: u8 = 0x88;
+pub const OP_HASH160: u8 = 0xa9;
+pub const OP_CHECKSIG: u8 = 0xac;
+pub const OP_CHECKMULTISIG: u8 = 0xae;
+
+const MAX_SCRIPT_SIZE: usize = 10_000;
+const MAX_SCRIPT_NUM_SIZE: usize = 4;
+
+const SIGHASH_ANYONECANPAY: u8 = 0x80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptType {
+    NonStandard,
+    PubKey,
+    PubKeyHash,
+    ScriptHash,
+    Multisig,
+    NullData,
+    WitnessV0KeyHash,
+    WitnessV0ScriptHash,
+    WitnessV1Taproot,
+    Anchor,
+    WitnessUnknown,
+}
+
+impl ScriptType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScriptType::NonStandard => "nonstandard",
+            ScriptType::PubKey => "pubkey",
+            ScriptType::PubKeyHash => "pubkeyhash",
+            ScriptType::ScriptHash => "scripthash",
+            ScriptType::Multisig => "multisig",
+            ScriptType::NullData => "nulldata",
+            ScriptType::WitnessV0KeyHash => "witness_v0_keyhash",
+            ScriptType::WitnessV0ScriptHash => "witness_v0_scripthash",
+            ScriptType::WitnessV1Taproot => "witness_v1_taproot",
+            ScriptType::Anchor => "anchor",
+            ScriptType::WitnessUnknown => "witness_unknown",
+        }
+    }
+}
+
+// A single parsed script element: either a data push or a non-push opcode.
+pub enum Instruction<'a> {
+    PushBytes(u8, &'a [u8]),
+    Op(u8),
+}
+
+// Iterates over the instructions of a script. Yields Err(()) once if the script is truncated
+// in the middle of a push, after which iteration stops.
+pub struct Instructions<'a> {
+    script: &'a [u8],
+    position: usize,
+    done: bool,
+}
+
+pub fn instructions(script: &[u8]) -> Instructions<'_> {
+    Instructions {
+        script,
+        position: 0,
+        done: false,
+    }
+}
+
+impl<'a> Iterator for Instructions<'a> {
+    type Item = Result<Instruction<'a>, ()>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.position >= self.script.len() {
+            return None;
+        }
+        let opcode = self.script[self.position];
+        self.position += 1;
+        if opcode > OP_PUSHDATA4 {
+            return Some(Ok(Instruction::Op(opcode)));
+        }
+        let remaining = &self.script[self.position..];
+        let (length_size, length) = match opcode {
+            OP_PUSHDATA1 => (1, remaining.first().map(|byte| *byte as usize)),
+            OP_PUSHDATA2 => (
+                2,
+                remaining
+                    .get(..2)
+                    .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]) as usize),
+            ),
+            OP_PUSHDATA4 => (
+                4,
+                remaining.get(..4).map(|bytes| {
+                    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize
+                }),
+            ),
+            _ => (0, Some(opcode as usize)),
+        };
+        let data = length.and_then(|length| remaining.get(length_size..length_size + length));
+        match data {
+            Some(data) => {
+                self.position += length_size + data.len();
+                Some(Ok(Instruction::PushBytes(opcode, data)))
+            }
+            None => {
+                self.done = true;
+                Some(Err(()))
+            }
+        }
+    }
+}
+
+pub fn opcode_name(opcode: u8) -> &'static str {
+    match opcode {
+        0x00 => "0",
+        0x4c => "OP_PUSHDATA1",
+        0x4d => "OP_PUSHDATA2",
+        0x4e => "OP_PUSHDATA4",
+        0x4f => "-1",
+        0x50 => "OP_RESERVED",
+        0x51 => "1",
+        0x52 => "2",
+        0x53 => "3",
+        0x54 => "4",
+        0x55 => "5",
+        0x56 => "6",
+        0x57 => "7",
+        0x58 => "8",
+        0x59 => "9",
+        0x5a => "10",
+        0x5b => "11",
+        0x5c => "12",
+        0x5d => "13",
+        0x5e => "14",
+        0x5f => "15",
+        0x60 => "16",
+        0x61 => "OP_NOP",
+        0x62 => "OP_VER",
+        0x63 => "OP_IF",
+        0x64 => "OP_NOTIF",
+        0x65 => "OP_VERIF",
+        0x66 => "OP_VERNOTIF",
+        0x67 => "OP_ELSE",
+        0x68 => "OP_ENDIF",
+        0x69 => "OP_VERIFY",
+        0x6a => "OP_RETURN",
+        0x6b => "OP_TOALTSTACK",
+        0x6c => "OP_FROMALTSTACK",
+        0x6d => "OP_2DROP",
+        0x6e => "OP_2DUP",
+        0x6f => "OP_3DUP",
+        0x70 => "OP_2OVER",
+        0x71 => "OP_2ROT",
+        0x72 => "OP_2SWAP",
+        0x73 => "OP_IFDUP",
+        0x74 => "OP_DEPTH",
+        0x75 => "OP_DROP",
+        0x76 => "OP_DUP",
+        0x77 => "OP_NIP",
+        0x78 => "OP_OVER",
+        0x79 => "OP_PICK",
+        0x7a => "OP_ROLL",
+        0x7b => "OP_ROT",
+        0x7c => "OP_SWAP",
+        0x7d => "OP_TUCK",
+        0x7e => "OP_CAT",
+        0x7f => "OP_SUBSTR",
+        0x80 => "OP_LEFT",
+        0x81 => "OP_RIGHT",
+        0x82 => "OP_SIZE",
+        0x83 => "OP_INVERT",
+        0x84 => "OP_AND",
+        0x85 => "OP_OR",
+        0x86 => "OP_XOR",
+        0x87 => "OP_EQUAL",
+        0x88 => "OP_EQUALVERIFY",
+        0x89 => "OP_RESERVED1",
+        0x8a => "OP_RESERVED2",
+        0x8b => "OP_1ADD",
+        0x8c => "OP_1SUB",
+        0x8d => "OP_2MUL",
+        0x8e => "OP_2DIV",
+        0x8f => "OP_NEGATE",
+        0x90 => "OP_ABS",
+        0x91 => "OP_NOT",
+        0x92 => "OP_0NOTEQUAL",
+        0x93 => "OP_ADD",
+        0x94 => "OP_SUB",
+        0x95 => "OP_MUL",
+        0x96 => "OP_DIV",
+        0x97 => "OP_MOD",
+        0x98 => "OP_LSHIFT",
+        0x99 => "OP_RSHIFT",
+        0x9a => "OP_BOOLAND",
+        0x9b => "OP_BOOLOR",
+        0x9c => "OP_NUMEQUAL",
+        0x9d => "OP_NUMEQUALVERIFY",
+        0x9e => "OP_NUMNOTEQUAL",
+        0x9f => "OP_LESSTHAN",
+        0xa0 => "OP_GREATERTHAN",
+        0xa1 => "OP_LESSTHANOREQUAL",
+        0xa2 => "OP_GREATERTHANOREQUAL",
+        0xa3 => "OP_MIN",
+        0xa4 => "OP_MAX",
+        0xa5 => "OP_WITHIN",
+        0xa6 => "OP_RIPEMD160",
+        0xa7 => "OP_SHA1",
+        0xa8 => "OP_SHA256",
+        0xa9 => "OP_HASH160",
+        0xaa => "OP_HASH256",
+        0xab => "OP_CODESEPARATOR",
+        0xac => "OP_CHECKSIG",
+        0xad => "OP_CHECKSIGVERIFY",
+        0xae => "OP_CHECKMULTISIG",
+        0xaf => "OP_CHECKMULTISIGVERIFY",
+        0xb0 => "OP_NOP1",
+        0xb1 => "OP_CHECKLOCKTIMEVERIFY",
+        0xb2 => "OP_CHECKSEQUENCEVERIFY",
+        0xb3 => "OP_NOP4",
+        0xb4 => "OP_NOP5",
+        0xb5 => "OP_NOP6",
+        0xb6 => "OP_NOP7",
+        0xb7 => "OP_NOP8",
+        0xb8 => "OP_NOP9",
+        0xb9 => "OP_NOP10",
+        0xba => "OP_CHECKSIGADD",
+        0xff => "OP_INVALIDOPCODE",
+        _ => "OP_UNKNOWN",
+    }
+}
+
+pub fn is_unspendable(script: &[u8]) -> bool {
+    script.first() == Some(&OP_RETURN) || script.len() > MAX_SCRIPT_SIZE
+}
+
+// Returns the witness version and program if the script is a segwit output (BIP141).
+pub fn witness_program(script: &[u8]) -> Option<(u8, &[u8])> {
+    if script.len() < 4 || script.len() > 42 {
+        return None;
+    }
+    let version = match script[0] {
+        OP_0 => 0,
+        opcode @ OP_1..=OP_16 => opcode - OP_1 + 1,
+        _ => return None,
+    };
+    if script[1] as usize + 2 != script.len() {
+        return None;
+    }
+    Some((version, &script[2..]))
+}
+
+fn is_push_only(script: &[u8]) -> bool {
+    instructions(script).all(|instruction| match instruction {
+        Ok(Instruction::PushBytes(..)) => true,
+        Ok(Instruction::Op(opcode)) => opcode <= OP_16,
+        Err(()) => false,
+    })
+}
+
+fn is_valid_pubkey_size(pubkey: &[u8]) -> bool {
+    match pubkey.first() {
+        Some(0x02) | Some(0x03) => pubkey.len() == 33,
+        Some(0x04) | Some(0x06) | Some(0x07) => pubkey.len() == 65,
+        _ => false,
+    }
+}
+
+fn small_int(opcode: u8) -> Option<usize> {
+    match opcode {
+        OP_0 => Some(0),
+        OP_1..=OP_16 => Some((opcode - OP_1 + 1) as usize),
+        _ => None,
+    }
+}
+
+fn is_multisig(script: &[u8]) -> bool {
+    if script.last() != Some(&OP_CHECKMULTISIG) {
+        return false;
+    }
+    let parsed: Result<Vec<Instruction>, ()> = instructions(script).collect();
+    let parsed = match parsed {
+        Ok(parsed) if parsed.len() >= 4 => parsed,
+        _ => return false,
+    };
+    let required = match parsed[0] {
+        Instruction::Op(opcode) => small_int(opcode),
+        Instruction::PushBytes(..) => None,
+    };
+    let total = match parsed[parsed.len() - 2] {
+        Instruction::Op(opcode) => small_int(opcode),
+        Instruction::PushBytes(..) => None,
+    };
+    let pubkeys = &parsed[1..parsed.len() - 2];
+    let all_pubkeys = pubkeys.iter().all(|instruction| match instruction {
+        Instruction::PushBytes(_, data) => is_valid_pubkey_size(data),
+        Instruction::Op(_) => false,
+    });
+    match (required, total) {
+        (Some(required), Some(total)) => {
+            all_pubkeys && required >= 1 && required <= total && total == pubkeys.len()
+        }
+        _ => false,
+    }
+}
+
+pub fn classify(script: &[u8]) -> ScriptType {
+    if script.len() == 23 && script[0] == OP_HASH160 && script[1] == 20 && script[22] == OP_EQUAL {
+        return ScriptType::ScriptHash;
+    }
+    if let Some((version, program)) = witness_program(script) {
+        return match (version, program.len()) {
+            (0, 20) => ScriptType::WitnessV0KeyHash,
+            (0, 32) => ScriptType::WitnessV0ScriptHash,
+            (0, _) => ScriptType::NonStandard,
+            (1, 32) => ScriptType::WitnessV1Taproot,
+            (1, 2) if program == [0x4e, 0x73] => ScriptType::Anchor,
+            _ => ScriptType::WitnessUnknown,
+        };
+    }
+    if script.first() == Some(&OP_RETURN) && is_push_only(&script[1..]) {
+        return ScriptType::NullData;
+    }
+    if (script.len() == 35 || script.len() == 67)
+        && script[0] as usize == script.len() - 2
+        && script.last() == Some(&OP_CHECKSIG)
+        && is_valid_pubkey_size(&script[1..script.len() - 1])
+    {
+        return ScriptType::PubKey;
+    }
+    if script.len() == 25
+        && script[0] == OP_DUP
+        && script[1] == OP_HASH160
+        && script[2] == 20
+        && script[23] == OP_EQUALVERIFY
+        && script[24] == OP_CHECKSIG
+    {
+        return ScriptType::PubKeyHash;
+    }
+    if is_multisig(script) {
+        return ScriptType::Multisig;
+    }
+    ScriptType::NonStandard
+}
+
+// Decodes a minimally sized script number of up to 4 bytes (little-endian, sign-magnitude).
+fn script_num(data: &[u8]) -> i64 {
+    if data.is_empty() {
+        return 0;
+    }
+    let mut result: i64 = 0;
+    for (index, byte) in data.iter().enumerate() {
+        result |= (*byte as i64) << (8 * index);
+    }
+    let sign_bit = 0x80_i64 << (8 * (data.len() - 1));
+    if data[data.len() - 1] & 0x80 != 0 {
+        -(result & !sign_bit)
+    } else {
+        result
+    }
+}
+
+// Strict DER signature check from BIP66, including the trailing sighash byte.
+fn is_valid_signature_encoding(sig: &[u8]) -> bool {
+    if sig.len() < 9 || sig.len() > 73 {
+        return false;
+    }
+    if sig[0] != 0x30 || sig[1] as usize != sig.len() - 3 {
+        return false;
+    }
+    let len_r = sig[3] as usize;
+    if 5 + len_r >= sig.len() {
+        return false;
+    }
+    let len_s = sig[5 + len_r] as usize;
+    if len_r + len_s + 7 != sig.len() {
+        return false;
+    }
+    if sig[2] != 0x02 || len_r == 0 || sig[4] & 0x80 != 0 {
+        return false;
+    }
+    if len_r > 1 && sig[4] == 0x00 && sig[5] & 0x80 == 0 {
+        return false;
+    }
+    if sig[len_r + 4] != 0x02 || len_s == 0 || sig[len_r + 6] & 0x80 != 0 {
+        return false;
+    }
+    if len_s > 1 && sig[len_r + 6] == 0x00 && sig[len_r + 7] & 0x80 == 0 {
+        return false;
+    }
+    true
+}
+
+fn sighash_type_name(sighash_type: u8) -> Option<&'static str> {
+    match sighash_type {
+        0x01 => Some("ALL"),
+        0x81 => Some("ALL|ANYONECANPAY"),
+        0x02 => Some("NONE"),
+        0x82 => Some("NONE|ANYONECANPAY"),
+        0x03 => Some("SINGLE"),
+        0x83 => Some("SINGLE|ANYONECANPAY"),
+        _ => None,
+    }
+}
+
+fn is_defined_hashtype_signature(sig: &[u8]) -> bool {
+    match sig.last() {
+        Some(sighash_type) => {
+            let base_type = sighash_type & !SIGHASH_ANYONECANPAY;
+            (0x01..=0x03).contains(&base_type)
+        }
+        None => false,
+    }
+}
+
+// Disassembles a script the same way bitcoin core's ScriptToAsmStr does. Signatures in a
+// scriptSig are printed with their sighash type decoded (e.g. "...[ALL]") when
+// attempt_sighash_decode is set.
+pub fn to_asm(script: &[u8], attempt_sighash_decode: bool) -> String {
+    let mut parts: Vec<String> = vec![];
+    for instruction in instructions(script) {
+        match instruction {
+            Err(()) => {
+                parts.push("[error]".to_string());
+                break;
+            }
+            Ok(Instruction::Op(opcode)) => parts.push(opcode_name(opcode).to_string()),
+            Ok(Instruction::PushBytes(_, data)) => {
+                if data.len() <= MAX_SCRIPT_NUM_SIZE {
+                    parts.push(script_num(data).to_string());
+                } else if attempt_sighash_decode
+                    && !is_unspendable(script)
+                    && is_valid_signature_encoding(data)
+                    && is_defined_hashtype_signature(data)
+                {
+                    let (sig, sighash_type) = data.split_at(data.len() - 1);
+                    match sighash_type_name(sighash_type[0]) {
+                        Some(name) => parts.push(format!("{}[{}]", encode_hex(sig), name)),
+                        None => parts.push(encode_hex(data)),
+                    }
+                } else {
+                    parts.push(encode_hex(data));
+                }
+            }
+        }
+    }
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::encode::decode_hex;
+
+    fn script(hex: &str) -> Vec<u8> {
+        decode_hex(hex).unwrap()
+    }
+
+    #[test]
+    fn classifies_standard_outputs() {
+        let cases = [
+            ("76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac", ScriptType::PubKeyHash),
+            ("a914748284390f9e263a4b766a75d0633c50426eb87587", ScriptType::ScriptHash),
+            ("0014751e76e8199196d454941c45d1b3a323f1433bd6", ScriptType::WitnessV0KeyHash),
+            (
+                "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262",
+                ScriptType::WitnessV0ScriptHash,
+            ),
+            (
+                "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+                ScriptType::WitnessV1Taproot,
+            ),
+            ("51024e73", ScriptType::Anchor),
+            ("6002751e", ScriptType::WitnessUnknown),
+            ("0010751e76e8199196d454941c45d1b3a323", ScriptType::NonStandard),
+            ("6a0b68656c6c6f20776f726c64", ScriptType::NullData),
+            ("6a", ScriptType::NullData),
+            ("6a76", ScriptType::NonStandard),
+            (
+                "4104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac",
+                ScriptType::PubKey,
+            ),
+            (
+                "512102b4632d08485ff1df2db55b9dafd23347d1c47a457072a1e87be26896549a87372103f1c0a0e4ac2d5c7ab0cc6e2c1d6b0b4b3c2e0cfd3e0a0b0c0d0e0f101112131452ae",
+                ScriptType::Multisig,
+            ),
+            ("", ScriptType::NonStandard),
+        ];
+        for (hex, script_type) in cases {
+            assert_eq!(classify(&script(hex)), script_type, "{}", hex);
+        }
+    }
+
+    #[test]
+    fn witness_programs() {
+        let p2wpkh = script("0014751e76e8199196d454941c45d1b3a323f1433bd6");
+        assert_eq!(witness_program(&p2wpkh), Some((0, &p2wpkh[2..])));
+        assert_eq!(witness_program(&script("6002751e")).unwrap().0, 16);
+        // The push must cover the rest of the script.
+        assert_eq!(
+            witness_program(&script("0015751e76e8199196d454941c45d1b3a323f1433bd6")),
+            None
+        );
+        assert_eq!(witness_program(&script("0001ff")), None);
+    }
+
+    #[test]
+    fn disassembles_like_core() {
+        assert_eq!(
+            to_asm(
+                &script("76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac"),
+                false
+            ),
+            "OP_DUP OP_HASH160 62e907b15cbf27d5425399ebf6f0fb50ebb88f18 OP_EQUALVERIFY OP_CHECKSIG"
+        );
+        // Pushes of up to 4 bytes are shown as numbers.
+        assert_eq!(to_asm(&script("0004ffff001d0104"), false), "0 486604799 4");
+        assert_eq!(to_asm(&script("0181028000"), false), "-1 128");
+        // A push running past the end of the script.
+        assert_eq!(to_asm(&script("51054142"), false), "1 [error]");
+    }
+
+    #[test]
+    fn decodes_signature_hash_types_in_script_sigs() {
+        let sig = "30450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed";
+        let script_sig = script(&format!("48{}01", sig));
+        assert_eq!(to_asm(&script_sig, true), format!("{}[ALL]", sig));
+        assert_eq!(to_asm(&script_sig, false), format!("{}01", sig));
+        let anyonecanpay = script(&format!("48{}83", sig));
+        assert_eq!(
+            to_asm(&anyonecanpay, true),
+            format!("{}[SINGLE|ANYONECANPAY]", sig)
+        );
+    }
+
+    #[test]
+    fn unspendable_scripts() {
+        assert!(is_unspendable(&script("6a0b68656c6c6f20776f726c64")));
+        assert!(is_unspendable(&vec![OP_1; MAX_SCRIPT_SIZE + 1]));
+        assert!(!is_unspendable(&script(
+            "0014751e76e8199196d454941c45d1b3a323f1433bd6"
+        )));
+    }
+}
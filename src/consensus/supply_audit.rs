@@ -0,0 +1,285 @@
+// Audit of the coin supply, block by block: no block created more coins than the halving schedule
+// allows, and the coins in the UTXO set plus the coins provably lost add up to exactly the
+// subsidies issued so far. Together these show the node's chain doesn't inflate the supply.
+//
+// The per-block amounts come from the coinstatsindex (bitcoind -coinstatsindex), which has to be
+// synced up to the heights audited. The subsidy and fees come from getblockstats, so the blocks
+// can't be pruned.
+//
+//     let auditor = SupplyAuditor::new(&client, Network::Main).set_start_height(839_990);
+//     for audit in auditor {
+//         let audit = audit?;
+//         println!("{}", audit);
+//     }
+use std::error::Error;
+use std::fmt;
+
+use crate::client::Client;
+use crate::command::get_block_count::GetBlockCountCommand;
+use crate::command::get_block_stats::{
+    GetBlockStatsCommand, StatNotSelected, StatsArgumentChoices, TargetBlockArgument,
+};
+use crate::command::get_tx_out_set_info::{GetTxOutSetInfoCommand, HashType};
+use crate::command::CallableCommand;
+use crate::consensus::params::{Network, COIN};
+use crate::Blockhash;
+
+#[derive(Debug)]
+pub enum SupplyAuditError {
+    // gettxoutsetinfo didn't use the coinstatsindex, so it has no per-block amounts.
+    IndexUnavailable {
+        height: u64,
+    },
+    // getblockstats didn't return a stat the audit needs.
+    MissingStat {
+        height: u64,
+        stat: StatsArgumentChoices,
+    },
+    Rpc(jsonrpc::Error),
+}
+
+impl fmt::Display for SupplyAuditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SupplyAuditError::IndexUnavailable { height } => write!(
+                f,
+                "no coinstatsindex data for block {} (is bitcoind running with -coinstatsindex?)",
+                height
+            ),
+            SupplyAuditError::MissingStat { height, stat } => {
+                write!(
+                    f,
+                    "getblockstats didn't return {} for block {}",
+                    stat, height
+                )
+            }
+            SupplyAuditError::Rpc(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for SupplyAuditError {}
+
+impl From<jsonrpc::Error> for SupplyAuditError {
+    fn from(error: jsonrpc::Error) -> Self {
+        SupplyAuditError::Rpc(error)
+    }
+}
+
+// Something wrong with a block's supply. All amounts are in satoshis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SupplyIssue {
+    // The subsidy the node reports isn't the one the halving schedule gives for the height.
+    SubsidyMismatch { expected: u64, reported: u64 },
+    // The UTXO set plus the unspendable amount doesn't add up to the subsidies issued so far.
+    SupplyMismatch { expected: u64, actual: u64 },
+    // The coinbase pays out more than the subsidy plus the block's fees.
+    CoinbaseOverclaim { claimed: u64, allowed: u64 },
+}
+
+impl fmt::Display for SupplyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SupplyIssue::SubsidyMismatch { expected, reported } => write!(
+                f,
+                "subsidy is {} but the schedule gives {}",
+                reported, expected
+            ),
+            SupplyIssue::SupplyMismatch { expected, actual } => {
+                write!(f, "supply is {} but {} has been issued", actual, expected)
+            }
+            SupplyIssue::CoinbaseOverclaim { claimed, allowed } => write!(
+                f,
+                "coinbase claims {} but only {} is allowed",
+                claimed, allowed
+            ),
+        }
+    }
+}
+
+// The audit of one block. All amounts are in satoshis.
+#[derive(Debug, Clone)]
+pub struct BlockSupplyAudit {
+    pub height: u64,
+    pub blockhash: Blockhash,
+    pub expected_subsidy: u64, // the subsidy the halving schedule gives for the height
+    pub reported_subsidy: u64, // the subsidy getblockstats reports
+    pub fees: u64,             // the fees of the block's transactions
+    pub coinbase: u64, // the coinbase's spendable outputs (outputs to unspendable scripts can never add to the supply)
+    pub expected_supply: u64, // the subsidies of all blocks up to and including this one
+    pub total_amount: u64, // the amount in the UTXO set after this block
+    pub total_unspendable_amount: u64, // the amount permanently excluded from the UTXO set, e.g. the genesis subsidy, unclaimed rewards and OP_RETURN outputs
+    pub issues: Vec<SupplyIssue>,
+}
+
+impl BlockSupplyAudit {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for BlockSupplyAudit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} subsidy={} fees={} coinbase={} supply={}+{}/{}",
+            self.height,
+            self.blockhash.0,
+            self.reported_subsidy,
+            self.fees,
+            self.coinbase,
+            self.total_amount,
+            self.total_unspendable_amount,
+            self.expected_supply
+        )?;
+        if self.is_ok() {
+            return write!(f, " ok");
+        }
+        for issue in &self.issues {
+            write!(f, " [{}]", issue)?;
+        }
+        Ok(())
+    }
+}
+
+// Amounts in BTC as returned by the node. They are whole numbers of satoshis, so rounding
+// only undoes the float conversion.
+fn to_satoshis(amount: f64) -> u64 {
+    (amount * COIN as f64).round() as u64
+}
+
+// Audits one block.
+pub fn audit_block(
+    client: &Client,
+    network: Network,
+    height: u64,
+) -> Result<BlockSupplyAudit, SupplyAuditError> {
+    let coin_stats = GetTxOutSetInfoCommand::new()
+        .set_hash_type(HashType::None)
+        .set_hash_or_height(TargetBlockArgument::Height(height))
+        .set_use_index(true)
+        .call(client)?;
+    let (total_unspendable_amount, block_info) =
+        match (coin_stats.total_unspendable_amount, coin_stats.block_info) {
+            (Some(total_unspendable_amount), Some(block_info)) => {
+                (total_unspendable_amount, block_info)
+            }
+            _ => return Err(SupplyAuditError::IndexUnavailable { height }),
+        };
+
+    let expected_subsidy = network.block_subsidy(height);
+    // The genesis block has no undo data, which getblockstats needs on older versions, and its
+    // coinbase can't be spent anyway.
+    let (reported_subsidy, fees) = if height == 0 {
+        (expected_subsidy, 0)
+    } else {
+        block_subsidy_and_fees(client, height)?
+    };
+    let coinbase = to_satoshis(block_info.coinbase);
+    let expected_supply = network.cumulative_subsidy(height);
+    let total_amount = to_satoshis(coin_stats.total_amount);
+    let total_unspendable_amount = to_satoshis(total_unspendable_amount);
+
+    let mut issues = vec![];
+    if reported_subsidy != expected_subsidy {
+        issues.push(SupplyIssue::SubsidyMismatch {
+            expected: expected_subsidy,
+            reported: reported_subsidy,
+        });
+    }
+    let actual_supply = total_amount + total_unspendable_amount;
+    if actual_supply != expected_supply {
+        issues.push(SupplyIssue::SupplyMismatch {
+            expected: expected_supply,
+            actual: actual_supply,
+        });
+    }
+    let allowed = expected_subsidy + fees;
+    if coinbase > allowed {
+        issues.push(SupplyIssue::CoinbaseOverclaim {
+            claimed: coinbase,
+            allowed,
+        });
+    }
+    Ok(BlockSupplyAudit {
+        height,
+        blockhash: coin_stats.bestblock,
+        expected_subsidy,
+        reported_subsidy,
+        fees,
+        coinbase,
+        expected_supply,
+        total_amount,
+        total_unspendable_amount,
+        issues,
+    })
+}
+
+fn block_subsidy_and_fees(client: &Client, height: u64) -> Result<(u64, u64), SupplyAuditError> {
+    let stats = GetBlockStatsCommand::new(TargetBlockArgument::Height(height))
+        .select(vec![
+            StatsArgumentChoices::Subsidy,
+            StatsArgumentChoices::TotalFee,
+        ])
+        .call(client)?;
+    let missing_stat = |error: StatNotSelected| SupplyAuditError::MissingStat {
+        height,
+        stat: error.0,
+    };
+    Ok((
+        stats.subsidy().map_err(missing_stat)?,
+        stats.totalfee().map_err(missing_stat)?,
+    ))
+}
+
+// Audits blocks from the start height (genesis by default) to the end height (the tip when the
+// first block is audited, by default), yielding a report per block.
+//
+// An error is yielded as an item, and iterating again retries the same block.
+pub struct SupplyAuditor<'a> {
+    client: &'a Client,
+    network: Network,
+    next_height: u64,
+    end_height: Option<u64>,
+}
+
+impl<'a> SupplyAuditor<'a> {
+    pub fn new(client: &'a Client, network: Network) -> Self {
+        SupplyAuditor {
+            client,
+            network,
+            next_height: 0,
+            end_height: None,
+        }
+    }
+    pub fn set_start_height(mut self, start_height: u64) -> Self {
+        self.next_height = start_height;
+        self
+    }
+    pub fn set_end_height(mut self, end_height: u64) -> Self {
+        self.end_height = Some(end_height);
+        self
+    }
+}
+
+impl Iterator for SupplyAuditor<'_> {
+    type Item = Result<BlockSupplyAudit, SupplyAuditError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let end_height = match self.end_height {
+            Some(end_height) => end_height,
+            None => match GetBlockCountCommand::new().call(self.client) {
+                Ok(block_count) => *self.end_height.insert(block_count.0),
+                Err(error) => return Some(Err(error.into())),
+            },
+        };
+        if self.next_height > end_height {
+            return None;
+        }
+        let audit = audit_block(self.client, self.network, self.next_height);
+        if audit.is_ok() {
+            self.next_height += 1;
+        }
+        Some(audit)
+    }
+}
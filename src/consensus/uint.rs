@@ -0,0 +1,334 @@
+// An unsigned 256 bit integer for proof of work arithmetic (targets and chainwork), modelled on
+// bitcoin core's arith_uint256. Arithmetic wraps on overflow, like core's.
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Not, Shl, Shr, Sub};
+use std::str::FromStr;
+
+use crate::consensus::encode::{decode_hex, DecodeError};
+use crate::consensus::hash::Hash256;
+
+// Limbs are stored least significant first.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct U256(pub [u64; 4]);
+
+impl U256 {
+    pub const ZERO: U256 = U256([0; 4]);
+    pub const ONE: U256 = U256([1, 0, 0, 0]);
+    pub const MAX: U256 = U256([u64::MAX; 4]);
+
+    pub fn from_u64(value: u64) -> Self {
+        U256([value, 0, 0, 0])
+    }
+    pub fn from_be_bytes(bytes: [u8; 32]) -> Self {
+        let mut limbs = [0; 4];
+        for (index, limb) in limbs.iter_mut().enumerate() {
+            let start = 32 - (index + 1) * 8;
+            *limb = u64::from_be_bytes(bytes[start..start + 8].try_into().unwrap());
+        }
+        U256(limbs)
+    }
+    pub fn from_le_bytes(bytes: [u8; 32]) -> Self {
+        let mut limbs = [0; 4];
+        for (index, limb) in limbs.iter_mut().enumerate() {
+            let start = index * 8;
+            *limb = u64::from_le_bytes(bytes[start..start + 8].try_into().unwrap());
+        }
+        U256(limbs)
+    }
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        for (index, limb) in self.0.iter().enumerate() {
+            let start = 32 - (index + 1) * 8;
+            bytes[start..start + 8].copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+    // Interprets a block hash as a number, the way core compares it against the target.
+    pub fn from_hash(hash: &Hash256) -> Self {
+        U256::from_le_bytes(hash.0)
+    }
+    // Parses big-endian hex, such as the "chainwork" field of getblockheader. Leading zeros may be
+    // omitted.
+    pub fn from_hex(hex: &str) -> Result<Self, DecodeError> {
+        let hex = hex.strip_prefix("0x").unwrap_or(hex);
+        if hex.len() > 64 {
+            return Err(DecodeError::InvalidLength {
+                expected: 32,
+                actual: hex.len().div_ceil(2),
+            });
+        }
+        let padded = format!("{:0>64}", hex);
+        let bytes = decode_hex(&padded)?;
+        Ok(U256::from_be_bytes(bytes.try_into().unwrap()))
+    }
+    // Formatted as 64 hex digits, like the "chainwork" field of getblockheader.
+    pub fn to_hex(&self) -> String {
+        format!(
+            "{:016x}{:016x}{:016x}{:016x}",
+            self.0[3], self.0[2], self.0[1], self.0[0]
+        )
+    }
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|limb| *limb == 0)
+    }
+    pub fn low_u64(&self) -> u64 {
+        self.0[0]
+    }
+    // The position of the highest set bit plus one, or 0 for zero.
+    pub fn bits(&self) -> u32 {
+        for index in (0..4).rev() {
+            if self.0[index] != 0 {
+                return 64 * index as u32 + (64 - self.0[index].leading_zeros());
+            }
+        }
+        0
+    }
+    pub fn overflowing_add(self, other: U256) -> (U256, bool) {
+        let mut result = [0; 4];
+        let mut carry = false;
+        for (index, limb) in result.iter_mut().enumerate() {
+            let (sum, overflow_a) = self.0[index].overflowing_add(other.0[index]);
+            let (sum, overflow_b) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = overflow_a || overflow_b;
+        }
+        (U256(result), carry)
+    }
+    pub fn overflowing_sub(self, other: U256) -> (U256, bool) {
+        let mut result = [0; 4];
+        let mut borrow = false;
+        for (index, limb) in result.iter_mut().enumerate() {
+            let (difference, overflow_a) = self.0[index].overflowing_sub(other.0[index]);
+            let (difference, overflow_b) = difference.overflowing_sub(borrow as u64);
+            *limb = difference;
+            borrow = overflow_a || overflow_b;
+        }
+        (U256(result), borrow)
+    }
+    pub fn checked_add(self, other: U256) -> Option<U256> {
+        match self.overflowing_add(other) {
+            (sum, false) => Some(sum),
+            (_, true) => None,
+        }
+    }
+    pub fn wrapping_mul_u64(self, other: u64) -> U256 {
+        let mut result = [0; 4];
+        let mut carry: u128 = 0;
+        for (index, limb) in result.iter_mut().enumerate() {
+            let product = self.0[index] as u128 * other as u128 + carry;
+            *limb = product as u64;
+            carry = product >> 64;
+        }
+        U256(result)
+    }
+    // Shift-and-subtract long division, returning (quotient, remainder). Panics on a zero divisor.
+    pub fn div_rem(self, divisor: U256) -> (U256, U256) {
+        assert!(!divisor.is_zero(), "U256 division by zero");
+        if self < divisor {
+            return (U256::ZERO, self);
+        }
+        let shift = self.bits() - divisor.bits();
+        let mut divisor = divisor << shift;
+        let mut remainder = self;
+        let mut quotient = U256::ZERO;
+        for bit in (0..=shift).rev() {
+            if remainder >= divisor {
+                remainder = remainder - divisor;
+                quotient.0[(bit / 64) as usize] |= 1 << (bit % 64);
+            }
+            divisor = divisor >> 1;
+        }
+        (quotient, remainder)
+    }
+    // An approximation, for difficulty and hashrate figures.
+    pub fn to_f64(&self) -> f64 {
+        self.0.iter().rev().fold(0.0, |total, limb| {
+            total * 18_446_744_073_709_551_616.0 + *limb as f64
+        })
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for U256 {
+    type Output = U256;
+    fn add(self, other: U256) -> U256 {
+        self.overflowing_add(other).0
+    }
+}
+
+impl Sub for U256 {
+    type Output = U256;
+    fn sub(self, other: U256) -> U256 {
+        self.overflowing_sub(other).0
+    }
+}
+
+impl Div for U256 {
+    type Output = U256;
+    fn div(self, other: U256) -> U256 {
+        self.div_rem(other).0
+    }
+}
+
+impl Not for U256 {
+    type Output = U256;
+    fn not(self) -> U256 {
+        U256([!self.0[0], !self.0[1], !self.0[2], !self.0[3]])
+    }
+}
+
+impl Shl<u32> for U256 {
+    type Output = U256;
+    fn shl(self, shift: u32) -> U256 {
+        let mut result = [0; 4];
+        let limb_shift = (shift / 64) as usize;
+        let bit_shift = shift % 64;
+        for (index, limb) in result.iter_mut().enumerate().skip(limb_shift) {
+            *limb = self.0[index - limb_shift] << bit_shift;
+            if bit_shift > 0 && index > limb_shift {
+                *limb |= self.0[index - limb_shift - 1] >> (64 - bit_shift);
+            }
+        }
+        U256(result)
+    }
+}
+
+impl Shr<u32> for U256 {
+    type Output = U256;
+    fn shr(self, shift: u32) -> U256 {
+        let mut result = [0; 4];
+        let limb_shift = (shift / 64) as usize;
+        let bit_shift = shift % 64;
+        for (index, limb) in result
+            .iter_mut()
+            .take(4usize.saturating_sub(limb_shift))
+            .enumerate()
+        {
+            *limb = self.0[index + limb_shift] >> bit_shift;
+            if bit_shift > 0 && index + limb_shift + 1 < 4 {
+                *limb |= self.0[index + limb_shift + 1] << (64 - bit_shift);
+            }
+        }
+        U256(result)
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+impl fmt::Debug for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "U256({})", self.to_hex())
+    }
+}
+
+impl FromStr for U256 {
+    type Err = DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        U256::from_hex(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u256(hex: &str) -> U256 {
+        U256::from_hex(hex).unwrap()
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        let chainwork = "000000000000000000000000000000000000000088e186b4b5a5ba1d4bb6e6fb";
+        assert_eq!(u256(chainwork).to_hex(), chainwork);
+        assert_eq!(u256("0x100010001"), U256::from_u64(0x1_0001_0001));
+        assert_eq!(U256::MAX.to_hex(), "f".repeat(64));
+        assert_eq!(
+            U256::from_hex(&"f".repeat(65)),
+            Err(DecodeError::InvalidLength {
+                expected: 32,
+                actual: 33
+            })
+        );
+        assert_eq!(U256::from_hex("xyz"), Err(DecodeError::InvalidHex));
+    }
+
+    #[test]
+    fn bytes_and_hashes() {
+        let value = u256("0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20");
+        let bytes = value.to_be_bytes();
+        assert_eq!((bytes[0], bytes[31]), (0x01, 0x20));
+        assert_eq!(U256::from_be_bytes(bytes), value);
+        let mut reversed = bytes;
+        reversed.reverse();
+        assert_eq!(U256::from_le_bytes(reversed), value);
+        // A hash compares as the number its RPC hex reads as.
+        let hash: Hash256 = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            U256::from_hash(&hash),
+            u256("000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f")
+        );
+    }
+
+    #[test]
+    fn arithmetic_carries_across_limbs() {
+        let low_max = U256::from_u64(u64::MAX);
+        assert_eq!(low_max + U256::ONE, U256([0, 1, 0, 0]));
+        assert_eq!(U256([0, 1, 0, 0]) - U256::ONE, low_max);
+        assert_eq!(U256::MAX.overflowing_add(U256::ONE), (U256::ZERO, true));
+        assert_eq!(U256::ZERO.overflowing_sub(U256::ONE), (U256::MAX, true));
+        assert_eq!(U256::MAX.checked_add(U256::ONE), None);
+        assert_eq!(
+            low_max.wrapping_mul_u64(u64::MAX),
+            U256([1, u64::MAX - 1, 0, 0])
+        );
+        assert_eq!(!U256::ZERO, U256::MAX);
+    }
+
+    #[test]
+    fn shifts() {
+        assert_eq!(U256::ONE << 255, u256(&format!("8{}", "0".repeat(63))));
+        assert_eq!(U256::ONE << 64, U256([0, 1, 0, 0]));
+        assert_eq!(U256::ONE << 256, U256::ZERO);
+        assert_eq!(U256::MAX >> 255, U256::ONE);
+        assert_eq!(U256([0, 1, 0, 0]) >> 1, U256([1 << 63, 0, 0, 0]));
+        assert_eq!(U256::MAX >> 256, U256::ZERO);
+    }
+
+    #[test]
+    fn division() {
+        let (quotient, remainder) = U256::MAX.div_rem(U256::from_u64(10));
+        assert_eq!(quotient.wrapping_mul_u64(10) + remainder, U256::MAX);
+        assert_eq!(remainder, U256::from_u64(5));
+        assert_eq!(U256::from_u64(7) / U256::from_u64(8), U256::ZERO);
+        assert_eq!(U256::MAX / U256::MAX, U256::ONE);
+    }
+
+    #[test]
+    fn bits_and_ordering() {
+        assert_eq!(U256::ZERO.bits(), 0);
+        assert_eq!(U256::ONE.bits(), 1);
+        assert_eq!(U256([0, 0, 1, 0]).bits(), 129);
+        assert_eq!(U256::MAX.bits(), 256);
+        assert!(U256([0, 0, 0, 1]) > U256([u64::MAX, u64::MAX, u64::MAX, 0]));
+        assert_eq!(U256::from_u64(1 << 53).to_f64(), 9_007_199_254_740_992.0);
+        assert_eq!((U256::ONE << 128).to_f64(), 2f64.powi(128));
+    }
+}
@@ -0,0 +1,512 @@
+// Fee market analytics over a range of blocks, from the per-block statistics of getblockstats:
+// rolling feerate percentiles, block space utilization, fee revenue against the subsidy and the
+// share of segwit transactions, exported as a time series.
+//
+//     let mut fee_market = FeeMarket::new(&client);
+//     let samples = fee_market.samples(&BlockRange::Heights { start: 840_000, end: 840_143 }, 6)?;
+//     println!("{}", samples_to_csv(&samples));
+//
+// Stats are fetched in batches and cached, so overlapping queries only fetch the blocks they
+// haven't seen, and so are the median times probed to find the heights of a time range. Blocks
+// near the tip aren't cached, as they could still be reorganized. The stats need the blocks'
+// undo data, so pruned blocks can't be queried, and the genesis block is skipped.
+use std::collections::HashMap;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Serialize};
+use serde_json::value::{to_raw_value, RawValue};
+
+use crate::client::Client;
+use crate::command::get_block_count::GetBlockCountCommand;
+use crate::command::get_block_stats::{
+    GetBlockStatsCommand, GetBlockStatsCommandWithSelectiveStatsResponse, StatNotSelected,
+    StatsArgumentChoices, TargetBlockArgument,
+};
+use crate::command::request::request_batch;
+use crate::command::CallableCommand;
+
+const GET_BLOCK_STATS_COMMAND: &str = "getblockstats";
+// The consensus limit on block weight.
+pub const MAX_BLOCK_WEIGHT: u64 = 4_000_000;
+const DEFAULT_BATCH_SIZE: usize = 100;
+// Blocks with fewer confirmations than this aren't cached.
+const CACHE_MIN_CONFIRMATIONS: u64 = 6;
+const FEE_STATS: [StatsArgumentChoices; 11] = [
+    StatsArgumentChoices::Blockhash,
+    StatsArgumentChoices::Height,
+    StatsArgumentChoices::Time,
+    StatsArgumentChoices::MedianTime,
+    StatsArgumentChoices::Txs,
+    StatsArgumentChoices::SwTxs,
+    StatsArgumentChoices::TotalWeight,
+    StatsArgumentChoices::TotalFee,
+    StatsArgumentChoices::Subsidy,
+    StatsArgumentChoices::AvgFeeRate,
+    StatsArgumentChoices::FeeRatePercentiles,
+];
+
+// The blocks to query. Times are unix timestamps, matched against the blocks' median time past,
+// which unlike the block time never decreases along the chain. Both ends are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockRange {
+    Heights { start: u64, end: u64 },
+    Times { start: u64, end: u64 },
+}
+
+// The fee related stats of one block.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockFeeStats {
+    pub height: u64,
+    pub blockhash: String,
+    pub time: u64,                     // The block time
+    pub mediantime: u64,               // The block median time past
+    pub txs: u64,                      // The number of transactions (including coinbase)
+    pub swtxs: u64,                    // The number of segwit transactions
+    pub total_weight: u64,             // Total weight of all non-coinbase transactions
+    pub totalfee: u64,                 // The fee total, in satoshis
+    pub subsidy: u64,                  // The block subsidy, in satoshis
+    pub avgfeerate: u64,               // Average feerate (in satoshis per virtual byte)
+    pub feerate_percentiles: [u64; 5], // Feerates at the 10th, 25th, 50th, 75th, and 90th percentile weight unit (in satoshis per virtual byte)
+}
+
+impl BlockFeeStats {
+    // The share of the maximum block weight used by transactions, from 0 to 1. The coinbase
+    // transaction isn't counted.
+    pub fn block_space_utilization(&self) -> f64 {
+        self.total_weight as f64 / MAX_BLOCK_WEIGHT as f64
+    }
+    // The fees earned by the miner relative to the subsidy. None once the subsidy is zero.
+    pub fn fee_to_subsidy_ratio(&self) -> Option<f64> {
+        if self.subsidy == 0 {
+            return None;
+        }
+        Some(self.totalfee as f64 / self.subsidy as f64)
+    }
+    // The share of the block's transactions that are segwit, from 0 to 1. The coinbase
+    // transaction isn't counted. None for a block with only a coinbase transaction.
+    pub fn segwit_share(&self) -> Option<f64> {
+        let non_coinbase_txs = self.txs.saturating_sub(1);
+        if non_coinbase_txs == 0 {
+            return None;
+        }
+        Some(self.swtxs as f64 / non_coinbase_txs as f64)
+    }
+}
+
+// A point of the fee market time series.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FeeMarketSample {
+    pub height: u64,
+    pub time: u64,
+    pub mediantime: u64,
+    pub feerate_percentiles: [u64; 5], // the block's own feerate percentiles
+    pub rolling_feerate_percentiles: [f64; 5], // the median of each percentile over the window ending at this block
+    pub avgfeerate: u64,
+    pub totalfee: u64,
+    pub subsidy: u64,
+    pub fee_to_subsidy_ratio: Option<f64>,
+    pub block_space_utilization: f64,
+    pub segwit_share: Option<f64>,
+}
+
+// Per block, the median of each feerate percentile over the `window` blocks ending at it. Blocks
+// near the start have a shorter window. The median keeps a single block of outliers, like one
+// full of consolidations, from skewing the series.
+pub fn rolling_feerate_percentiles(blocks: &[BlockFeeStats], window: usize) -> Vec<[f64; 5]> {
+    let window = window.max(1);
+    (0..blocks.len())
+        .map(|i| {
+            let blocks = &blocks[(i + 1).saturating_sub(window)..=i];
+            let mut rolling = [0.0; 5];
+            for (percentile, value) in rolling.iter_mut().enumerate() {
+                let mut feerates: Vec<u64> = blocks
+                    .iter()
+                    .map(|block| block.feerate_percentiles[percentile])
+                    .collect();
+                *value = median(&mut feerates);
+            }
+            rolling
+        })
+        .collect()
+}
+
+fn median(values: &mut [u64]) -> f64 {
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] as f64 + values[middle] as f64) / 2.0
+    } else {
+        values[middle] as f64
+    }
+}
+
+// The samples as CSV, one row per block. For JSON, serialize the samples with serde_json.
+pub fn samples_to_csv(samples: &[FeeMarketSample]) -> String {
+    let mut csv = String::from(
+        "height,time,mediantime,feerate_p10,feerate_p25,feerate_p50,feerate_p75,feerate_p90,\
+         rolling_feerate_p10,rolling_feerate_p25,rolling_feerate_p50,rolling_feerate_p75,\
+         rolling_feerate_p90,avgfeerate,totalfee,subsidy,fee_to_subsidy_ratio,\
+         block_space_utilization,segwit_share\n",
+    );
+    let optional = |value: Option<f64>| value.map(|value| value.to_string()).unwrap_or_default();
+    for sample in samples {
+        let mut row: Vec<String> = vec![
+            sample.height.to_string(),
+            sample.time.to_string(),
+            sample.mediantime.to_string(),
+        ];
+        row.extend(sample.feerate_percentiles.iter().map(u64::to_string));
+        row.extend(
+            sample
+                .rolling_feerate_percentiles
+                .iter()
+                .map(f64::to_string),
+        );
+        row.extend([
+            sample.avgfeerate.to_string(),
+            sample.totalfee.to_string(),
+            sample.subsidy.to_string(),
+            optional(sample.fee_to_subsidy_ratio),
+            sample.block_space_utilization.to_string(),
+            optional(sample.segwit_share),
+        ]);
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub struct FeeMarket<'a> {
+    client: &'a Client,
+    batch_size: usize,
+    cache: HashMap<u64, BlockFeeStats>,
+    mediantimes: HashMap<u64, u64>, // by height, for the blocks probed but not fetched
+}
+
+impl<'a> FeeMarket<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        FeeMarket {
+            client,
+            batch_size: DEFAULT_BATCH_SIZE,
+            cache: HashMap::new(),
+            mediantimes: HashMap::new(),
+        }
+    }
+    // How many getblockstats calls are sent per batch.
+    pub fn set_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+        self.mediantimes.clear();
+    }
+
+    // The heights of the range, or None if no block falls in it.
+    pub fn resolve_range(
+        &mut self,
+        range: &BlockRange,
+    ) -> Result<Option<(u64, u64)>, jsonrpc::Error> {
+        let tip = GetBlockCountCommand::new().call(self.client)?.0;
+        let (start, end) = match *range {
+            BlockRange::Heights { start, end } => (start.max(1), end.min(tip)),
+            BlockRange::Times { start, end } => {
+                let first = self.first_height_with_mediantime_above(start, tip, false)?;
+                let after_last = self.first_height_with_mediantime_above(end, tip, true)?;
+                (first, after_last.saturating_sub(1))
+            }
+        };
+        if start > end {
+            return Ok(None);
+        }
+        Ok(Some((start, end)))
+    }
+
+    // The lowest height from 1 whose median time is at least `time` (above it if `strictly`), or
+    // tip + 1 if there is none. Median times never decrease, so this is a binary search.
+    fn first_height_with_mediantime_above(
+        &mut self,
+        time: u64,
+        tip: u64,
+        strictly: bool,
+    ) -> Result<u64, jsonrpc::Error> {
+        let (mut low, mut high) = (1, tip + 1);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let mediantime = self.mediantime(middle, tip)?;
+            let above = if strictly {
+                mediantime > time
+            } else {
+                mediantime >= time
+            };
+            if above {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+        Ok(low)
+    }
+
+    fn mediantime(&mut self, height: u64, tip: u64) -> Result<u64, jsonrpc::Error> {
+        if let Some(block) = self.cache.get(&height) {
+            return Ok(block.mediantime);
+        }
+        if let Some(mediantime) = self.mediantimes.get(&height) {
+            return Ok(*mediantime);
+        }
+        let stats = GetBlockStatsCommand::new(TargetBlockArgument::Height(height))
+            .select(vec![StatsArgumentChoices::MedianTime])
+            .call(self.client)?;
+        let mediantime = stats.mediantime().map_err(missing_stat)?;
+        if is_cacheable(height, tip) {
+            self.mediantimes.insert(height, mediantime);
+        }
+        Ok(mediantime)
+    }
+
+    // The stats of every block in the range, in order of height.
+    pub fn block_stats(
+        &mut self,
+        range: &BlockRange,
+    ) -> Result<Vec<BlockFeeStats>, jsonrpc::Error> {
+        match self.resolve_range(range)? {
+            Some((start, end)) => self.fetch(start, end),
+            None => Ok(vec![]),
+        }
+    }
+
+    // The time series of the range. Rolling percentiles are over `window` blocks, and blocks
+    // before the range are fetched so its first samples have a full window too.
+    pub fn samples(
+        &mut self,
+        range: &BlockRange,
+        window: usize,
+    ) -> Result<Vec<FeeMarketSample>, jsonrpc::Error> {
+        let (start, end) = match self.resolve_range(range)? {
+            Some(heights) => heights,
+            None => return Ok(vec![]),
+        };
+        let window = window.max(1);
+        let fetch_start = start.saturating_sub(window as u64 - 1).max(1);
+        let blocks = self.fetch(fetch_start, end)?;
+        let rolling = rolling_feerate_percentiles(&blocks, window);
+        let samples = blocks
+            .iter()
+            .zip(rolling)
+            .skip((start - fetch_start) as usize)
+            .map(|(block, rolling_feerate_percentiles)| FeeMarketSample {
+                height: block.height,
+                time: block.time,
+                mediantime: block.mediantime,
+                feerate_percentiles: block.feerate_percentiles,
+                rolling_feerate_percentiles,
+                avgfeerate: block.avgfeerate,
+                totalfee: block.totalfee,
+                subsidy: block.subsidy,
+                fee_to_subsidy_ratio: block.fee_to_subsidy_ratio(),
+                block_space_utilization: block.block_space_utilization(),
+                segwit_share: block.segwit_share(),
+            })
+            .collect();
+        Ok(samples)
+    }
+
+    fn fetch(&mut self, start: u64, end: u64) -> Result<Vec<BlockFeeStats>, jsonrpc::Error> {
+        let missing: Vec<u64> = (start..=end)
+            .filter(|height| !self.cache.contains_key(height))
+            .collect();
+        let mut fetched = HashMap::new();
+        for heights in missing.chunks(self.batch_size) {
+            let params: Vec<Vec<Box<RawValue>>> = heights
+                .iter()
+                .map(|height| block_stats_params(*height))
+                .collect();
+            for response in request_batch(self.client, GET_BLOCK_STATS_COMMAND, &params)? {
+                let block = block_fee_stats(response.result()?).map_err(missing_stat)?;
+                fetched.insert(block.height, block);
+            }
+        }
+        let tip = GetBlockCountCommand::new().call(self.client)?.0;
+        let mut blocks = Vec::with_capacity((end - start + 1) as usize);
+        for height in start..=end {
+            let block = match fetched.remove(&height) {
+                Some(block) => block,
+                None => match self.cache.get(&height) {
+                    Some(block) => block.clone(),
+                    None => return Err(missing_block(height)),
+                },
+            };
+            if is_cacheable(height, tip) {
+                self.cache.insert(height, block.clone());
+            }
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+}
+
+fn block_stats_params(height: u64) -> Vec<Box<RawValue>> {
+    vec![
+        to_raw_value(&height).unwrap(),
+        to_raw_value(&FEE_STATS).unwrap(),
+    ]
+}
+
+fn is_cacheable(height: u64, tip: u64) -> bool {
+    tip.saturating_sub(height) + 1 >= CACHE_MIN_CONFIRMATIONS
+}
+
+// The batch had no response for a block, e.g. one reorganized away while it was fetched.
+fn missing_block(height: u64) -> jsonrpc::Error {
+    jsonrpc::Error::Json(serde_json::Error::custom(format!(
+        "getblockstats didn't return the block at height {}",
+        height
+    )))
+}
+
+fn missing_stat(error: StatNotSelected) -> jsonrpc::Error {
+    jsonrpc::Error::Json(serde_json::Error::custom(format!(
+        "getblockstats didn't return {}",
+        error.0
+    )))
+}
+
+fn block_fee_stats(
+    stats: GetBlockStatsCommandWithSelectiveStatsResponse,
+) -> Result<BlockFeeStats, StatNotSelected> {
+    Ok(BlockFeeStats {
+        height: stats.height()?,
+        blockhash: stats.blockhash()?,
+        time: stats.time()?,
+        mediantime: stats.mediantime()?,
+        txs: stats.txs()?,
+        swtxs: stats.swtxs()?,
+        total_weight: stats.total_weight()?,
+        totalfee: stats.totalfee()?,
+        subsidy: stats.subsidy()?,
+        avgfeerate: stats.avgfeerate()?,
+        feerate_percentiles: stats.feerate_percentiles()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(height: u64, feerate_percentiles: [u64; 5]) -> BlockFeeStats {
+        BlockFeeStats {
+            height,
+            blockhash: format!("{:064x}", height),
+            time: 1_700_000_000 + height * 600,
+            mediantime: 1_700_000_000 + height * 600 - 3000,
+            txs: 3001,
+            swtxs: 2400,
+            total_weight: 3_990_000,
+            totalfee: 31_250_000,
+            subsidy: 312_500_000,
+            avgfeerate: 25,
+            feerate_percentiles,
+        }
+    }
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        assert_eq!(median(&mut [7]), 7.0);
+        assert_eq!(median(&mut [9, 1, 5]), 5.0);
+        assert_eq!(median(&mut [4, 1, 3, 2]), 2.5);
+        assert_eq!(median(&mut [u64::MAX, u64::MAX]), u64::MAX as f64);
+    }
+
+    #[test]
+    fn rolling_percentiles_over_a_window_shorter_than_the_blocks() {
+        let blocks = [
+            block(1, [1, 2, 3, 4, 5]),
+            block(2, [3, 4, 5, 6, 7]),
+            block(3, [100, 200, 300, 400, 500]),
+            block(4, [2, 2, 2, 2, 2]),
+        ];
+        assert_eq!(
+            rolling_feerate_percentiles(&blocks, 3),
+            vec![
+                [1.0, 2.0, 3.0, 4.0, 5.0],
+                [2.0, 3.0, 4.0, 5.0, 6.0],
+                [3.0, 4.0, 5.0, 6.0, 7.0],
+                [3.0, 4.0, 5.0, 6.0, 7.0],
+            ]
+        );
+        // A window of 0 is a window of 1, each block on its own.
+        assert_eq!(
+            rolling_feerate_percentiles(&blocks[2..], 0),
+            vec![[100.0, 200.0, 300.0, 400.0, 500.0], [2.0; 5]]
+        );
+        assert!(rolling_feerate_percentiles(&[], 6).is_empty());
+    }
+
+    #[test]
+    fn rolling_percentiles_over_a_window_longer_than_the_blocks() {
+        let blocks = [block(1, [1, 1, 1, 1, 1]), block(2, [2, 2, 2, 2, 2])];
+        assert_eq!(
+            rolling_feerate_percentiles(&blocks, 144),
+            vec![[1.0; 5], [1.5; 5]]
+        );
+    }
+
+    #[test]
+    fn block_ratios() {
+        let full = block(840_000, [1; 5]);
+        assert_eq!(full.block_space_utilization(), 0.9975);
+        assert_eq!(full.fee_to_subsidy_ratio(), Some(0.1));
+        assert_eq!(full.segwit_share(), Some(0.8));
+    }
+
+    #[test]
+    fn a_coinbase_only_block() {
+        let empty = BlockFeeStats {
+            txs: 1,
+            swtxs: 0,
+            total_weight: 0,
+            totalfee: 0,
+            avgfeerate: 0,
+            ..block(840_001, [0; 5])
+        };
+        assert_eq!(empty.block_space_utilization(), 0.0);
+        assert_eq!(empty.fee_to_subsidy_ratio(), Some(0.0));
+        assert_eq!(empty.segwit_share(), None);
+    }
+
+    #[test]
+    fn no_fee_to_subsidy_ratio_without_a_subsidy() {
+        let last = BlockFeeStats {
+            subsidy: 0,
+            ..block(6_930_000, [1; 5])
+        };
+        assert_eq!(last.fee_to_subsidy_ratio(), None);
+    }
+
+    #[test]
+    fn csv_has_a_row_per_sample_and_blank_missing_values() {
+        let sample = FeeMarketSample {
+            height: 840_000,
+            time: 1_713_571_767,
+            mediantime: 1_713_570_177,
+            feerate_percentiles: [10, 20, 30, 40, 50],
+            rolling_feerate_percentiles: [10.0, 20.5, 30.0, 40.0, 50.0],
+            avgfeerate: 31,
+            totalfee: 0,
+            subsidy: 0,
+            fee_to_subsidy_ratio: None,
+            block_space_utilization: 0.25,
+            segwit_share: None,
+        };
+        let csv = samples_to_csv(&[sample]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].split(',').count(), 19);
+        assert_eq!(
+            lines[1],
+            "840000,1713571767,1713570177,10,20,30,40,50,10,20.5,30,40,50,31,0,0,,0.25,"
+        );
+        assert_eq!(samples_to_csv(&[]).lines().count(), 1);
+    }
+}
@@ -0,0 +1,273 @@
+// A regtest bitcoind for integration tests. BitcoindHarness starts a user supplied bitcoind binary
+// on a fresh temporary datadir and free ports, waits until its RPC server is ready and hands out a
+// Client for it. The node is stopped and its datadir removed when the harness is dropped.
+//
+// Tests find the binary through the BITCOIND_EXE environment variable and skip when it isn't set:
+//
+//     let node = match BitcoindHarness::from_env() {
+//         Some(node) => node.expect("failed to start bitcoind"),
+//         None => return,
+//     };
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use jsonrpc::simple_http;
+
+use crate::client::Client;
+use crate::command::generate_to_descriptor;
+use crate::Blockhash;
+
+pub const BITCOIND_EXE_ENV_VAR: &str = "BITCOIND_EXE";
+// RPC_IN_WARMUP: the node is up but still loading (block index, wallet, ...).
+const RPC_IN_WARMUP: i32 = -28;
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+static DATADIR_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug)]
+pub enum HarnessError {
+    Io(io::Error),
+    Client(simple_http::Error),
+    // bitcoind exited before its RPC server was ready.
+    Exited(ExitStatus),
+    // The RPC server wasn't ready in time. Holds the last error seen while waiting.
+    NotReady(String),
+}
+
+impl fmt::Display for HarnessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HarnessError::Io(error) => write!(f, "{}", error),
+            HarnessError::Client(error) => write!(f, "{}", error),
+            HarnessError::Exited(status) => write!(f, "bitcoind exited during startup: {}", status),
+            HarnessError::NotReady(last_error) => write!(
+                f,
+                "bitcoind RPC wasn't ready after {:?}: {}",
+                STARTUP_TIMEOUT, last_error
+            ),
+        }
+    }
+}
+
+impl Error for HarnessError {}
+
+impl From<io::Error> for HarnessError {
+    fn from(error: io::Error) -> Self {
+        HarnessError::Io(error)
+    }
+}
+
+impl From<simple_http::Error> for HarnessError {
+    fn from(error: simple_http::Error) -> Self {
+        HarnessError::Client(error)
+    }
+}
+
+pub struct BitcoindHarness {
+    process: Child,
+    datadir: PathBuf,
+    rpc_url: String,
+    rpc_user: String,
+    rpc_password: String,
+    client: Client,
+}
+
+impl BitcoindHarness {
+    // Starts the binary at BITCOIND_EXE, or returns None when it isn't set so tests can skip.
+    pub fn from_env() -> Option<Result<Self, HarnessError>> {
+        BitcoindHarness::from_env_with_args(&[])
+    }
+
+    // Like from_env, passing extra arguments to bitcoind (see start_with_args).
+    pub fn from_env_with_args(extra_args: &[&str]) -> Option<Result<Self, HarnessError>> {
+        match std::env::var_os(BITCOIND_EXE_ENV_VAR) {
+            Some(path) if !path.is_empty() => {
+                Some(BitcoindHarness::start_with_args(path, extra_args))
+            }
+            _ => {
+                eprintln!("{} is not set, skipping", BITCOIND_EXE_ENV_VAR);
+                None
+            }
+        }
+    }
+
+    pub fn start(bitcoind_path: impl AsRef<Path>) -> Result<Self, HarnessError> {
+        BitcoindHarness::start_with_args(bitcoind_path, &[])
+    }
+
+    // Starts bitcoind with extra arguments, e.g. "-txindex" or "-blockfilterindex".
+    pub fn start_with_args(
+        bitcoind_path: impl AsRef<Path>,
+        extra_args: &[&str],
+    ) -> Result<Self, HarnessError> {
+        let datadir = create_datadir()?;
+        let rpc_port = free_port()?;
+        let p2p_port = free_port()?;
+        let process = Command::new(bitcoind_path.as_ref())
+            .arg("-regtest")
+            .arg(format!("-datadir={}", datadir.display()))
+            .arg(format!("-rpcport={}", rpc_port))
+            .arg(format!("-port={}", p2p_port))
+            .arg("-rpcbind=127.0.0.1")
+            .arg("-rpcallowip=127.0.0.1")
+            .arg("-listen=0")
+            .arg("-server=1")
+            .arg("-printtoconsole=0")
+            .arg("-fallbackfee=0.0002")
+            .args(extra_args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut process = match process {
+            Ok(process) => process,
+            Err(error) => {
+                let _ = fs::remove_dir_all(&datadir);
+                return Err(error.into());
+            }
+        };
+        let rpc_url = format!("http://127.0.0.1:{}", rpc_port);
+        let connected = wait_for_cookie(&mut process, &datadir).and_then(|(user, password)| {
+            let client = Client::new(&rpc_url, &user, &password)?;
+            Ok((user, password, client))
+        });
+        // Until there's a harness to drop, the node is torn down here.
+        let (rpc_user, rpc_password, client) = match connected {
+            Ok(connected) => connected,
+            Err(error) => {
+                let _ = process.kill();
+                let _ = process.wait();
+                let _ = fs::remove_dir_all(&datadir);
+                return Err(error);
+            }
+        };
+        let harness = BitcoindHarness {
+            process,
+            datadir,
+            rpc_url,
+            rpc_user,
+            rpc_password,
+            client,
+        };
+        harness.wait_for_rpc()?;
+        Ok(harness)
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+    // The cookie credentials, for building additional clients.
+    pub fn rpc_user(&self) -> &str {
+        &self.rpc_user
+    }
+    pub fn rpc_password(&self) -> &str {
+        &self.rpc_password
+    }
+    pub fn datadir(&self) -> &Path {
+        &self.datadir
+    }
+
+    // Mines blocks to an anyone-can-spend output.
+    pub fn generate(&self, num_blocks: u64) -> Result<Vec<Blockhash>, jsonrpc::Error> {
+        generate_to_descriptor::generate(&self.client, num_blocks)
+    }
+
+    // Polls getblockchaininfo until it succeeds. Calls fail with a connection error until the RPC
+    // server is listening, then with RPC_IN_WARMUP until the node has finished loading.
+    fn wait_for_rpc(&self) -> Result<(), HarnessError> {
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        let mut last_error = String::new();
+        while Instant::now() < deadline {
+            let request = self.client.build_request("getblockchaininfo", &[]);
+            match self
+                .client
+                .send_request(request)
+                .and_then(|response| response.result::<serde_json::Value>())
+            {
+                Ok(_) => return Ok(()),
+                Err(jsonrpc::Error::Rpc(error)) if error.code == RPC_IN_WARMUP => {
+                    last_error = error.message;
+                }
+                Err(error) => last_error = error.to_string(),
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        Err(HarnessError::NotReady(last_error))
+    }
+}
+
+impl Drop for BitcoindHarness {
+    fn drop(&mut self) {
+        // Ask for a clean shutdown first, and kill the node if it doesn't exit in time.
+        let request = self.client.build_request("stop", &[]);
+        let _ = self.client.send_request(request);
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        while Instant::now() < deadline {
+            match self.process.try_wait() {
+                Ok(Some(_)) => break,
+                Ok(None) => thread::sleep(POLL_INTERVAL),
+                Err(_) => break,
+            }
+        }
+        if let Ok(None) = self.process.try_wait() {
+            let _ = self.process.kill();
+            let _ = self.process.wait();
+        }
+        let _ = fs::remove_dir_all(&self.datadir);
+    }
+}
+
+fn create_datadir() -> io::Result<PathBuf> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    let datadir = std::env::temp_dir().join(format!(
+        "bitcoind-request-{}-{}-{}",
+        std::process::id(),
+        nanos,
+        DATADIR_COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    fs::create_dir_all(&datadir)?;
+    Ok(datadir)
+}
+
+// Asks the OS for an unused port. Another process could grab it before bitcoind binds it, but
+// that's unlikely enough for tests.
+fn free_port() -> io::Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
+
+// bitcoind writes "__cookie__:<password>" to the cookie file once its RPC server is set up.
+fn wait_for_cookie(process: &mut Child, datadir: &Path) -> Result<(String, String), HarnessError> {
+    let cookie_path = datadir.join("regtest").join(".cookie");
+    let deadline = Instant::now() + STARTUP_TIMEOUT;
+    while Instant::now() < deadline {
+        if let Some(status) = process.try_wait()? {
+            return Err(HarnessError::Exited(status));
+        }
+        if let Ok(cookie) = fs::read_to_string(&cookie_path) {
+            if let Some((user, password)) = cookie.trim().split_once(':') {
+                return Ok((user.to_string(), password.to_string()));
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    Err(HarnessError::NotReady(format!(
+        "no cookie file at {}",
+        cookie_path.display()
+    )))
+}
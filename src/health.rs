@@ -0,0 +1,636 @@
+// A health check for a node, combining several commands into one verdict with the reasons for
+// it, for load balancers and liveness/readiness probes:
+//
+//     let verdict = HealthChecker::new(&client).check()?;
+//     if verdict.status != HealthStatus::Healthy {
+//         for reason in &verdict.reasons {
+//             println!("{}", reason.message);
+//         }
+//     }
+//
+// or served as JSON with HealthChecker::serve. The node's state is gathered by NodeState::fetch
+// and judged by Thresholds::evaluate, which can also be used on its own.
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io;
+use std::net::{IpAddr, TcpListener};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::de::Error as _;
+use serde::Serialize;
+
+use crate::client::Client;
+use crate::command::get_block_header::{GetBlockHeaderCommand, GetBlockHeaderCommandResponse};
+use crate::command::get_blockchain_info::GetBlockchainInfoCommand;
+use crate::command::get_connection_count::GetConnectionCountCommand;
+use crate::command::get_network_info::GetNetworkInfoCommand;
+use crate::command::get_peer_info::{ConnectedNetworkNode, ConnectionType, GetPeerInfoCommand};
+use crate::command::set_ban::Subnet;
+use crate::command::CallableCommand;
+use crate::http::{self, HttpResponse};
+use crate::Blockhash;
+
+pub const CONTENT_TYPE: &str = "application/json";
+// Fails only when the node is unhealthy, for liveness probes.
+pub const HEALTH_PATH: &str = "/health";
+// Fails unless the node is healthy, for readiness probes and load balancers.
+pub const READY_PATH: &str = "/ready";
+
+// Ordered from best to worst, so the verdict is the worst status of its reasons.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+impl HealthStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthStatus::Healthy => "healthy",
+            HealthStatus::Degraded => "degraded",
+            HealthStatus::Unhealthy => "unhealthy",
+        }
+    }
+}
+
+impl fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Check {
+    Rpc, // the node couldn't be queried
+    InitialBlockDownload,
+    VerificationProgress,
+    TipAge,
+    HeaderLag,
+    Peers,
+    OutboundDiversity,
+    NetworkActive,
+    Warnings,
+}
+
+// Why a node isn't healthy.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Reason {
+    pub check: Check,
+    pub status: HealthStatus,
+    pub message: String,
+}
+
+// The two levels of a check. For the checks on a maximum (tip age, header lag) a value above
+// `degraded` degrades the node and one above `unhealthy` makes it unhealthy. For the checks on a
+// minimum (peers, outbound diversity) it's a value below them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Threshold<T> {
+    pub degraded: T,
+    pub unhealthy: T,
+}
+
+impl<T: PartialOrd> Threshold<T> {
+    fn above(&self, value: T) -> HealthStatus {
+        if value > self.unhealthy {
+            HealthStatus::Unhealthy
+        } else if value > self.degraded {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        }
+    }
+    fn below(&self, value: T) -> HealthStatus {
+        if value < self.unhealthy {
+            HealthStatus::Unhealthy
+        } else if value < self.degraded {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thresholds {
+    pub tip_age: Threshold<Duration>, // time since the tip's block time
+    pub header_lag: Threshold<u64>,   // validated headers whose blocks aren't validated yet
+    pub peers: Threshold<u64>,        // connected peers, inbound and outbound
+    pub outbound_netgroups: Threshold<u64>, // distinct network groups of the outbound peers
+    pub min_verification_progress: f64, // degraded below this, outside initial block download
+    // If not, a node in initial block download is unhealthy. If it is, its tip age and header
+    // lag, which are expected to be large while it syncs, only degrade it.
+    pub allow_initial_block_download: bool,
+    pub allow_warnings: bool, // if not, a node with warnings is degraded
+}
+
+impl Thresholds {
+    pub fn new() -> Self {
+        Thresholds {
+            // A block takes longer than an hour about once every 400 blocks, and longer than
+            // three hours practically never.
+            tip_age: Threshold {
+                degraded: Duration::from_secs(60 * 60),
+                unhealthy: Duration::from_secs(3 * 60 * 60),
+            },
+            header_lag: Threshold {
+                degraded: 2,
+                unhealthy: 12,
+            },
+            peers: Threshold {
+                degraded: 4,
+                unhealthy: 1,
+            },
+            outbound_netgroups: Threshold {
+                degraded: 2,
+                unhealthy: 0,
+            },
+            min_verification_progress: 0.9999,
+            allow_initial_block_download: false,
+            allow_warnings: false,
+        }
+    }
+    pub fn set_tip_age(mut self, tip_age: Threshold<Duration>) -> Self {
+        self.tip_age = tip_age;
+        self
+    }
+    pub fn set_header_lag(mut self, header_lag: Threshold<u64>) -> Self {
+        self.header_lag = header_lag;
+        self
+    }
+    pub fn set_peers(mut self, peers: Threshold<u64>) -> Self {
+        self.peers = peers;
+        self
+    }
+    pub fn set_outbound_netgroups(mut self, outbound_netgroups: Threshold<u64>) -> Self {
+        self.outbound_netgroups = outbound_netgroups;
+        self
+    }
+    pub fn set_min_verification_progress(mut self, min_verification_progress: f64) -> Self {
+        self.min_verification_progress = min_verification_progress;
+        self
+    }
+    pub fn set_allow_initial_block_download(mut self, allow_initial_block_download: bool) -> Self {
+        self.allow_initial_block_download = allow_initial_block_download;
+        self
+    }
+    pub fn set_allow_warnings(mut self, allow_warnings: bool) -> Self {
+        self.allow_warnings = allow_warnings;
+        self
+    }
+
+    // The verdict on the node's state at `now` (a unix timestamp).
+    pub fn evaluate(&self, state: NodeState, now: u64) -> Verdict {
+        let mut reasons = vec![];
+        let mut add = |check: Check, status: HealthStatus, message: String| {
+            if status != HealthStatus::Healthy {
+                reasons.push(Reason {
+                    check,
+                    status,
+                    message,
+                });
+            }
+        };
+
+        if state.initial_block_download {
+            let status = if self.allow_initial_block_download {
+                HealthStatus::Healthy
+            } else {
+                HealthStatus::Unhealthy
+            };
+            add(
+                Check::InitialBlockDownload,
+                status,
+                format!(
+                    "in initial block download ({:.2}% verified)",
+                    state.verification_progress * 100.0
+                ),
+            );
+        } else if state.verification_progress < self.min_verification_progress {
+            add(
+                Check::VerificationProgress,
+                HealthStatus::Degraded,
+                format!(
+                    "verification progress is {:.4}%",
+                    state.verification_progress * 100.0
+                ),
+            );
+        }
+
+        // A syncing node stays degraded, so out of the ready set, until it's caught up.
+        let syncing = state.initial_block_download && self.allow_initial_block_download;
+        let while_syncing = |status: HealthStatus| {
+            if syncing {
+                status.min(HealthStatus::Degraded)
+            } else {
+                status
+            }
+        };
+
+        let tip_age = Duration::from_secs(now.saturating_sub(state.tip_time));
+        add(
+            Check::TipAge,
+            while_syncing(self.tip_age.above(tip_age)),
+            format!("the tip is {} seconds old", tip_age.as_secs()),
+        );
+
+        let header_lag = state.headers.saturating_sub(state.blocks);
+        add(
+            Check::HeaderLag,
+            while_syncing(self.header_lag.above(header_lag)),
+            format!(
+                "{} headers ahead of the validated blocks ({} headers, {} blocks)",
+                header_lag, state.headers, state.blocks
+            ),
+        );
+
+        add(
+            Check::Peers,
+            self.peers.below(state.connections),
+            format!("{} connected peers", state.connections),
+        );
+
+        add(
+            Check::OutboundDiversity,
+            self.outbound_netgroups.below(state.outbound_netgroups),
+            format!(
+                "{} outbound peers in {} network groups",
+                state.outbound_peers, state.outbound_netgroups
+            ),
+        );
+
+        if !state.network_active {
+            add(
+                Check::NetworkActive,
+                HealthStatus::Unhealthy,
+                "p2p networking is disabled".to_string(),
+            );
+        }
+
+        if !self.allow_warnings {
+            for warning in &state.warnings {
+                add(
+                    Check::Warnings,
+                    HealthStatus::Degraded,
+                    format!("warning: {}", warning),
+                );
+            }
+        }
+
+        Verdict::new(reasons, Some(state))
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// What the checks look at, as returned by the node.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct NodeState {
+    pub initial_block_download: bool,
+    pub verification_progress: f64,
+    pub blocks: u64,
+    pub headers: u64,
+    pub tip_time: u64, // the block time of the tip
+    pub connections: u64,
+    pub outbound_peers: u64, // not counting feeler and addr-fetch connections
+    pub outbound_netgroups: u64,
+    pub network_active: bool,
+    pub warnings: Vec<String>, // from getblockchaininfo and getnetworkinfo, without duplicates
+}
+
+impl NodeState {
+    pub fn fetch(client: &Client) -> Result<Self, jsonrpc::Error> {
+        let blockchain_info = GetBlockchainInfoCommand::new().call(client)?;
+        let tip_time =
+            match GetBlockHeaderCommand::new(Blockhash(blockchain_info.bestblockhash.clone()))
+                .call(client)?
+            {
+                GetBlockHeaderCommandResponse::BlockHeader(header) => header.time,
+                GetBlockHeaderCommandResponse::BlockHash(_) => {
+                    return Err(jsonrpc::Error::Json(serde_json::Error::custom(
+                        "getblockheader didn't return a verbose header",
+                    )))
+                }
+            };
+        let connections = GetConnectionCountCommand::new().call(client)?.0;
+        let network_info = GetNetworkInfoCommand::new().call(client)?;
+        let peers = GetPeerInfoCommand::new().call(client)?.0;
+
+        let outbound: Vec<&ConnectedNetworkNode> = peers
+            .iter()
+            .filter(|peer| {
+                // Short-lived connections don't count towards the outbound peers.
+                !peer.is_inbound()
+                    && !matches!(
+                        peer.connection_type,
+                        Some(ConnectionType::Feeler | ConnectionType::AddrFetch)
+                    )
+            })
+            .collect();
+        let netgroups: BTreeSet<String> = outbound.iter().map(|peer| netgroup(peer)).collect();
+
+        let mut warnings: Vec<String> = vec![];
+        for warning in blockchain_info
+            .warnings
+            .messages()
+            .into_iter()
+            .chain(network_info.warnings.messages())
+        {
+            if !warnings.iter().any(|seen| seen == warning) {
+                warnings.push(warning.to_string());
+            }
+        }
+
+        Ok(NodeState {
+            initial_block_download: blockchain_info.initialblockdownload,
+            verification_progress: blockchain_info.verificationprogress,
+            blocks: blockchain_info.blocks,
+            headers: blockchain_info.headers,
+            tip_time,
+            connections,
+            outbound_peers: outbound.len() as u64,
+            outbound_netgroups: netgroups.len() as u64,
+            network_active: network_info.networkactive,
+            warnings,
+        })
+    }
+}
+
+// Roughly how core groups peers to diversify its outbound connections: the /16 of an IPv4
+// address, the /32 of an IPv6 address, and the network of the other addresses (onion, i2p,
+// cjdns), as they can't be told apart by location.
+fn netgroup(peer: &ConnectedNetworkNode) -> String {
+    let group = match Subnet::from_peer_addr(peer.addr()) {
+        Ok(Subnet::Ip {
+            network: address, ..
+        }) => match address {
+            IpAddr::V4(_) => Subnet::ip(address, 16),
+            IpAddr::V6(_) => Subnet::ip(address, 32),
+        }
+        .ok(),
+        _ => None,
+    };
+    match group {
+        Some(group) => group.to_string(),
+        None => peer.network().unwrap_or(peer.addr()).to_string(),
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub status: HealthStatus, // the worst status of the reasons, healthy if there are none
+    pub reasons: Vec<Reason>,
+    pub state: Option<NodeState>, // none if the node couldn't be queried
+}
+
+impl Verdict {
+    pub fn new(reasons: Vec<Reason>, state: Option<NodeState>) -> Self {
+        let status = reasons
+            .iter()
+            .map(|reason| reason.status)
+            .max()
+            .unwrap_or(HealthStatus::Healthy);
+        Verdict {
+            status,
+            reasons,
+            state,
+        }
+    }
+    // The verdict on a node that couldn't be queried: unhealthy.
+    pub fn unreachable(error: &jsonrpc::Error) -> Self {
+        Verdict::new(
+            vec![Reason {
+                check: Check::Rpc,
+                status: HealthStatus::Unhealthy,
+                message: format!("couldn't query the node: {}", error),
+            }],
+            None,
+        )
+    }
+}
+
+pub struct HealthChecker<'a> {
+    client: &'a Client,
+    thresholds: Thresholds,
+}
+
+impl<'a> HealthChecker<'a> {
+    pub fn new(client: &'a Client) -> Self {
+        HealthChecker {
+            client,
+            thresholds: Thresholds::new(),
+        }
+    }
+    pub fn set_thresholds(mut self, thresholds: Thresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    pub fn check(&self) -> Result<Verdict, jsonrpc::Error> {
+        let state = NodeState::fetch(self.client)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since| since.as_secs())
+            .unwrap_or_default();
+        Ok(self.thresholds.evaluate(state, now))
+    }
+
+    // Serves the verdict as JSON until the listener fails: on /health with a 503 status if the
+    // node is unhealthy, and on /ready with a 503 status unless it's healthy. A node that can't
+    // be queried is unhealthy, and `on_error` is called with the error, e.g. to log it.
+    pub fn serve<F: FnMut(&jsonrpc::Error)>(
+        &self,
+        listener: TcpListener,
+        mut on_error: F,
+    ) -> io::Result<()> {
+        http::serve(listener, |path| {
+            let worst_passing = match path {
+                HEALTH_PATH => HealthStatus::Degraded,
+                READY_PATH => HealthStatus::Healthy,
+                _ => return HttpResponse::not_found(),
+            };
+            let verdict = self.check().unwrap_or_else(|error| {
+                on_error(&error);
+                Verdict::unreachable(&error)
+            });
+            let status = if verdict.status <= worst_passing {
+                200
+            } else {
+                503
+            };
+            HttpResponse {
+                status,
+                content_type: CONTENT_TYPE,
+                body: serde_json::to_string(&verdict).unwrap() + "\n",
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn synced() -> NodeState {
+        NodeState {
+            initial_block_download: false,
+            verification_progress: 0.99999,
+            blocks: 800_000,
+            headers: 800_000,
+            tip_time: NOW - 600,
+            connections: 10,
+            outbound_peers: 8,
+            outbound_netgroups: 8,
+            network_active: true,
+            warnings: vec![],
+        }
+    }
+
+    fn syncing() -> NodeState {
+        NodeState {
+            initial_block_download: true,
+            verification_progress: 0.4,
+            blocks: 500_000,
+            headers: 800_000,
+            tip_time: NOW - 5 * 365 * 24 * 60 * 60,
+            ..synced()
+        }
+    }
+
+    fn checks(verdict: &Verdict) -> Vec<(Check, HealthStatus)> {
+        verdict
+            .reasons
+            .iter()
+            .map(|reason| (reason.check, reason.status))
+            .collect()
+    }
+
+    #[test]
+    fn a_synced_node_is_healthy() {
+        let verdict = Thresholds::new().evaluate(synced(), NOW);
+        assert_eq!(verdict.status, HealthStatus::Healthy);
+        assert!(verdict.reasons.is_empty());
+        assert_eq!(verdict.state, Some(synced()));
+    }
+
+    #[test]
+    fn a_slow_tip_and_few_peers_degrade_the_node() {
+        let state = NodeState {
+            tip_time: NOW - 2 * 60 * 60,
+            connections: 3,
+            warnings: vec!["unknown new rules activated".to_string()],
+            ..synced()
+        };
+        let verdict = Thresholds::new().evaluate(state.clone(), NOW);
+        assert_eq!(verdict.status, HealthStatus::Degraded);
+        assert_eq!(
+            checks(&verdict),
+            vec![
+                (Check::TipAge, HealthStatus::Degraded),
+                (Check::Peers, HealthStatus::Degraded),
+                (Check::Warnings, HealthStatus::Degraded),
+            ]
+        );
+
+        let verdict = Thresholds::new()
+            .set_allow_warnings(true)
+            .evaluate(state, NOW);
+        assert_eq!(checks(&verdict).len(), 2);
+    }
+
+    #[test]
+    fn the_worst_check_decides_the_verdict() {
+        let state = NodeState {
+            headers: 800_020,
+            network_active: false,
+            connections: 0,
+            outbound_netgroups: 0,
+            ..synced()
+        };
+        let verdict = Thresholds::new().evaluate(state, NOW);
+        assert_eq!(verdict.status, HealthStatus::Unhealthy);
+        assert_eq!(
+            checks(&verdict),
+            vec![
+                (Check::HeaderLag, HealthStatus::Unhealthy),
+                (Check::Peers, HealthStatus::Unhealthy),
+                (Check::OutboundDiversity, HealthStatus::Degraded),
+                (Check::NetworkActive, HealthStatus::Unhealthy),
+            ]
+        );
+    }
+
+    #[test]
+    fn low_verification_progress_degrades_a_synced_node() {
+        let state = NodeState {
+            verification_progress: 0.99,
+            ..synced()
+        };
+        let verdict = Thresholds::new().evaluate(state, NOW);
+        assert_eq!(
+            checks(&verdict),
+            vec![(Check::VerificationProgress, HealthStatus::Degraded)]
+        );
+    }
+
+    #[test]
+    fn initial_block_download_is_unhealthy_unless_allowed() {
+        let verdict = Thresholds::new().evaluate(syncing(), NOW);
+        assert_eq!(verdict.status, HealthStatus::Unhealthy);
+        assert_eq!(
+            checks(&verdict),
+            vec![
+                (Check::InitialBlockDownload, HealthStatus::Unhealthy),
+                (Check::TipAge, HealthStatus::Unhealthy),
+                (Check::HeaderLag, HealthStatus::Unhealthy),
+            ]
+        );
+
+        let verdict = Thresholds::new()
+            .set_allow_initial_block_download(true)
+            .evaluate(syncing(), NOW);
+        assert_eq!(verdict.status, HealthStatus::Degraded);
+        assert_eq!(
+            checks(&verdict),
+            vec![
+                (Check::TipAge, HealthStatus::Degraded),
+                (Check::HeaderLag, HealthStatus::Degraded),
+            ]
+        );
+    }
+
+    #[test]
+    fn allowing_initial_block_download_doesnt_excuse_a_synced_node() {
+        let state = NodeState {
+            tip_time: NOW - 4 * 60 * 60,
+            ..synced()
+        };
+        let verdict = Thresholds::new()
+            .set_allow_initial_block_download(true)
+            .evaluate(state, NOW);
+        assert_eq!(
+            checks(&verdict),
+            vec![(Check::TipAge, HealthStatus::Unhealthy)]
+        );
+    }
+
+    #[test]
+    fn an_unreachable_node_is_unhealthy() {
+        let error = jsonrpc::Error::Json(serde_json::Error::custom("connection refused"));
+        let verdict = Verdict::unreachable(&error);
+        assert_eq!(verdict.status, HealthStatus::Unhealthy);
+        assert_eq!(
+            checks(&verdict),
+            vec![(Check::Rpc, HealthStatus::Unhealthy)]
+        );
+        assert_eq!(verdict.state, None);
+    }
+}
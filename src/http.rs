@@ -0,0 +1,261 @@
+// Just enough HTTP/1.1 to serve a scrape endpoint: GET requests, handled one at a time, one per
+// connection.
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+// How long a client may take to send its whole request, and then to read the whole response,
+// before the connection is dropped, so a stuck client can't block the ones behind it.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+// The most a request line and headers may take, which is plenty for a scraper.
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+
+pub(crate) struct HttpResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn ok(content_type: &'static str, body: String) -> Self {
+        HttpResponse {
+            status: 200,
+            content_type,
+            body,
+        }
+    }
+    pub fn not_found() -> Self {
+        HttpResponse::error(404, "not found")
+    }
+    fn error(status: u16, message: &str) -> Self {
+        HttpResponse {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: format!("{}\n", message),
+        }
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+// Calls `handler` with the path of each request, without the query string. Errors on a
+// connection only drop that connection.
+pub(crate) fn serve<F: FnMut(&str) -> HttpResponse>(
+    listener: TcpListener,
+    mut handler: F,
+) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        let _ = handle_connection(stream, &mut handler);
+    }
+    Ok(())
+}
+
+fn handle_connection<F: FnMut(&str) -> HttpResponse>(
+    stream: TcpStream,
+    handler: &mut F,
+) -> io::Result<()> {
+    let mut method = String::new();
+    let response = match read_request(&stream)? {
+        Ok(request_line) => {
+            let mut parts = request_line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some(request_method), Some(target)) => {
+                    method = request_method.to_string();
+                    let path = target.split('?').next().unwrap_or_default();
+                    match request_method {
+                        "GET" | "HEAD" => handler(path),
+                        _ => HttpResponse::error(405, "method not allowed"),
+                    }
+                }
+                _ => HttpResponse::error(400, "bad request"),
+            }
+        }
+        Err(response) => response,
+    };
+
+    let mut bytes = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.content_type,
+        response.body.len()
+    )
+    .into_bytes();
+    if method != "HEAD" {
+        bytes.extend_from_slice(response.body.as_bytes());
+    }
+    write_all_before(&stream, &bytes, Instant::now() + WRITE_TIMEOUT)
+}
+
+// Reads the request line and the headers, which aren't used but are read so the client isn't reset
+// before it reads the response. Gives the response to send instead if the request is too large.
+fn read_request(stream: &TcpStream) -> io::Result<Result<String, HttpResponse>> {
+    let reader = ReadBefore {
+        stream,
+        deadline: Instant::now() + READ_TIMEOUT,
+    };
+    let mut reader = BufReader::new(reader).take(MAX_REQUEST_BYTES);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut header = String::new();
+    loop {
+        // Whatever was cut off by the limit, the headers didn't end within it.
+        if reader.limit() == 0 {
+            return Ok(Err(HttpResponse::error(431, "request too large")));
+        }
+        header.clear();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            return Ok(Ok(request_line));
+        }
+    }
+}
+
+// A read timeout alone bounds each read, not the request: a client sending a byte at a time keeps
+// every read under it. So, as for writes, the timeout is shortened to what's left before the
+// deadline.
+struct ReadBefore<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for ReadBefore<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        let mut stream = self.stream;
+        stream.read(buf)
+    }
+}
+
+// A write timeout alone bounds each write, not the response: a client reading a little at a time
+// keeps every write under it. So the timeout is shortened to what's left before the deadline.
+fn write_all_before(mut stream: &TcpStream, mut bytes: &[u8], deadline: Instant) -> io::Result<()> {
+    while !bytes.is_empty() {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        stream.set_write_timeout(Some(left))?;
+        match stream.write(bytes) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(written) => bytes = &bytes[written..],
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::thread;
+
+    #[test]
+    fn a_client_that_never_reads_doesnt_block_the_next_one() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            serve(listener, |path| match path {
+                // Far more than the socket buffers hold.
+                "/large" => HttpResponse::ok("text/plain", "x".repeat(64 << 20)),
+                _ => HttpResponse::ok("text/plain", "ok\n".to_string()),
+            })
+        });
+
+        let mut stalled = TcpStream::connect(address).unwrap();
+        stalled.write_all(b"GET /large HTTP/1.1\r\n\r\n").unwrap();
+
+        let start = Instant::now();
+        let mut client = TcpStream::connect(address).unwrap();
+        client.write_all(b"GET /small HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\nok\n"));
+        assert!(start.elapsed() < WRITE_TIMEOUT * 2);
+        drop(stalled);
+    }
+
+    fn get(address: std::net::SocketAddr, request: &[u8]) -> String {
+        let mut client = TcpStream::connect(address).unwrap();
+        client.write_all(request).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    fn start() -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, |_| HttpResponse::ok("text/plain", "ok\n".into())));
+        address
+    }
+
+    #[test]
+    fn a_client_sending_slowly_doesnt_block_the_next_one() {
+        let address = start();
+        let mut slow = TcpStream::connect(address).unwrap();
+        thread::spawn(move || {
+            // A byte well within the read timeout, forever.
+            while slow.write_all(b"x").is_ok() {
+                thread::sleep(Duration::from_millis(100));
+            }
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        let start = Instant::now();
+        let response = get(address, b"GET / HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(start.elapsed() < READ_TIMEOUT * 2);
+    }
+
+    #[test]
+    fn requests_over_the_limit() {
+        let address = start();
+        // Headers that don't end within the limit.
+        let mut request = b"GET / HTTP/1.1\r\nX-Padding: ".to_vec();
+        request.resize(MAX_REQUEST_BYTES as usize, b'a');
+        let response = get(address, &request);
+        assert!(response.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"));
+
+        // A request line that doesn't end within the limit.
+        let response = get(address, &vec![b'a'; MAX_REQUEST_BYTES as usize]);
+        assert!(response.starts_with("HTTP/1.1 431 "));
+
+        // Just within it.
+        let mut request = b"GET / HTTP/1.1\r\nX-Padding: ".to_vec();
+        request.resize(MAX_REQUEST_BYTES as usize - 4, b'a');
+        request.extend_from_slice(b"\r\n\r\n");
+        let response = get(address, &request);
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+
+    #[test]
+    fn malformed_request_line() {
+        let address = start();
+        let response = get(address, b"\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+        let response = get(address, b"POST / HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+    }
+}
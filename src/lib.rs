@@ -1,9 +1,21 @@
 pub mod client;
 pub mod command;
+pub mod config;
+pub mod consensus;
+pub mod fee_market;
+pub mod harness;
+pub mod health;
+mod http;
+pub mod long_poll;
+pub mod metrics;
+pub mod peer_analytics;
+pub mod sync_progress;
+pub mod types;
+pub mod version;
 // pub use command;
 use serde::{Deserialize, Serialize};
 #[derive(Debug)]
 pub struct BlockhashHexEncoded(pub String);
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Blockhash(pub String);
@@ -0,0 +1,171 @@
+// Iterators over calls that block on the node until something changes: NewBlockPoller yields the
+// tip each time it changes (waitfornewblock) and TemplatePoller yields a block template each time
+// the node's template changes (getblocktemplate long polling).
+//
+// Calls are re-issued when they time out, either on the node or in the client, so a poller can
+// run for as long as needed. It stops once cancelled through its LongPollCancel, which can be
+// shared with other threads:
+//
+//     let cancel = LongPollCancel::new();
+//     let handle = cancel.clone();
+//     thread::spawn(move || {
+//         thread::sleep(Duration::from_secs(600));
+//         handle.cancel();
+//     });
+//     for tip in NewBlockPoller::new(&client, cancel) {
+//         println!("{:?}", tip?);
+//     }
+//
+// A call in progress isn't interrupted, so cancellation takes effect when it returns: within the
+// poll timeout for NewBlockPoller, and within the client's timeout (see Client::with_timeout) for
+// TemplatePoller.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::de::Error as _;
+
+use crate::client::{is_timeout, Client};
+use crate::command::get_best_block_hash::GetBestBlockHashCommand;
+use crate::command::get_block_template::{
+    BlockTemplate, GetBlockTemplateCommand, GetBlockTemplateCommandResponse, TemplateCapability,
+    TemplateRule,
+};
+use crate::command::wait_for_new_block::{WaitForNewBlockCommand, WaitForNewBlockCommandResponse};
+use crate::command::CallableCommand;
+use crate::Blockhash;
+
+const DEFAULT_POLL_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, Default)]
+pub struct LongPollCancel(Arc<AtomicBool>);
+
+impl LongPollCancel {
+    pub fn new() -> Self {
+        LongPollCancel::default()
+    }
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+// Yields the node's tip each time it changes, starting from the tip when the first item is
+// requested. Several blocks arriving together may be reported as a single change.
+//
+// An error is yielded as an item, and iterating again retries.
+pub struct NewBlockPoller<'a> {
+    client: &'a Client,
+    cancel: LongPollCancel,
+    poll_timeout: Duration,
+    tip: Option<Blockhash>,
+}
+
+impl<'a> NewBlockPoller<'a> {
+    pub fn new(client: &'a Client, cancel: LongPollCancel) -> Self {
+        NewBlockPoller {
+            client,
+            cancel,
+            poll_timeout: DEFAULT_POLL_TIMEOUT,
+            tip: None,
+        }
+    }
+    // How long each waitfornewblock call waits on the node. Shorter timeouts make cancellation
+    // quicker at the cost of more calls. It should be below the client's timeout.
+    pub fn set_poll_timeout(mut self, poll_timeout: Duration) -> Self {
+        self.poll_timeout = poll_timeout;
+        self
+    }
+}
+
+impl Iterator for NewBlockPoller<'_> {
+    type Item = Result<WaitForNewBlockCommandResponse, jsonrpc::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.tip.is_none() && !self.cancel.is_cancelled() {
+            match GetBestBlockHashCommand::default().call(self.client) {
+                Ok(best_block_hash) => self.tip = Some(best_block_hash.0),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        let command = WaitForNewBlockCommand::new().set_timeout(self.poll_timeout);
+        while !self.cancel.is_cancelled() {
+            match command.call(self.client) {
+                // waitfornewblock returns the current tip on timeout, which also catches blocks
+                // that arrived between two calls.
+                Ok(tip) if Some(&tip.hash) != self.tip.as_ref() => {
+                    self.tip = Some(tip.hash.clone());
+                    return Some(Ok(tip));
+                }
+                Ok(_) => {}
+                Err(error) if is_timeout(&error) => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        None
+    }
+}
+
+// Yields the node's current block template, then a new one each time the node signals a change:
+// a new tip, or new transactions in the mempool (the node checks for those about once a minute).
+//
+// An error is yielded as an item, and iterating again retries the same long poll.
+pub struct TemplatePoller<'a> {
+    client: &'a Client,
+    cancel: LongPollCancel,
+    rules: Vec<TemplateRule>,
+    capabilities: Vec<TemplateCapability>,
+    longpollid: Option<String>,
+}
+
+impl<'a> TemplatePoller<'a> {
+    pub fn new(client: &'a Client, cancel: LongPollCancel) -> Self {
+        TemplatePoller {
+            client,
+            cancel,
+            rules: vec![TemplateRule::Segwit],
+            capabilities: vec![TemplateCapability::Longpoll],
+            longpollid: None,
+        }
+    }
+    // Defaults to segwit, which bitcoind requires. Signet also requires signet.
+    pub fn set_rules(mut self, rules: Vec<TemplateRule>) -> Self {
+        self.rules = rules;
+        self
+    }
+    pub fn set_capabilities(mut self, capabilities: Vec<TemplateCapability>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+}
+
+impl Iterator for TemplatePoller<'_> {
+    type Item = Result<BlockTemplate, jsonrpc::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.cancel.is_cancelled() {
+            let mut command = GetBlockTemplateCommand::new()
+                .set_rules(self.rules.clone())
+                .set_capabilities(self.capabilities.clone());
+            if let Some(longpollid) = &self.longpollid {
+                command = command.set_longpollid(longpollid.clone());
+            }
+            match command.call(self.client) {
+                Ok(GetBlockTemplateCommandResponse::Template(template)) => {
+                    self.longpollid = Some(template.longpollid.clone());
+                    return Some(Ok(*template));
+                }
+                Ok(_) => {
+                    return Some(Err(jsonrpc::Error::Json(serde_json::Error::custom(
+                        "getblocktemplate returned a proposal result instead of a template",
+                    ))))
+                }
+                Err(error) if is_timeout(&error) => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        None
+    }
+}
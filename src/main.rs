@@ -1 +1,1256 @@
-fn main() {}
+// bitcoind-request: every command of the crate from the command line.
+//
+// Unlike bitcoin-cli, arguments are checked before anything is sent to the node, and responses
+// are parsed into the crate's types, so a malformed argument or an unexpected response is
+// reported as such instead of as a node error.
+//
+//     bitcoind-request --chain regtest getblockstats 100 --stats height,totalfee
+//     bitcoind-request --format csv getpeerinfo
+use std::io::{self, Write};
+use std::process;
+use std::time::Duration;
+
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use jsonrpc::simple_http;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use bitcoind_request::client::{is_timeout, Client};
+use bitcoind_request::command::add_node::{AddNodeCommand, AddNodeCommandArg};
+use bitcoind_request::command::clear_banned::ClearBannedCommand;
+use bitcoind_request::command::create_multisig::{CreateMultisigCommand, MultisigAddressType};
+use bitcoind_request::command::decode_raw_transaction::DecodeRawTransactionCommand;
+use bitcoind_request::command::derive_addresses::{DeriveAddressesCommand, DescriptorRange};
+use bitcoind_request::command::disconnect_node::{DisconnectNodeCommand, DisconnectNodeTarget};
+use bitcoind_request::command::generate_block::GenerateBlockCommand;
+use bitcoind_request::command::generate_to_address::GenerateToAddressCommand;
+use bitcoind_request::command::generate_to_descriptor::GenerateToDescriptorCommand;
+use bitcoind_request::command::get_added_node_info::GetAddedNodeInfoCommand;
+use bitcoind_request::command::get_best_block_hash::GetBestBlockHashCommand;
+use bitcoind_request::command::get_block::{GetBlockCommand, GetBlockCommandVerbosity};
+use bitcoind_request::command::get_block_count::GetBlockCountCommand;
+use bitcoind_request::command::get_block_filter::{BlockFilterType, GetBlockFilterCommand};
+use bitcoind_request::command::get_block_hash::GetBlockHashCommand;
+use bitcoind_request::command::get_block_header::GetBlockHeaderCommand;
+use bitcoind_request::command::get_block_stats::{
+    GetBlockStatsCommand, StatsArgumentChoices, TargetBlockArgument,
+};
+use bitcoind_request::command::get_block_template::{
+    GetBlockTemplateCommand, TemplateCapability, TemplateRule,
+};
+use bitcoind_request::command::get_blockchain_info::GetBlockchainInfoCommand;
+use bitcoind_request::command::get_chain_tips::GetChainTipsCommand;
+use bitcoind_request::command::get_chain_tx_stats::GetChainTxStatsCommand;
+use bitcoind_request::command::get_connection_count::GetConnectionCountCommand;
+use bitcoind_request::command::get_deployment_info::GetDeploymentInfoCommand;
+use bitcoind_request::command::get_descriptor_info::GetDescriptorInfoCommand;
+use bitcoind_request::command::get_difficulty::GetDifficultyCommand;
+use bitcoind_request::command::get_index_info::{GetIndexInfoCommand, IndexName};
+use bitcoind_request::command::get_memory_info::{GetMemoryInfoCommand, GetMemoryInfoCommandMode};
+use bitcoind_request::command::get_mempool_entry::GetMempoolEntryCommand;
+use bitcoind_request::command::get_mempool_info::GetMempoolInfoCommand;
+use bitcoind_request::command::get_mining_info::GetMiningInfoCommand;
+use bitcoind_request::command::get_net_totals::GetNetTotalsCommand;
+use bitcoind_request::command::get_network_hash_ps::{
+    BlocksToIncludeArg, GetNetworkHashPsCommand, HeightArg,
+};
+use bitcoind_request::command::get_network_info::GetNetworkInfoCommand;
+use bitcoind_request::command::get_node_addresses::{
+    CountArg, GetNodeAddressesCommand, NetworkArg,
+};
+use bitcoind_request::command::get_peer_info::GetPeerInfoCommand;
+use bitcoind_request::command::get_prioritised_transactions::GetPrioritisedTransactionsCommand;
+use bitcoind_request::command::get_raw_mempool::GetRawMempoolCommand;
+use bitcoind_request::command::get_raw_transaction::GetRawTransactionCommand;
+use bitcoind_request::command::get_rpc_info::GetRpcInfoCommand;
+use bitcoind_request::command::get_tx_out::GetTxOutCommand;
+use bitcoind_request::command::get_tx_out_proof::GetTxOutProofCommand;
+use bitcoind_request::command::get_tx_out_set_info::{GetTxOutSetInfoCommand, HashType};
+use bitcoind_request::command::help::HelpCommand;
+use bitcoind_request::command::invalidate_block::InvalidateBlockCommand;
+use bitcoind_request::command::list_banned::ListBannedCommand;
+use bitcoind_request::command::logging::{LoggingCategory, LoggingCommand};
+use bitcoind_request::command::ping::PingCommand;
+use bitcoind_request::command::precious_block::PreciousBlockCommand;
+use bitcoind_request::command::prioritise_transaction::PrioritiseTransactionCommand;
+use bitcoind_request::command::prune_blockchain::{PruneBlockchainCommand, PruneBlockchainTarget};
+use bitcoind_request::command::reconsider_block::ReconsiderBlockCommand;
+use bitcoind_request::command::save_mempool::SaveMempoolCommand;
+use bitcoind_request::command::scan_tx_out_set::{
+    ScanObject, ScanTxOutSetAction, ScanTxOutSetCommand,
+};
+use bitcoind_request::command::set_ban::{BanDuration, SetBanCommand, SetBanCommandArg, Subnet};
+use bitcoind_request::command::set_network_active::SetNetworkActiveCommand;
+use bitcoind_request::command::sign_message_with_priv_key::SignMessageWithPrivKeyCommand;
+use bitcoind_request::command::stop::StopCommand;
+use bitcoind_request::command::submit_block::SubmitBlockCommand;
+use bitcoind_request::command::submit_header::SubmitHeaderCommand;
+use bitcoind_request::command::uptime::UptimeCommand;
+use bitcoind_request::command::validate_address::ValidateAddressCommand;
+use bitcoind_request::command::verify_chain::{
+    CheckLevel, VerifyChainBlocksArg, VerifyChainCommand,
+};
+use bitcoind_request::command::verify_message::VerifyMessageCommand;
+use bitcoind_request::command::verify_tx_out_proof::VerifyTxOutProofCommand;
+use bitcoind_request::command::wait_for_block::WaitForBlockCommand;
+use bitcoind_request::command::wait_for_block_height::WaitForBlockHeightCommand;
+use bitcoind_request::command::wait_for_new_block::WaitForNewBlockCommand;
+use bitcoind_request::command::CallableCommand;
+use bitcoind_request::config::{ConnectionArgs, ConnectionSettings};
+use bitcoind_request::Blockhash;
+
+#[derive(Parser)]
+#[command(
+    name = "bitcoind-request",
+    version,
+    about = "Send typed RPC commands to a bitcoind server",
+    // help is the node's help RPC, --help is this program's
+    disable_help_subcommand = true
+)]
+struct Cli {
+    #[command(flatten)]
+    connection: ConnectionArgs,
+    /// How to print the response
+    #[arg(long, value_enum, default_value_t = Format::Pretty, global = true)]
+    format: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Pretty, // indented JSON
+    Json,   // JSON on one line
+    Table,  // aligned columns
+    Csv,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AddNodeAction {
+    Add,
+    Remove,
+    Onetry,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BanAction {
+    Add,
+    Remove,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MemoryInfoMode {
+    Stats,
+    Mallocinfo,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AddressNetwork {
+    Ipv4,
+    Ipv6,
+    Onion,
+    I2p,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ScanAction {
+    Start,
+    Abort,
+    Status,
+}
+
+// Subcommands are named after the RPCs, and their arguments after the builder methods.
+// rename_all applies to the flags as well, so multiword flags are named explicitly.
+#[derive(Subcommand)]
+#[command(rename_all = "lower")]
+enum Command {
+    /// Add or remove a node from the addnode list, or try a connection to it once
+    AddNode {
+        node: String,
+        #[arg(value_enum)]
+        command: AddNodeAction,
+        #[arg(long)]
+        v2transport: Option<bool>,
+    },
+    /// Clear all banned IPs
+    ClearBanned,
+    /// Create a multi-signature address
+    CreateMultisig {
+        nrequired: u32,
+        /// Hex-encoded public keys
+        #[arg(required = true, value_parser = parse_public_key)]
+        keys: Vec<String>,
+        /// legacy, p2sh-segwit or bech32
+        #[arg(long = "address-type", value_parser = parse_name::<MultisigAddressType>)]
+        address_type: Option<MultisigAddressType>,
+    },
+    /// Decode a hex-encoded transaction
+    DecodeRawTransaction {
+        #[arg(value_parser = parse_hex)]
+        hexstring: String,
+        #[arg(long = "is-witness")]
+        is_witness: Option<bool>,
+    },
+    /// Derive addresses from a descriptor
+    DeriveAddresses {
+        descriptor: String,
+        /// END or BEGIN-END, for ranged descriptors
+        #[arg(long, value_parser = parse_descriptor_range)]
+        range: Option<DescriptorRange>,
+    },
+    /// Disconnect a peer by address or node id
+    #[command(group(clap::ArgGroup::new("target").required(true)))]
+    DisconnectNode {
+        #[arg(long, group = "target")]
+        address: Option<String>,
+        #[arg(long, group = "target")]
+        nodeid: Option<u64>,
+    },
+    /// Mine a block with the given transactions (regtest)
+    GenerateBlock {
+        /// An address or descriptor to send the coinbase to
+        output: String,
+        /// Txids of mempool transactions or hex-encoded raw transactions
+        transactions: Vec<String>,
+        #[arg(long)]
+        submit: Option<bool>,
+    },
+    /// Mine blocks to an address (regtest)
+    GenerateToAddress {
+        nblocks: u64,
+        address: String,
+        #[arg(long)]
+        maxtries: Option<u64>,
+    },
+    /// Mine blocks to a descriptor (regtest)
+    GenerateToDescriptor {
+        num_blocks: u64,
+        descriptor: String,
+        #[arg(long)]
+        maxtries: Option<u64>,
+    },
+    /// Information about added nodes
+    GetAddedNodeInfo { node: Option<String> },
+    /// The hash of the best block
+    GetBestBlockHash,
+    /// A block, as hex or an object
+    GetBlock {
+        #[arg(value_parser = parse_blockhash)]
+        blockhash: Blockhash,
+        /// 0 for hex, 1 for an object, 2 with transaction data, 3 with prevouts
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=3))]
+        verbosity: Option<u8>,
+    },
+    /// The height of the most-work chain
+    GetBlockCount,
+    /// A BIP158 block filter (requires -blockfilterindex)
+    GetBlockFilter {
+        #[arg(value_parser = parse_blockhash)]
+        blockhash: Blockhash,
+        #[arg(long, value_parser = parse_name::<BlockFilterType>)]
+        filtertype: Option<BlockFilterType>,
+    },
+    /// The hash of the block at a height
+    GetBlockHash { height: u64 },
+    /// A block header, as hex or an object
+    GetBlockHeader {
+        #[arg(value_parser = parse_blockhash)]
+        blockhash: Blockhash,
+        /// false for hex
+        #[arg(long)]
+        verbose: Option<bool>,
+    },
+    /// Per-block statistics
+    GetBlockStats {
+        /// A block height or hash
+        #[arg(value_parser = parse_target_block)]
+        hash_or_height: String,
+        /// Only these stats, comma separated
+        #[arg(long, value_delimiter = ',', value_parser = parse_name::<StatsArgumentChoices>)]
+        stats: Vec<StatsArgumentChoices>,
+    },
+    /// A block template to mine on, or the validity of a block proposal
+    GetBlockTemplate {
+        #[arg(long, value_delimiter = ',', value_parser = parse_name::<TemplateRule>)]
+        rules: Vec<TemplateRule>,
+        #[arg(long, value_delimiter = ',', value_parser = parse_name::<TemplateCapability>)]
+        capabilities: Vec<TemplateCapability>,
+        #[arg(long)]
+        longpollid: Option<String>,
+        /// A hex-encoded block to check instead of getting a template
+        #[arg(long, value_parser = parse_hex)]
+        proposal: Option<String>,
+    },
+    /// The state of the blockchain
+    GetBlockchainInfo,
+    /// The tips of all known chains, including forks
+    GetChainTips,
+    /// Statistics about the number and rate of transactions
+    GetChainTxStats {
+        #[arg(long = "n-blocks")]
+        n_blocks: Option<u64>,
+        #[arg(long, value_parser = parse_blockhash)]
+        blockhash: Option<Blockhash>,
+    },
+    /// The number of connected peers
+    GetConnectionCount,
+    /// The state of consensus deployments (v23 and later)
+    GetDeploymentInfo {
+        #[arg(value_parser = parse_blockhash)]
+        blockhash: Option<Blockhash>,
+    },
+    /// Analyse a descriptor
+    GetDescriptorInfo { descriptor: String },
+    /// The difficulty of the tip, as a multiple of the minimum
+    GetDifficulty,
+    /// The status of the node's indexes
+    GetIndexInfo {
+        #[arg(value_parser = parse_name::<IndexName>)]
+        index_name: Option<IndexName>,
+    },
+    /// The memory usage of the node
+    GetMemoryInfo {
+        #[arg(long, value_enum)]
+        mode: Option<MemoryInfoMode>,
+    },
+    /// A mempool transaction
+    GetMempoolEntry {
+        #[arg(value_parser = parse_txid)]
+        txid: String,
+    },
+    /// The state of the mempool
+    GetMempoolInfo,
+    /// Mining related information
+    GetMiningInfo,
+    /// Network traffic statistics
+    GetNetTotals,
+    /// The estimated network hashes per second
+    GetNetworkHashPs {
+        /// Blocks to average over, -1 for those since the last difficulty change
+        #[arg(long = "n-blocks", allow_negative_numbers = true, value_parser = clap::value_parser!(i64).range(-1..))]
+        n_blocks: Option<i64>,
+        /// The height to estimate at, -1 for the tip
+        #[arg(long, allow_negative_numbers = true, value_parser = clap::value_parser!(i64).range(-1..))]
+        height: Option<i64>,
+    },
+    /// The state of the P2P network
+    GetNetworkInfo,
+    /// Addresses known to the node
+    GetNodeAddresses {
+        /// 0 for all of them
+        #[arg(long)]
+        count: Option<u64>,
+        #[arg(long, value_enum)]
+        network: Option<AddressNetwork>,
+    },
+    /// The connected peers
+    GetPeerInfo,
+    /// Fee deltas set with prioritisetransaction (v27 and later)
+    GetPrioritisedTransactions,
+    /// The txids of the mempool transactions, or the transactions with --verbose true
+    GetRawMempool {
+        #[arg(long)]
+        verbose: Option<bool>,
+        #[arg(long = "mempool-sequence")]
+        mempool_sequence: Option<bool>,
+    },
+    /// A transaction, as hex or an object (requires -txindex unless in the mempool or a given block)
+    GetRawTransaction {
+        #[arg(value_parser = parse_txid)]
+        txid: String,
+        #[arg(long)]
+        verbose: Option<bool>,
+        /// The block to look for the transaction in
+        #[arg(long, value_parser = parse_blockhash)]
+        blockhash: Option<Blockhash>,
+    },
+    /// The state of the RPC server
+    GetRpcInfo,
+    /// An unspent transaction output
+    GetTxOut {
+        #[arg(value_parser = parse_txid)]
+        txid: String,
+        n: u64,
+        #[arg(long = "include-mempool")]
+        include_mempool: Option<bool>,
+    },
+    /// A proof that transactions are included in a block
+    GetTxOutProof {
+        #[arg(required = true, value_parser = parse_txid)]
+        txids: Vec<String>,
+        #[arg(long, value_parser = parse_blockhash)]
+        blockhash: Option<Blockhash>,
+    },
+    /// Statistics about the UTXO set
+    GetTxOutSetInfo {
+        /// hash_serialized_2, hash_serialized_3, muhash or none
+        #[arg(long = "hash-type", value_parser = parse_name::<HashType>)]
+        hash_type: Option<HashType>,
+        /// A block height or hash (requires -coinstatsindex)
+        #[arg(long = "hash-or-height", value_parser = parse_target_block)]
+        hash_or_height: Option<String>,
+        #[arg(long = "use-index")]
+        use_index: Option<bool>,
+    },
+    /// The node's help for all commands or one of them
+    Help { command: Option<String> },
+    /// Mark a block and its descendants invalid
+    InvalidateBlock {
+        #[arg(value_parser = parse_blockhash)]
+        blockhash: Blockhash,
+    },
+    /// The banned IPs and subnets
+    ListBanned,
+    /// Get or set the logging categories
+    Logging {
+        #[arg(long, value_delimiter = ',', value_parser = parse_name::<LoggingCategory>)]
+        include: Vec<LoggingCategory>,
+        #[arg(long, value_delimiter = ',', value_parser = parse_name::<LoggingCategory>)]
+        exclude: Vec<LoggingCategory>,
+    },
+    /// Ask peers to measure ping time
+    Ping,
+    /// Treat a block as if it was received before others with the same work
+    PreciousBlock {
+        #[arg(value_parser = parse_blockhash)]
+        blockhash: Blockhash,
+    },
+    /// Change the fee a mempool transaction is mined with, in satoshis
+    PrioritiseTransaction {
+        #[arg(value_parser = parse_txid)]
+        txid: String,
+        #[arg(allow_negative_numbers = true)]
+        fee_delta: i64,
+    },
+    /// Prune blocks up to a height, or up to a UNIX time with --time
+    PruneBlockchain {
+        height: u64,
+        #[arg(long)]
+        time: bool,
+    },
+    /// Remove invalidity status of a block and its ancestors
+    ReconsiderBlock {
+        #[arg(value_parser = parse_blockhash)]
+        blockhash: Blockhash,
+    },
+    /// Dump the mempool to disk
+    SaveMempool,
+    /// Scan the UTXO set for outputs matching descriptors
+    ScanTxOutSet {
+        #[arg(value_enum)]
+        action: ScanAction,
+        /// The descriptors to scan for (start only)
+        descriptors: Vec<String>,
+        /// END or BEGIN-END, for ranged descriptors
+        #[arg(long, value_parser = parse_descriptor_range)]
+        range: Option<DescriptorRange>,
+    },
+    /// Ban or unban an IP, subnet or host
+    SetBan {
+        subnet: Subnet,
+        #[arg(value_enum)]
+        command: BanAction,
+        /// Seconds to ban for
+        #[arg(long, conflicts_with = "until")]
+        bantime: Option<u64>,
+        /// UNIX time to ban until
+        #[arg(long)]
+        until: Option<u64>,
+    },
+    /// Enable or disable all P2P network activity
+    SetNetworkActive {
+        #[arg(action = clap::ArgAction::Set)]
+        state: bool,
+    },
+    /// Sign a message with a private key
+    SignMessageWithPrivKey { privkey: String, message: String },
+    /// Stop the node
+    Stop,
+    /// Submit a hex-encoded block
+    SubmitBlock {
+        #[arg(value_parser = parse_hex)]
+        hexdata: String,
+    },
+    /// Submit a hex-encoded block header
+    SubmitHeader {
+        #[arg(value_parser = parse_hex)]
+        hexdata: String,
+    },
+    /// Seconds since the node started
+    Uptime,
+    /// Check an address
+    ValidateAddress { address: String },
+    /// Verify the blockchain database
+    VerifyChain {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(0..=4))]
+        checklevel: Option<u8>,
+        /// 0 for all of them
+        #[arg(long)]
+        nblocks: Option<u64>,
+    },
+    /// Verify a signed message
+    VerifyMessage {
+        address: String,
+        signature: String,
+        message: String,
+    },
+    /// The txids a proof commits to, if its block is in the best chain
+    VerifyTxOutProof {
+        #[arg(value_parser = parse_hex)]
+        proof: String,
+    },
+    /// Wait for a block to be the tip (--timeout-ms 0 waits forever)
+    WaitForBlock {
+        #[arg(value_parser = parse_blockhash)]
+        blockhash: Blockhash,
+        #[arg(long = "timeout-ms")]
+        timeout_ms: Option<u64>,
+    },
+    /// Wait for the tip to reach a height (--timeout-ms 0 waits forever)
+    WaitForBlockHeight {
+        height: u64,
+        #[arg(long = "timeout-ms")]
+        timeout_ms: Option<u64>,
+    },
+    /// Wait for a new tip (--timeout-ms 0 waits forever)
+    WaitForNewBlock {
+        #[arg(long = "timeout-ms")]
+        timeout_ms: Option<u64>,
+    },
+}
+
+fn is_hex(s: &str) -> bool {
+    s.len().is_multiple_of(2) && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn parse_hex(s: &str) -> Result<String, String> {
+    if !is_hex(s) {
+        return Err("expected hex data".to_string());
+    }
+    Ok(s.to_string())
+}
+
+fn parse_hash(s: &str) -> Result<String, String> {
+    if s.len() != 64 || !is_hex(s) {
+        return Err("expected 64 hex characters".to_string());
+    }
+    Ok(s.to_string())
+}
+
+fn parse_blockhash(s: &str) -> Result<Blockhash, String> {
+    Ok(Blockhash(parse_hash(s)?))
+}
+
+fn parse_txid(s: &str) -> Result<String, String> {
+    parse_hash(s)
+}
+
+// Compressed (33 bytes) or uncompressed (65 bytes).
+fn parse_public_key(s: &str) -> Result<String, String> {
+    if !is_hex(s) || (s.len() != 66 && s.len() != 130) {
+        return Err("expected a hex-encoded public key of 33 or 65 bytes".to_string());
+    }
+    Ok(s.to_string())
+}
+
+// Heights are kept as strings until the command is built, as TargetBlockArgument can't be
+// cloned the way clap needs.
+fn parse_target_block(s: &str) -> Result<String, String> {
+    if s.chars().all(|c| c.is_ascii_digit()) {
+        s.parse::<u64>().map_err(|error| error.to_string())?;
+        return Ok(s.to_string());
+    }
+    parse_hash(s).map_err(|_| "expected a block height or a 64 character block hash".to_string())
+}
+
+fn target_block(hash_or_height: &str) -> TargetBlockArgument {
+    match hash_or_height.parse() {
+        Ok(height) => TargetBlockArgument::Height(height),
+        Err(_) => TargetBlockArgument::Hash(Blockhash(hash_or_height.to_string())),
+    }
+}
+
+fn parse_descriptor_range(s: &str) -> Result<DescriptorRange, String> {
+    let parse_index = |index: &str| {
+        index
+            .parse::<u64>()
+            .map_err(|_| format!("invalid range index: {}", index))
+    };
+    match s.split_once('-') {
+        Some((begin, end)) => {
+            let (begin, end) = (parse_index(begin)?, parse_index(end)?);
+            if begin > end {
+                return Err(format!("range begins after it ends: {}", s));
+            }
+            Ok(DescriptorRange::Range(begin, end))
+        }
+        None => Ok(DescriptorRange::End(parse_index(s)?)),
+    }
+}
+
+// Arguments named the way the node names them, parsed with the type's Deserialize.
+fn parse_name<T: DeserializeOwned>(s: &str) -> Result<T, String> {
+    serde_json::from_value(Value::String(s.to_string())).map_err(|error| error.to_string())
+}
+
+fn call<C: CallableCommand>(command: C, client: &Client) -> Result<Value, jsonrpc::Error>
+where
+    C::Response: Serialize,
+{
+    let response = command.call(client)?;
+    Ok(serde_json::to_value(response)?)
+}
+
+fn usage_error(message: String) -> ! {
+    Cli::command()
+        .error(ErrorKind::ValueValidation, message)
+        .exit()
+}
+
+fn run(command: Command, client: &Client) -> Result<Value, jsonrpc::Error> {
+    match command {
+        Command::AddNode {
+            node,
+            command,
+            v2transport,
+        } => {
+            let command = match command {
+                AddNodeAction::Add => AddNodeCommandArg::Add,
+                AddNodeAction::Remove => AddNodeCommandArg::Remove,
+                AddNodeAction::Onetry => AddNodeCommandArg::OneTry,
+            };
+            let mut add_node = AddNodeCommand::new(node, command);
+            if let Some(v2transport) = v2transport {
+                add_node = add_node.set_v2transport(v2transport);
+            }
+            call(add_node, client)
+        }
+        Command::ClearBanned => call(ClearBannedCommand::new(), client),
+        Command::CreateMultisig {
+            nrequired,
+            keys,
+            address_type,
+        } => {
+            if nrequired == 0 || nrequired as usize > keys.len() {
+                usage_error(format!(
+                    "nrequired must be between 1 and the number of keys ({})",
+                    keys.len()
+                ));
+            }
+            let mut create_multisig = CreateMultisigCommand::new(nrequired, keys);
+            if let Some(address_type) = address_type {
+                create_multisig = create_multisig.set_address_type(address_type);
+            }
+            call(create_multisig, client)
+        }
+        Command::DecodeRawTransaction {
+            hexstring,
+            is_witness,
+        } => {
+            let mut decode_raw_transaction = DecodeRawTransactionCommand::new(hexstring);
+            if let Some(is_witness) = is_witness {
+                decode_raw_transaction = decode_raw_transaction.set_is_witness(is_witness);
+            }
+            call(decode_raw_transaction, client)
+        }
+        Command::DeriveAddresses { descriptor, range } => {
+            let mut derive_addresses = DeriveAddressesCommand::new(descriptor);
+            if let Some(range) = range {
+                derive_addresses = derive_addresses.set_range(range);
+            }
+            call(derive_addresses, client)
+        }
+        Command::DisconnectNode { address, nodeid } => {
+            let target = match (address, nodeid) {
+                (Some(address), _) => DisconnectNodeTarget::Address(address),
+                (None, Some(nodeid)) => DisconnectNodeTarget::NodeId(nodeid),
+                (None, None) => usage_error("an --address or --nodeid is required".to_string()),
+            };
+            call(DisconnectNodeCommand::new(target), client)
+        }
+        Command::GenerateBlock {
+            output,
+            transactions,
+            submit,
+        } => {
+            let mut generate_block = GenerateBlockCommand::new(output, transactions);
+            if let Some(submit) = submit {
+                generate_block = generate_block.set_submit(submit);
+            }
+            call(generate_block, client)
+        }
+        Command::GenerateToAddress {
+            nblocks,
+            address,
+            maxtries,
+        } => {
+            let mut generate_to_address = GenerateToAddressCommand::new(nblocks, address);
+            if let Some(maxtries) = maxtries {
+                generate_to_address = generate_to_address.set_maxtries(maxtries);
+            }
+            call(generate_to_address, client)
+        }
+        Command::GenerateToDescriptor {
+            num_blocks,
+            descriptor,
+            maxtries,
+        } => {
+            let mut generate_to_descriptor =
+                GenerateToDescriptorCommand::new(num_blocks, descriptor);
+            if let Some(maxtries) = maxtries {
+                generate_to_descriptor = generate_to_descriptor.set_maxtries(maxtries);
+            }
+            call(generate_to_descriptor, client)
+        }
+        Command::GetAddedNodeInfo { node } => {
+            let mut get_added_node_info = GetAddedNodeInfoCommand::new();
+            if let Some(node) = node {
+                get_added_node_info = get_added_node_info.set_node(node);
+            }
+            call(get_added_node_info, client)
+        }
+        Command::GetBestBlockHash => call(GetBestBlockHashCommand::default(), client),
+        Command::GetBlock {
+            blockhash,
+            verbosity,
+        } => {
+            let mut get_block = GetBlockCommand::new(blockhash);
+            if let Some(verbosity) = verbosity {
+                let verbosity = match verbosity {
+                    0 => GetBlockCommandVerbosity::SerializedHexEncodedData,
+                    1 => GetBlockCommandVerbosity::BlockObjectWithoutTransactionInformation,
+                    2 => GetBlockCommandVerbosity::BlockObjectWithTransactionInformation,
+                    _ => GetBlockCommandVerbosity::BlockObjectWithTransactionInformationAndPrevoutInformation,
+                };
+                get_block = get_block.verbosity(verbosity);
+            }
+            call(get_block, client)
+        }
+        Command::GetBlockCount => call(GetBlockCountCommand::new(), client),
+        Command::GetBlockFilter {
+            blockhash,
+            filtertype,
+        } => {
+            let mut get_block_filter = GetBlockFilterCommand::new(blockhash);
+            if let Some(filtertype) = filtertype {
+                get_block_filter = get_block_filter.set_filtertype(filtertype);
+            }
+            call(get_block_filter, client)
+        }
+        Command::GetBlockHash { height } => call(GetBlockHashCommand::new(height), client),
+        Command::GetBlockHeader { blockhash, verbose } => {
+            let mut get_block_header = GetBlockHeaderCommand::new(blockhash);
+            if let Some(verbose) = verbose {
+                get_block_header.verbose(verbose);
+            }
+            call(get_block_header, client)
+        }
+        Command::GetBlockStats {
+            hash_or_height,
+            stats,
+        } => {
+            let get_block_stats = GetBlockStatsCommand::new(target_block(&hash_or_height));
+            if stats.is_empty() {
+                call(get_block_stats, client)
+            } else {
+                call(get_block_stats.select(stats), client)
+            }
+        }
+        Command::GetBlockTemplate {
+            rules,
+            capabilities,
+            longpollid,
+            proposal,
+        } => {
+            let mut get_block_template = GetBlockTemplateCommand::new();
+            if !rules.is_empty() {
+                get_block_template = get_block_template.set_rules(rules);
+            }
+            if !capabilities.is_empty() {
+                get_block_template = get_block_template.set_capabilities(capabilities);
+            }
+            if let Some(longpollid) = longpollid {
+                get_block_template = get_block_template.set_longpollid(longpollid);
+            }
+            if let Some(proposal) = proposal {
+                get_block_template = get_block_template.set_proposal(proposal);
+            }
+            call(get_block_template, client)
+        }
+        Command::GetBlockchainInfo => call(GetBlockchainInfoCommand::new(), client),
+        Command::GetChainTips => call(GetChainTipsCommand::new(), client),
+        Command::GetChainTxStats {
+            n_blocks,
+            blockhash,
+        } => {
+            let mut get_chain_tx_stats = GetChainTxStatsCommand::new();
+            if let Some(n_blocks) = n_blocks {
+                get_chain_tx_stats = get_chain_tx_stats.set_n_blocks(n_blocks);
+            }
+            if let Some(blockhash) = blockhash {
+                get_chain_tx_stats = get_chain_tx_stats.set_blockhash(blockhash);
+            }
+            call(get_chain_tx_stats, client)
+        }
+        Command::GetConnectionCount => call(GetConnectionCountCommand::new(), client),
+        Command::GetDeploymentInfo { blockhash } => {
+            let mut get_deployment_info = GetDeploymentInfoCommand::new();
+            if let Some(blockhash) = blockhash {
+                get_deployment_info = get_deployment_info.set_blockhash(blockhash);
+            }
+            call(get_deployment_info, client)
+        }
+        Command::GetDescriptorInfo { descriptor } => {
+            call(GetDescriptorInfoCommand::new(descriptor), client)
+        }
+        Command::GetDifficulty => call(GetDifficultyCommand::new(), client),
+        Command::GetIndexInfo { index_name } => {
+            let mut get_index_info = GetIndexInfoCommand::new();
+            if let Some(index_name) = index_name {
+                get_index_info = get_index_info.set_index_name(index_name);
+            }
+            call(get_index_info, client)
+        }
+        Command::GetMemoryInfo { mode } => {
+            let mut get_memory_info = GetMemoryInfoCommand::new();
+            if let Some(mode) = mode {
+                let mode = match mode {
+                    MemoryInfoMode::Stats => GetMemoryInfoCommandMode::Stats,
+                    MemoryInfoMode::Mallocinfo => GetMemoryInfoCommandMode::MallocInfo,
+                };
+                get_memory_info = get_memory_info.set_mode(mode);
+            }
+            call(get_memory_info, client)
+        }
+        Command::GetMempoolEntry { txid } => call(GetMempoolEntryCommand::new(txid), client),
+        Command::GetMempoolInfo => call(GetMempoolInfoCommand::new(), client),
+        Command::GetMiningInfo => call(GetMiningInfoCommand::new(), client),
+        Command::GetNetTotals => call(GetNetTotalsCommand::new(), client),
+        Command::GetNetworkHashPs { n_blocks, height } => {
+            let mut get_network_hash_ps = GetNetworkHashPsCommand::new();
+            if let Some(n_blocks) = n_blocks {
+                let n_blocks = match n_blocks {
+                    -1 => BlocksToIncludeArg::BlocksSinceLastDifficultyChange,
+                    n_blocks => BlocksToIncludeArg::NBlocks(n_blocks as u64),
+                };
+                get_network_hash_ps = get_network_hash_ps.set_n_blocks(n_blocks);
+            }
+            if let Some(height) = height {
+                let height = match height {
+                    -1 => HeightArg::CurrentHeight,
+                    height => HeightArg::Height(height as u64),
+                };
+                get_network_hash_ps = get_network_hash_ps.set_height(height);
+            }
+            call(get_network_hash_ps, client)
+        }
+        Command::GetNetworkInfo => call(GetNetworkInfoCommand::new(), client),
+        Command::GetNodeAddresses { count, network } => {
+            let mut get_node_addresses = GetNodeAddressesCommand::new();
+            if let Some(count) = count {
+                let count = match count {
+                    0 => CountArg::AllAddresses,
+                    count => CountArg::MaxAddresses(count),
+                };
+                get_node_addresses = get_node_addresses.set_count(count);
+            }
+            let network = match network {
+                None => NetworkArg::All,
+                Some(AddressNetwork::Ipv4) => NetworkArg::Ipv4,
+                Some(AddressNetwork::Ipv6) => NetworkArg::Ipv6,
+                Some(AddressNetwork::Onion) => NetworkArg::Onion,
+                Some(AddressNetwork::I2p) => NetworkArg::I2p,
+            };
+            call(get_node_addresses.set_network(network), client)
+        }
+        Command::GetPeerInfo => call(GetPeerInfoCommand::new(), client),
+        Command::GetPrioritisedTransactions => {
+            call(GetPrioritisedTransactionsCommand::new(), client)
+        }
+        Command::GetRawMempool {
+            verbose,
+            mempool_sequence,
+        } => {
+            let mut get_raw_mempool = GetRawMempoolCommand::new();
+            if let Some(verbose) = verbose {
+                get_raw_mempool = get_raw_mempool.set_verbose(verbose);
+            }
+            if let Some(mempool_sequence) = mempool_sequence {
+                get_raw_mempool = get_raw_mempool.set_mempool_sequence(mempool_sequence);
+            }
+            call(get_raw_mempool, client)
+        }
+        Command::GetRawTransaction {
+            txid,
+            verbose,
+            blockhash,
+        } => {
+            let mut get_raw_transaction = GetRawTransactionCommand::new(txid);
+            if let Some(verbose) = verbose {
+                get_raw_transaction.verbose(verbose);
+            }
+            if let Some(blockhash) = blockhash {
+                get_raw_transaction.blockhash(blockhash);
+            }
+            call(get_raw_transaction, client)
+        }
+        Command::GetRpcInfo => call(GetRpcInfoCommand::new(), client),
+        Command::GetTxOut {
+            txid,
+            n,
+            include_mempool,
+        } => {
+            let mut get_tx_out = GetTxOutCommand::new(txid, n);
+            if let Some(include_mempool) = include_mempool {
+                get_tx_out.include_mempool(include_mempool);
+            }
+            call(get_tx_out, client)
+        }
+        Command::GetTxOutProof { txids, blockhash } => {
+            let mut get_tx_out_proof = GetTxOutProofCommand::new(txids);
+            if let Some(blockhash) = blockhash {
+                get_tx_out_proof = get_tx_out_proof.set_blockhash(blockhash);
+            }
+            call(get_tx_out_proof, client)
+        }
+        Command::GetTxOutSetInfo {
+            hash_type,
+            hash_or_height,
+            use_index,
+        } => {
+            let mut get_tx_out_set_info = GetTxOutSetInfoCommand::new();
+            if let Some(hash_type) = hash_type {
+                get_tx_out_set_info = get_tx_out_set_info.set_hash_type(hash_type);
+            }
+            if let Some(hash_or_height) = hash_or_height {
+                get_tx_out_set_info =
+                    get_tx_out_set_info.set_hash_or_height(target_block(&hash_or_height));
+            }
+            if let Some(use_index) = use_index {
+                get_tx_out_set_info = get_tx_out_set_info.set_use_index(use_index);
+            }
+            call(get_tx_out_set_info, client)
+        }
+        Command::Help { command } => {
+            let mut help = HelpCommand::new();
+            if let Some(command) = command {
+                help = help.set_command(command);
+            }
+            call(help, client)
+        }
+        Command::InvalidateBlock { blockhash } => {
+            call(InvalidateBlockCommand::new(blockhash), client)
+        }
+        Command::ListBanned => call(ListBannedCommand::new(), client),
+        Command::Logging { include, exclude } => {
+            let mut logging = LoggingCommand::new();
+            if !include.is_empty() {
+                logging = logging.set_include(include);
+            }
+            if !exclude.is_empty() {
+                logging = logging.set_exclude(exclude);
+            }
+            call(logging, client)
+        }
+        Command::Ping => call(PingCommand::new(), client),
+        Command::PreciousBlock { blockhash } => call(PreciousBlockCommand::new(blockhash), client),
+        Command::PrioritiseTransaction { txid, fee_delta } => {
+            call(PrioritiseTransactionCommand::new(txid, fee_delta), client)
+        }
+        Command::PruneBlockchain { height, time } => {
+            let target = match time {
+                true => PruneBlockchainTarget::Time(height),
+                false => PruneBlockchainTarget::Height(height),
+            };
+            call(PruneBlockchainCommand::new(target), client)
+        }
+        Command::ReconsiderBlock { blockhash } => {
+            call(ReconsiderBlockCommand::new(blockhash), client)
+        }
+        Command::SaveMempool => call(SaveMempoolCommand::new(), client),
+        Command::ScanTxOutSet {
+            action,
+            descriptors,
+            range,
+        } => {
+            let action = match action {
+                ScanAction::Start if descriptors.is_empty() => {
+                    usage_error("start needs at least one descriptor".to_string())
+                }
+                ScanAction::Start => {
+                    let scan_objects = descriptors
+                        .into_iter()
+                        .map(|desc| match range {
+                            Some(range) if desc.contains('*') => {
+                                ScanObject::RangedDescriptor { desc, range }
+                            }
+                            _ => ScanObject::Descriptor(desc),
+                        })
+                        .collect();
+                    ScanTxOutSetAction::Start(scan_objects)
+                }
+                _ if !descriptors.is_empty() => {
+                    usage_error("descriptors are only used with start".to_string())
+                }
+                ScanAction::Abort => ScanTxOutSetAction::Abort,
+                ScanAction::Status => ScanTxOutSetAction::Status,
+            };
+            call(ScanTxOutSetCommand::new(action), client)
+        }
+        Command::SetBan {
+            subnet,
+            command,
+            bantime,
+            until,
+        } => {
+            let command = match command {
+                BanAction::Add => SetBanCommandArg::Add,
+                BanAction::Remove => SetBanCommandArg::Remove,
+            };
+            let ban_duration = match (bantime, until) {
+                (Some(bantime), _) => BanDuration::For(Duration::from_secs(bantime)),
+                (None, Some(until)) => BanDuration::Until(until),
+                (None, None) => BanDuration::Default,
+            };
+            call(
+                SetBanCommand::new(subnet, command).set_ban_duration(ban_duration),
+                client,
+            )
+        }
+        Command::SetNetworkActive { state } => call(SetNetworkActiveCommand::new(state), client),
+        Command::SignMessageWithPrivKey { privkey, message } => {
+            call(SignMessageWithPrivKeyCommand::new(privkey, message), client)
+        }
+        Command::Stop => call(StopCommand::new(), client),
+        Command::SubmitBlock { hexdata } => call(SubmitBlockCommand::new(hexdata), client),
+        Command::SubmitHeader { hexdata } => call(SubmitHeaderCommand::new(hexdata), client),
+        Command::Uptime => call(UptimeCommand::new(), client),
+        Command::ValidateAddress { address } => call(ValidateAddressCommand::new(address), client),
+        Command::VerifyChain {
+            checklevel,
+            nblocks,
+        } => {
+            let mut verify_chain = VerifyChainCommand::new();
+            if let Some(checklevel) = checklevel {
+                let checklevel = match checklevel {
+                    0 => CheckLevel::ReadBlocks,
+                    1 => CheckLevel::BlockValidity,
+                    2 => CheckLevel::UndoData,
+                    3 => CheckLevel::DisconnectTipBlocks,
+                    _ => CheckLevel::ReconnectBlocks,
+                };
+                verify_chain = verify_chain.set_checklevel(checklevel);
+            }
+            if let Some(nblocks) = nblocks {
+                let nblocks = match nblocks {
+                    0 => VerifyChainBlocksArg::All,
+                    nblocks => VerifyChainBlocksArg::NBlocks(nblocks),
+                };
+                verify_chain = verify_chain.set_nblocks(nblocks);
+            }
+            call(verify_chain, client)
+        }
+        Command::VerifyMessage {
+            address,
+            signature,
+            message,
+        } => call(
+            VerifyMessageCommand::new(address, signature, message),
+            client,
+        ),
+        Command::VerifyTxOutProof { proof } => call(VerifyTxOutProofCommand::new(proof), client),
+        Command::WaitForBlock {
+            blockhash,
+            timeout_ms,
+        } => {
+            let mut wait_for_block = WaitForBlockCommand::new(blockhash);
+            if let Some(timeout_ms) = timeout_ms {
+                wait_for_block = wait_for_block.set_timeout(Duration::from_millis(timeout_ms));
+            }
+            call(wait_for_block, client)
+        }
+        Command::WaitForBlockHeight { height, timeout_ms } => {
+            let mut wait_for_block_height = WaitForBlockHeightCommand::new(height);
+            if let Some(timeout_ms) = timeout_ms {
+                wait_for_block_height =
+                    wait_for_block_height.set_timeout(Duration::from_millis(timeout_ms));
+            }
+            call(wait_for_block_height, client)
+        }
+        Command::WaitForNewBlock { timeout_ms } => {
+            let mut wait_for_new_block = WaitForNewBlockCommand::new();
+            if let Some(timeout_ms) = timeout_ms {
+                wait_for_new_block =
+                    wait_for_new_block.set_timeout(Duration::from_millis(timeout_ms));
+            }
+            call(wait_for_new_block, client)
+        }
+    }
+}
+
+// A cell of a table or CSV row: strings without quotes, nested values as JSON.
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+// The response as a header and rows: a row per element of a list of objects, a key/value row
+// per field of an object, or a single column otherwise.
+fn rows(value: &Value) -> (Vec<String>, Vec<Vec<String>>) {
+    match value {
+        Value::Array(elements) if elements.iter().all(Value::is_object) && !elements.is_empty() => {
+            let mut header: Vec<String> = vec![];
+            for element in elements {
+                for key in element.as_object().unwrap().keys() {
+                    if !header.contains(key) {
+                        header.push(key.clone());
+                    }
+                }
+            }
+            let rows = elements
+                .iter()
+                .map(|element| {
+                    header
+                        .iter()
+                        .map(|key| element.get(key).map(cell).unwrap_or_default())
+                        .collect()
+                })
+                .collect();
+            (header, rows)
+        }
+        Value::Array(elements) => (
+            vec!["value".to_string()],
+            elements.iter().map(|element| vec![cell(element)]).collect(),
+        ),
+        Value::Object(fields) => (
+            vec!["key".to_string(), "value".to_string()],
+            fields
+                .iter()
+                .map(|(key, value)| vec![key.clone(), cell(value)])
+                .collect(),
+        ),
+        value => (vec!["value".to_string()], vec![vec![cell(value)]]),
+    }
+}
+
+fn table(value: &Value) -> String {
+    let (header, rows) = rows(value);
+    let mut widths: Vec<usize> = header.iter().map(|column| column.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |row: &[String]| {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        cells.join("  ").trim_end().to_string()
+    };
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    let mut lines = vec![line(&header), separator.join("  ")];
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv(value: &Value) -> String {
+    let (header, rows) = rows(value);
+    let line = |row: &[String]| {
+        row.iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<String>>()
+            .join(",")
+    };
+    let mut lines = vec![line(&header)];
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.join("\n")
+}
+
+fn format(value: &Value, format: Format) -> String {
+    match format {
+        Format::Json => value.to_string(),
+        // A bare string, like a block hash or hex data, is printed as is, as bitcoin-cli does.
+        _ if value.is_string() => cell(value),
+        Format::Pretty => serde_json::to_string_pretty(value).unwrap(),
+        Format::Table => table(value),
+        Format::Csv => csv(value),
+    }
+}
+
+// What went wrong, in terms of what to check.
+fn describe_error(error: &jsonrpc::Error, settings: &ConnectionSettings, timeout: u64) -> String {
+    if is_timeout(error) {
+        return format!(
+            "no response from {} within {}s (raise it with --timeout)",
+            settings.url(),
+            timeout
+        );
+    }
+    match error {
+        jsonrpc::Error::Rpc(error) => format!("{} (code {})", error.message, error.code),
+        jsonrpc::Error::Json(error) => format!(
+            "the node's response doesn't have the expected shape: {}",
+            error
+        ),
+        jsonrpc::Error::Transport(transport_error) => {
+            match transport_error.downcast_ref::<simple_http::Error>() {
+                Some(simple_http::Error::SocketError(error)) => format!(
+                    "couldn't connect to {}: {} (is bitcoind running, and are --chain and --rpcport right?)",
+                    settings.url(),
+                    error
+                ),
+                Some(simple_http::Error::HttpErrorCode(401)) => format!(
+                    "{} rejected the credentials (check --rpcuser and --rpcpassword, or the cookie file)",
+                    settings.url()
+                ),
+                _ => error.to_string(),
+            }
+        }
+        error => error.to_string(),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let settings = match cli.connection.resolve() {
+        Ok(settings) => settings,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    };
+    let timeout = cli.connection.timeout;
+    let client = match settings.connect(cli.connection.timeout()) {
+        Ok(client) => client,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    };
+    match run(cli.command, &client) {
+        Ok(value) => {
+            let mut stdout = io::stdout().lock();
+            // Don't panic when piped into something like head.
+            let _ = writeln!(stdout, "{}", format(&value, cli.format));
+        }
+        Err(error) => {
+            eprintln!("error: {}", describe_error(&error, &settings, timeout));
+            process::exit(1);
+        }
+    }
+}
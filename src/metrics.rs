@@ -0,0 +1,531 @@
+// Prometheus metrics for a node, in the text exposition format:
+//
+//     let exporter = Exporter::new(&client).set_collectors(vec![Collector::Chain, Collector::Mempool]);
+//     print!("{}", exporter.render());
+//
+// or served on /metrics with Exporter::serve. The metrics are collected when they are scraped,
+// each collector making its own RPC calls. A collector that fails is reported with
+// bitcoind_collector_up 0 and its metrics are left out, rather than failing the whole scrape.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::net::TcpListener;
+use std::str::FromStr;
+use std::time::Instant;
+
+use crate::client::Client;
+use crate::command::get_blockchain_info::GetBlockchainInfoCommand;
+use crate::command::get_chain_tips::GetChainTipsCommand;
+use crate::command::get_mempool_info::GetMempoolInfoCommand;
+use crate::command::get_mining_info::GetMiningInfoCommand;
+use crate::command::get_net_totals::GetNetTotalsCommand;
+use crate::command::get_network_info::GetNetworkInfoCommand;
+use crate::command::get_peer_info::GetPeerInfoCommand;
+use crate::command::CallableCommand;
+use crate::http::{self, HttpResponse};
+
+pub const DEFAULT_NAMESPACE: &str = "bitcoind";
+// The content type of the text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const METRICS_PATH: &str = "/metrics";
+// The label value for peers of nodes that don't report a connection type or network (before
+// bitcoin core 0.21).
+const UNKNOWN_LABEL: &str = "unknown";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Collector {
+    Chain,     // getblockchaininfo: height, headers lag, verification progress
+    Mempool,   // getmempoolinfo: transactions, bytes, memory usage, minimum fee
+    Peers,     // getpeerinfo: peers by connection type and network
+    Network,   // getnetworkinfo and getnettotals: connections, bytes sent and received
+    Mining,    // getmininginfo: hashrate and difficulty
+    ChainTips, // getchaintips: tips by status, stale tips
+}
+
+impl Collector {
+    pub const ALL: [Collector; 6] = [
+        Collector::Chain,
+        Collector::Mempool,
+        Collector::Peers,
+        Collector::Network,
+        Collector::Mining,
+        Collector::ChainTips,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Collector::Chain => "chain",
+            Collector::Mempool => "mempool",
+            Collector::Peers => "peers",
+            Collector::Network => "network",
+            Collector::Mining => "mining",
+            Collector::ChainTips => "chaintips",
+        }
+    }
+}
+
+impl fmt::Display for Collector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Collector {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Collector::ALL
+            .into_iter()
+            .find(|collector| collector.as_str() == s)
+            .ok_or_else(|| format!("unknown collector: {}", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricType {
+    Gauge,
+    Counter,
+}
+
+impl fmt::Display for MetricType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MetricType::Gauge => write!(f, "gauge"),
+            MetricType::Counter => write!(f, "counter"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub labels: Vec<(String, String)>,
+    pub value: f64,
+}
+
+// A metric and its samples, one per set of label values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricFamily {
+    pub name: String,
+    pub help: String,
+    pub metric_type: MetricType,
+    pub samples: Vec<Sample>,
+}
+
+impl MetricFamily {
+    pub fn new(name: String, help: &str, metric_type: MetricType) -> Self {
+        MetricFamily {
+            name,
+            help: help.to_string(),
+            metric_type,
+            samples: vec![],
+        }
+    }
+    pub fn add_sample(mut self, labels: &[(&str, &str)], value: f64) -> Self {
+        let labels = labels
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        self.samples.push(Sample { labels, value });
+        self
+    }
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+// The families in the text exposition format.
+pub fn render(families: &[MetricFamily]) -> String {
+    let mut text = String::new();
+    for family in families {
+        text.push_str(&format!(
+            "# HELP {} {}\n",
+            family.name,
+            escape_help(&family.help)
+        ));
+        text.push_str(&format!("# TYPE {} {}\n", family.name, family.metric_type));
+        for sample in &family.samples {
+            text.push_str(&family.name);
+            if !sample.labels.is_empty() {
+                let labels: Vec<String> = sample
+                    .labels
+                    .iter()
+                    .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+                    .collect();
+                text.push_str(&format!("{{{}}}", labels.join(",")));
+            }
+            text.push_str(&format!(" {}\n", format_value(sample.value)));
+        }
+    }
+    text
+}
+
+fn bool_value(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+pub struct Exporter<'a> {
+    client: &'a Client,
+    collectors: Vec<Collector>,
+    namespace: String,
+}
+
+impl<'a> Exporter<'a> {
+    // All collectors, with metric names starting with "bitcoind_".
+    pub fn new(client: &'a Client) -> Self {
+        Exporter {
+            client,
+            collectors: Collector::ALL.to_vec(),
+            namespace: DEFAULT_NAMESPACE.to_string(),
+        }
+    }
+    pub fn set_collectors(mut self, collectors: Vec<Collector>) -> Self {
+        self.collectors = collectors;
+        self
+    }
+    // The prefix of the metric names, e.g. to tell apart the metrics of two exporters.
+    pub fn set_namespace(mut self, namespace: String) -> Self {
+        self.namespace = namespace;
+        self
+    }
+
+    fn name(&self, name: &str) -> String {
+        format!("{}_{}", self.namespace, name)
+    }
+    fn gauge(&self, name: &str, help: &str, value: f64) -> MetricFamily {
+        MetricFamily::new(self.name(name), help, MetricType::Gauge).add_sample(&[], value)
+    }
+
+    // The metrics of one collector.
+    pub fn collect(&self, collector: Collector) -> Result<Vec<MetricFamily>, jsonrpc::Error> {
+        match collector {
+            Collector::Chain => self.collect_chain(),
+            Collector::Mempool => self.collect_mempool(),
+            Collector::Peers => self.collect_peers(),
+            Collector::Network => self.collect_network(),
+            Collector::Mining => self.collect_mining(),
+            Collector::ChainTips => self.collect_chain_tips(),
+        }
+    }
+
+    fn collect_chain(&self) -> Result<Vec<MetricFamily>, jsonrpc::Error> {
+        let info = GetBlockchainInfoCommand::new().call(self.client)?;
+        Ok(vec![
+            self.gauge(
+                "blocks",
+                "Height of the most-work fully-validated chain",
+                info.blocks as f64,
+            ),
+            self.gauge(
+                "headers",
+                "Number of validated headers",
+                info.headers as f64,
+            ),
+            self.gauge(
+                "headers_lag",
+                "Validated headers whose blocks aren't validated yet",
+                info.headers.saturating_sub(info.blocks) as f64,
+            ),
+            self.gauge(
+                "verification_progress",
+                "Estimate of the verification progress, from 0 to 1",
+                info.verificationprogress,
+            ),
+            self.gauge(
+                "initial_block_download",
+                "Whether the node is in initial block download",
+                bool_value(info.initialblockdownload),
+            ),
+            self.gauge(
+                "size_on_disk_bytes",
+                "Estimated size of the block and undo files on disk",
+                info.size_on_disk as f64,
+            ),
+        ])
+    }
+
+    fn collect_mempool(&self) -> Result<Vec<MetricFamily>, jsonrpc::Error> {
+        let info = GetMempoolInfoCommand::new().call(self.client)?;
+        Ok(vec![
+            self.gauge(
+                "mempool_transactions",
+                "Transactions in the mempool",
+                info.size as f64,
+            ),
+            self.gauge(
+                "mempool_bytes",
+                "Sum of the virtual sizes of the mempool transactions",
+                info.bytes as f64,
+            ),
+            self.gauge(
+                "mempool_usage_bytes",
+                "Memory used by the mempool",
+                info.usage as f64,
+            ),
+            self.gauge(
+                "mempool_max_bytes",
+                "Maximum memory usage of the mempool",
+                info.maxmempool as f64,
+            ),
+            self.gauge(
+                "mempool_min_fee_btc_per_kvb",
+                "Minimum fee rate for a transaction to be accepted, in BTC/kvB",
+                info.mempoolminfee,
+            ),
+            self.gauge(
+                "min_relay_fee_btc_per_kvb",
+                "Minimum relay fee rate, in BTC/kvB",
+                info.minrelaytxfee,
+            ),
+        ])
+    }
+
+    fn collect_peers(&self) -> Result<Vec<MetricFamily>, jsonrpc::Error> {
+        let peers = GetPeerInfoCommand::new().call(self.client)?;
+        // Sorted, so the samples come out in the same order on every scrape.
+        let mut counts: BTreeMap<(&str, &str), u64> = BTreeMap::new();
+        for peer in &peers.0 {
+            let connection_type = peer.connection_type().unwrap_or(UNKNOWN_LABEL);
+            let network = peer.network().unwrap_or(UNKNOWN_LABEL);
+            *counts.entry((connection_type, network)).or_default() += 1;
+        }
+        let mut family = MetricFamily::new(
+            self.name("peers"),
+            "Connected peers by connection type and network",
+            MetricType::Gauge,
+        );
+        for ((connection_type, network), count) in counts {
+            family = family.add_sample(
+                &[("connection_type", connection_type), ("network", network)],
+                count as f64,
+            );
+        }
+        Ok(vec![family])
+    }
+
+    fn collect_network(&self) -> Result<Vec<MetricFamily>, jsonrpc::Error> {
+        let info = GetNetworkInfoCommand::new().call(self.client)?;
+        let totals = GetNetTotalsCommand::new().call(self.client)?;
+        let mut families = vec![
+            MetricFamily::new(
+                self.name("info"),
+                "The version of the node",
+                MetricType::Gauge,
+            )
+            .add_sample(
+                &[
+                    ("version", &info.version.to_string()),
+                    ("subversion", &info.subversion),
+                ],
+                1.0,
+            ),
+            self.gauge(
+                "network_active",
+                "Whether p2p networking is enabled",
+                bool_value(info.networkactive),
+            ),
+            self.gauge("connections", "Connected peers", info.connections as f64),
+        ];
+        if let (Some(connections_in), Some(connections_out)) =
+            (info.connections_in, info.connections_out)
+        {
+            families.push(
+                MetricFamily::new(
+                    self.name("connections_by_direction"),
+                    "Connected peers by direction",
+                    MetricType::Gauge,
+                )
+                .add_sample(&[("direction", "inbound")], connections_in as f64)
+                .add_sample(&[("direction", "outbound")], connections_out as f64),
+            );
+        }
+        families.push(
+            MetricFamily::new(
+                self.name("net_received_bytes_total"),
+                "Bytes received from peers since the node started",
+                MetricType::Counter,
+            )
+            .add_sample(&[], totals.totalbytesrecv as f64),
+        );
+        families.push(
+            MetricFamily::new(
+                self.name("net_sent_bytes_total"),
+                "Bytes sent to peers since the node started",
+                MetricType::Counter,
+            )
+            .add_sample(&[], totals.totalbytessent as f64),
+        );
+        Ok(families)
+    }
+
+    fn collect_mining(&self) -> Result<Vec<MetricFamily>, jsonrpc::Error> {
+        let info = GetMiningInfoCommand::new().call(self.client)?;
+        Ok(vec![
+            self.gauge(
+                "network_hashes_per_second",
+                "Estimated hashes per second of the network",
+                info.networkhashps,
+            ),
+            self.gauge(
+                "difficulty",
+                "Difficulty of the tip, as a multiple of the minimum",
+                info.difficulty,
+            ),
+        ])
+    }
+
+    fn collect_chain_tips(&self) -> Result<Vec<MetricFamily>, jsonrpc::Error> {
+        let tips = GetChainTipsCommand::new().call(self.client)?;
+        let mut counts: BTreeMap<&str, u64> = BTreeMap::new();
+        for tip in &tips.0 {
+            *counts.entry(tip.status.as_str()).or_default() += 1;
+        }
+        let stale_tips = tips.0.iter().filter(|tip| tip.status != "active").count();
+        let mut family = MetricFamily::new(
+            self.name("chain_tips"),
+            "Known chain tips by status",
+            MetricType::Gauge,
+        );
+        for (status, count) in counts {
+            family = family.add_sample(&[("status", status)], count as f64);
+        }
+        Ok(vec![
+            family,
+            self.gauge(
+                "stale_tips",
+                "Known chain tips that aren't the tip of the active chain",
+                stale_tips as f64,
+            ),
+        ])
+    }
+
+    // The metrics of all collectors, followed by whether each one succeeded and how long it
+    // took. The errors of failed collectors are returned alongside.
+    pub fn gather(&self) -> (Vec<MetricFamily>, Vec<(Collector, jsonrpc::Error)>) {
+        let mut families = vec![];
+        let mut errors = vec![];
+        let mut up = MetricFamily::new(
+            self.name("collector_up"),
+            "Whether the collector's RPC calls succeeded",
+            MetricType::Gauge,
+        );
+        let mut duration = MetricFamily::new(
+            self.name("collector_duration_seconds"),
+            "How long the collector's RPC calls took",
+            MetricType::Gauge,
+        );
+        for collector in &self.collectors {
+            let start = Instant::now();
+            let result = self.collect(*collector);
+            let labels = [("collector", collector.as_str())];
+            duration = duration.add_sample(&labels, start.elapsed().as_secs_f64());
+            match result {
+                Ok(collected) => {
+                    families.extend(collected);
+                    up = up.add_sample(&labels, 1.0);
+                }
+                Err(error) => {
+                    errors.push((*collector, error));
+                    up = up.add_sample(&labels, 0.0);
+                }
+            }
+        }
+        families.push(up);
+        families.push(duration);
+        (families, errors)
+    }
+
+    pub fn render(&self) -> String {
+        render(&self.gather().0)
+    }
+
+    // Serves the metrics on /metrics until the listener fails. Scrapes are handled one at a
+    // time. `on_error` is called with the errors of failed collectors, e.g. to log them.
+    pub fn serve<F: FnMut(Collector, &jsonrpc::Error)>(
+        &self,
+        listener: TcpListener,
+        mut on_error: F,
+    ) -> io::Result<()> {
+        http::serve(listener, |path| {
+            if path != METRICS_PATH {
+                return HttpResponse::not_found();
+            }
+            let (families, errors) = self.gather();
+            for (collector, error) in &errors {
+                on_error(*collector, error);
+            }
+            HttpResponse::ok(CONTENT_TYPE, render(&families))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_matches_the_text_format() {
+        let families = [
+            MetricFamily::new(
+                "bitcoind_blocks".to_string(),
+                "The height of the most-work chain",
+                MetricType::Gauge,
+            )
+            .add_sample(&[], 840000.0),
+            MetricFamily::new(
+                "bitcoind_peer_bytes_sent_total".to_string(),
+                "Bytes sent\\by \"type\"\nper message",
+                MetricType::Counter,
+            )
+            .add_sample(&[("msg", "inv"), ("addr", "[::1]:8333")], 1234.0)
+            .add_sample(&[("msg", "a\\b \"c\"\nd")], 0.5),
+            MetricFamily::new(
+                "bitcoind_difficulty".to_string(),
+                "Odd values",
+                MetricType::Gauge,
+            )
+            .add_sample(&[("value", "nan")], f64::NAN)
+            .add_sample(&[("value", "inf")], f64::INFINITY)
+            .add_sample(&[("value", "-inf")], f64::NEG_INFINITY),
+        ];
+        let expected = r#"# HELP bitcoind_blocks The height of the most-work chain
+# TYPE bitcoind_blocks gauge
+bitcoind_blocks 840000
+# HELP bitcoind_peer_bytes_sent_total Bytes sent\\by "type"\nper message
+# TYPE bitcoind_peer_bytes_sent_total counter
+bitcoind_peer_bytes_sent_total{msg="inv",addr="[::1]:8333"} 1234
+bitcoind_peer_bytes_sent_total{msg="a\\b \"c\"\nd"} 0.5
+# HELP bitcoind_difficulty Odd values
+# TYPE bitcoind_difficulty gauge
+bitcoind_difficulty{value="nan"} NaN
+bitcoind_difficulty{value="inf"} +Inf
+bitcoind_difficulty{value="-inf"} -Inf
+"#;
+        assert_eq!(render(&families), expected);
+    }
+
+    #[test]
+    fn render_of_nothing_is_empty() {
+        assert_eq!(render(&[]), "");
+    }
+}
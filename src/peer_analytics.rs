@@ -0,0 +1,511 @@
+// Peer analytics from successive getpeerinfo snapshots: bandwidth per peer, the ping
+// distribution, the versions peers run, which peers connected and disconnected, and which are
+// stuck behind the tip.
+//
+//     let mut analyzer = PeerAnalyzer::new();
+//     loop {
+//         if let Some(report) = analyzer.update(PeerSnapshot::fetch(&client)?) {
+//             println!("{:?}", report.disconnected);
+//         }
+//         thread::sleep(Duration::from_secs(60));
+//     }
+//
+// Rates and churn need two snapshots; the breakdowns only use the latest one. A peer is matched
+// across snapshots by its id and connection time: ids aren't reused while the node runs, but
+// start over when it restarts.
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::client::Client;
+use crate::command::get_block_count::GetBlockCountCommand;
+use crate::command::get_peer_info::{ConnectedNetworkNode, ConnectionType, GetPeerInfoCommand};
+use crate::command::CallableCommand;
+
+const DEFAULT_MAX_BLOCKS_BEHIND: u64 = 6;
+
+#[derive(Debug, Clone)]
+pub struct PeerSnapshot {
+    pub at: Instant, // when the snapshot was taken
+    pub height: u64, // the node's block count
+    pub peers: Vec<ConnectedNetworkNode>,
+}
+
+impl PeerSnapshot {
+    pub fn fetch(client: &Client) -> Result<Self, jsonrpc::Error> {
+        let height = GetBlockCountCommand::new().call(client)?.0;
+        let peers = GetPeerInfoCommand::new().call(client)?.0;
+        Ok(PeerSnapshot {
+            at: Instant::now(),
+            height,
+            peers,
+        })
+    }
+
+    fn find(&self, peer: &ConnectedNetworkNode) -> Option<&ConnectedNetworkNode> {
+        self.peers
+            .iter()
+            .find(|other| other.id == peer.id && other.conntime == peer.conntime)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeerSummary {
+    pub id: u64,
+    pub addr: String,
+    pub connection_type: Option<ConnectionType>,
+    pub subver: String,
+}
+
+impl From<&ConnectedNetworkNode> for PeerSummary {
+    fn from(peer: &ConnectedNetworkNode) -> Self {
+        PeerSummary {
+            id: peer.id,
+            addr: peer.addr.clone(),
+            connection_type: peer.connection_type.clone(),
+            subver: peer.subver.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeerBandwidth {
+    pub id: u64,
+    pub addr: String,
+    pub sent_per_second: f64,     // in bytes
+    pub received_per_second: f64, // in bytes
+}
+
+// Ping times in seconds, over the peers that have one. Percentiles are nearest-rank.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PingDistribution {
+    pub peers: usize,
+    pub min: f64,
+    pub median: f64,
+    pub p90: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl PingDistribution {
+    // None if no peer has a ping time yet.
+    pub fn new(mut pings: Vec<f64>) -> Option<Self> {
+        if pings.is_empty() {
+            return None;
+        }
+        pings.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            let rank = (p * pings.len() as f64).ceil() as usize;
+            pings[rank.clamp(1, pings.len()) - 1]
+        };
+        Some(PingDistribution {
+            peers: pings.len(),
+            min: pings[0],
+            median: percentile(0.5),
+            p90: percentile(0.9),
+            max: pings[pings.len() - 1],
+            mean: pings.iter().sum::<f64>() / pings.len() as f64,
+        })
+    }
+}
+
+// A peer whose last block in common with us is behind the tip and hasn't moved since the
+// previous snapshot.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct StuckPeer {
+    pub id: u64,
+    pub addr: String,
+    pub synced_blocks: i64, // -1 if we have no block in common yet
+    pub blocks_behind: u64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeerReport {
+    pub elapsed_seconds: f64, // between the two snapshots
+    pub peers: usize,
+    pub bandwidth: Vec<PeerBandwidth>, // peers in both snapshots, busiest first
+    pub sent_per_second: f64,          // over all the peers in both snapshots, in bytes
+    pub received_per_second: f64,      // over all the peers in both snapshots, in bytes
+    pub ping: Option<PingDistribution>,
+    pub versions: BTreeMap<u64, usize>, // peers by protocol version
+    pub subversions: BTreeMap<String, usize>, // peers by user agent
+    pub connection_types: BTreeMap<String, usize>, // peers by connection type, "unknown" before bitcoin core 0.21
+    pub connected: Vec<PeerSummary>,               // peers that weren't in the previous snapshot
+    pub disconnected: Vec<PeerSummary>,            // peers that aren't in the latest snapshot
+    pub stuck: Vec<StuckPeer>,
+}
+
+pub struct PeerAnalyzer {
+    max_blocks_behind: u64,
+    previous: Option<PeerSnapshot>,
+}
+
+impl PeerAnalyzer {
+    pub fn new() -> Self {
+        PeerAnalyzer {
+            max_blocks_behind: DEFAULT_MAX_BLOCKS_BEHIND,
+            previous: None,
+        }
+    }
+    // How far behind the tip a peer's synced_blocks can be before it counts as stuck.
+    pub fn set_max_blocks_behind(mut self, max_blocks_behind: u64) -> Self {
+        self.max_blocks_behind = max_blocks_behind;
+        self
+    }
+
+    // The report against the previous snapshot, none for the first one.
+    pub fn update(&mut self, snapshot: PeerSnapshot) -> Option<PeerReport> {
+        let report = self
+            .previous
+            .as_ref()
+            .map(|previous| self.compare(previous, &snapshot));
+        self.previous = Some(snapshot);
+        report
+    }
+
+    pub fn compare(&self, previous: &PeerSnapshot, current: &PeerSnapshot) -> PeerReport {
+        let elapsed = current.at.saturating_duration_since(previous.at);
+        let elapsed_seconds = elapsed.as_secs_f64();
+        let per_second = |bytes: u64| {
+            if elapsed > Duration::ZERO {
+                bytes as f64 / elapsed_seconds
+            } else {
+                0.0
+            }
+        };
+
+        let mut bandwidth = vec![];
+        let mut stuck = vec![];
+        let mut connected = vec![];
+        for peer in &current.peers {
+            let before = match previous.find(peer) {
+                Some(before) => before,
+                None => {
+                    connected.push(PeerSummary::from(peer));
+                    continue;
+                }
+            };
+            bandwidth.push(PeerBandwidth {
+                id: peer.id,
+                addr: peer.addr.clone(),
+                sent_per_second: per_second(peer.bytessent.saturating_sub(before.bytessent)),
+                received_per_second: per_second(peer.bytesrecv.saturating_sub(before.bytesrecv)),
+            });
+            if let Some(stuck_peer) = self.stuck(before, peer, current.height) {
+                stuck.push(stuck_peer);
+            }
+        }
+        bandwidth.sort_by(|a, b| {
+            let total = |peer: &PeerBandwidth| peer.sent_per_second + peer.received_per_second;
+            total(b).total_cmp(&total(a))
+        });
+        let disconnected = previous
+            .peers
+            .iter()
+            .filter(|peer| current.find(peer).is_none())
+            .map(PeerSummary::from)
+            .collect();
+
+        let mut versions = BTreeMap::new();
+        let mut subversions = BTreeMap::new();
+        let mut connection_types = BTreeMap::new();
+        for peer in &current.peers {
+            *versions.entry(peer.version).or_default() += 1;
+            *subversions.entry(peer.subver.clone()).or_default() += 1;
+            let connection_type = peer.connection_type().unwrap_or("unknown").to_string();
+            *connection_types.entry(connection_type).or_default() += 1;
+        }
+
+        PeerReport {
+            elapsed_seconds,
+            peers: current.peers.len(),
+            sent_per_second: bandwidth.iter().map(|peer| peer.sent_per_second).sum(),
+            received_per_second: bandwidth.iter().map(|peer| peer.received_per_second).sum(),
+            bandwidth,
+            ping: PingDistribution::new(
+                current
+                    .peers
+                    .iter()
+                    .filter_map(|peer| peer.pingtime)
+                    .collect(),
+            ),
+            versions,
+            subversions,
+            connection_types,
+            connected,
+            disconnected,
+            stuck,
+        }
+    }
+
+    fn stuck(
+        &self,
+        before: &ConnectedNetworkNode,
+        peer: &ConnectedNetworkNode,
+        height: u64,
+    ) -> Option<StuckPeer> {
+        // Peers whose state isn't available don't report synced_blocks.
+        let synced_blocks = peer.synced_blocks?;
+        let blocks_behind = (height as i64).saturating_sub(synced_blocks).max(0) as u64;
+        let advanced = before
+            .synced_blocks
+            .is_some_and(|synced_before| synced_blocks > synced_before);
+        if blocks_behind <= self.max_blocks_behind || advanced {
+            return None;
+        }
+        Some(StuckPeer {
+            id: peer.id,
+            addr: peer.addr.clone(),
+            synced_blocks,
+            blocks_behind,
+        })
+    }
+}
+
+impl Default for PeerAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn peer(id: u64, conntime: u64) -> ConnectedNetworkNode {
+        serde_json::from_value(json!({
+            "id": id,
+            "addr": format!("203.0.113.{}:8333", id),
+            "services": "0000000000000409",
+            "lastsend": conntime,
+            "lastrecv": conntime,
+            "bytessent": 0,
+            "bytesrecv": 0,
+            "conntime": conntime,
+            "timeoffset": 0,
+            "version": 70016,
+            "subver": "/Satoshi:27.0.0/",
+            "inbound": false,
+            "connection_type": "outbound-full-relay",
+            "bytessent_per_msg": {},
+            "bytesrecv_per_msg": {},
+        }))
+        .unwrap()
+    }
+
+    fn snapshot(at: Instant, height: u64, peers: Vec<ConnectedNetworkNode>) -> PeerSnapshot {
+        PeerSnapshot { at, height, peers }
+    }
+
+    fn ids(peers: &[PeerSummary]) -> Vec<u64> {
+        peers.iter().map(|peer| peer.id).collect()
+    }
+
+    #[test]
+    fn no_report_from_the_first_snapshot() {
+        let mut analyzer = PeerAnalyzer::new();
+        assert_eq!(
+            analyzer.update(snapshot(Instant::now(), 100, vec![peer(1, 1000)])),
+            None
+        );
+    }
+
+    #[test]
+    fn peers_are_matched_by_id_and_connection_time() {
+        let start = Instant::now();
+        let mut analyzer = PeerAnalyzer::new();
+        analyzer.update(snapshot(start, 100, vec![peer(1, 1000), peer(2, 1000)]));
+        let report = analyzer
+            .update(snapshot(
+                start + Duration::from_secs(60),
+                100,
+                vec![peer(2, 1000), peer(3, 1050)],
+            ))
+            .unwrap();
+        assert_eq!(ids(&report.connected), vec![3]);
+        assert_eq!(ids(&report.disconnected), vec![1]);
+        assert_eq!(report.peers, 2);
+        assert_eq!(
+            report
+                .bandwidth
+                .iter()
+                .map(|peer| peer.id)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn an_id_reused_after_a_restart_is_a_new_peer() {
+        let start = Instant::now();
+        let before = snapshot(start, 100, vec![peer(0, 1000)]);
+        let mut reused = peer(0, 5000);
+        reused.bytessent = 10;
+        let after = snapshot(start + Duration::from_secs(60), 100, vec![reused]);
+        let report = PeerAnalyzer::new().compare(&before, &after);
+        assert_eq!(ids(&report.connected), vec![0]);
+        assert_eq!(ids(&report.disconnected), vec![0]);
+        assert!(report.bandwidth.is_empty());
+    }
+
+    #[test]
+    fn bandwidth_per_peer_busiest_first() {
+        let start = Instant::now();
+        let before = snapshot(start, 100, vec![peer(1, 1000), peer(2, 1000)]);
+        let mut quiet = peer(1, 1000);
+        quiet.bytessent = 100;
+        quiet.bytesrecv = 100;
+        let mut busy = peer(2, 1000);
+        busy.bytessent = 1000;
+        busy.bytesrecv = 3000;
+        let after = snapshot(start + Duration::from_secs(10), 100, vec![quiet, busy]);
+        let report = PeerAnalyzer::new().compare(&before, &after);
+        assert_eq!(report.elapsed_seconds, 10.0);
+        assert_eq!(
+            report.bandwidth,
+            vec![
+                PeerBandwidth {
+                    id: 2,
+                    addr: "203.0.113.2:8333".to_string(),
+                    sent_per_second: 100.0,
+                    received_per_second: 300.0,
+                },
+                PeerBandwidth {
+                    id: 1,
+                    addr: "203.0.113.1:8333".to_string(),
+                    sent_per_second: 10.0,
+                    received_per_second: 10.0,
+                },
+            ]
+        );
+        assert_eq!(report.sent_per_second, 110.0);
+        assert_eq!(report.received_per_second, 310.0);
+    }
+
+    #[test]
+    fn no_rates_over_no_time() {
+        let at = Instant::now();
+        let before = snapshot(at, 100, vec![peer(1, 1000)]);
+        let mut after = peer(1, 1000);
+        after.bytessent = 500;
+        let report = PeerAnalyzer::new().compare(&before, &snapshot(at, 100, vec![after]));
+        assert_eq!(report.elapsed_seconds, 0.0);
+        assert_eq!(report.bandwidth[0].sent_per_second, 0.0);
+        assert_eq!(report.sent_per_second, 0.0);
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        assert_eq!(PingDistribution::new(vec![]), None);
+        let ping = PingDistribution::new(vec![0.5]).unwrap();
+        assert_eq!(
+            (ping.min, ping.median, ping.p90, ping.max),
+            (0.5, 0.5, 0.5, 0.5)
+        );
+
+        // Ranks are ceil(p * n): the 5th and 9th of 10 pings.
+        let pings = vec![0.10, 0.09, 0.08, 0.07, 0.06, 0.05, 0.04, 0.03, 0.02, 0.01];
+        let ping = PingDistribution::new(pings).unwrap();
+        assert_eq!(ping.peers, 10);
+        assert_eq!(ping.min, 0.01);
+        assert_eq!(ping.median, 0.05);
+        assert_eq!(ping.p90, 0.09);
+        assert_eq!(ping.max, 0.10);
+        assert!((ping.mean - 0.055).abs() < 1e-12);
+
+        // The median of an even count is the lower middle value, not their mean.
+        let ping = PingDistribution::new(vec![0.4, 0.1, 0.3, 0.2]).unwrap();
+        assert_eq!(ping.median, 0.2);
+        assert_eq!(ping.p90, 0.4);
+    }
+
+    #[test]
+    fn a_peer_behind_and_not_advancing_is_stuck() {
+        let start = Instant::now();
+        let with_synced_blocks = |id, synced_blocks| {
+            let mut peer = peer(id, 1000);
+            peer.synced_blocks = synced_blocks;
+            peer
+        };
+        let before = snapshot(
+            start,
+            100,
+            vec![
+                with_synced_blocks(1, Some(80)),
+                with_synced_blocks(2, Some(80)),
+                with_synced_blocks(3, Some(-1)),
+                with_synced_blocks(4, Some(97)),
+                with_synced_blocks(5, None),
+            ],
+        );
+        let after = snapshot(
+            start + Duration::from_secs(60),
+            110,
+            vec![
+                // Behind, but catching up.
+                with_synced_blocks(1, Some(90)),
+                with_synced_blocks(2, Some(80)),
+                with_synced_blocks(3, Some(-1)),
+                // Within the limit.
+                with_synced_blocks(4, Some(104)),
+                with_synced_blocks(5, None),
+            ],
+        );
+        let report = PeerAnalyzer::new().compare(&before, &after);
+        assert_eq!(
+            report.stuck,
+            vec![
+                StuckPeer {
+                    id: 2,
+                    addr: "203.0.113.2:8333".to_string(),
+                    synced_blocks: 80,
+                    blocks_behind: 30,
+                },
+                StuckPeer {
+                    id: 3,
+                    addr: "203.0.113.3:8333".to_string(),
+                    synced_blocks: -1,
+                    blocks_behind: 111,
+                },
+            ]
+        );
+
+        let report = PeerAnalyzer::new()
+            .set_max_blocks_behind(30)
+            .compare(&before, &after);
+        assert_eq!(report.stuck.len(), 1);
+    }
+
+    #[test]
+    fn breakdowns_of_the_latest_snapshot() {
+        let start = Instant::now();
+        let mut old = peer(2, 1000);
+        old.version = 70015;
+        old.subver = "/Satoshi:0.20.1/".to_string();
+        old.connection_type = None;
+        old.pingtime = Some(0.2);
+        let mut current = peer(1, 1000);
+        current.pingtime = Some(0.1);
+        let peers = vec![current, old, peer(3, 1000)];
+        let report = PeerAnalyzer::new()
+            .compare(&snapshot(start, 100, vec![]), &snapshot(start, 100, peers));
+        assert_eq!(report.versions, BTreeMap::from([(70015, 1), (70016, 2)]));
+        assert_eq!(
+            report.subversions,
+            BTreeMap::from([
+                ("/Satoshi:0.20.1/".to_string(), 1),
+                ("/Satoshi:27.0.0/".to_string(), 2),
+            ])
+        );
+        assert_eq!(
+            report.connection_types,
+            BTreeMap::from([
+                ("outbound-full-relay".to_string(), 2),
+                ("unknown".to_string(), 1),
+            ])
+        );
+        assert_eq!(report.ping.unwrap().peers, 2);
+    }
+}
@@ -0,0 +1,328 @@
+// Tracks a node's sync, mostly during initial block download: samples getblockchaininfo over time
+// and estimates how fast the node is syncing and how long it has left.
+//
+//     let cancel = LongPollCancel::new();
+//     for estimate in SyncMonitor::new(&client, cancel) {
+//         let estimate = estimate?;
+//         println!("{} blocks left, done in {:?}", estimate.remaining_blocks(), estimate.eta);
+//     }
+//
+// The monitor stops once the node is synced. SyncTracker does the estimating on its own, for
+// samples taken some other way. Rates are taken over a window of recent samples, which smooths
+// out the bursts of block validation. The time left is estimated from the verification progress,
+// which the node weighs by transactions, so unlike the block count it doesn't get
+// overoptimistic in the early, nearly empty blocks.
+use std::collections::VecDeque;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::client::Client;
+use crate::command::get_blockchain_info::GetBlockchainInfoCommand;
+use crate::command::CallableCommand;
+use crate::long_poll::LongPollCancel;
+
+const DEFAULT_WINDOW: Duration = Duration::from_secs(10 * 60);
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncSample {
+    pub at: Instant, // when the sample was taken
+    pub blocks: u64,
+    pub headers: u64,
+    pub verification_progress: f64,
+    pub size_on_disk: u64,
+    pub initial_block_download: bool,
+    pub tip_time: Option<u64>, // the block time of the tip (added in bitcoin core 23)
+}
+
+impl SyncSample {
+    pub fn fetch(client: &Client) -> Result<Self, jsonrpc::Error> {
+        let info = GetBlockchainInfoCommand::new().call(client)?;
+        Ok(SyncSample {
+            at: Instant::now(),
+            blocks: info.blocks,
+            headers: info.headers,
+            verification_progress: info.verificationprogress,
+            size_on_disk: info.size_on_disk,
+            initial_block_download: info.initialblockdownload,
+            tip_time: info.time,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncEstimate {
+    pub sample: SyncSample,               // the latest sample
+    pub blocks_per_second: Option<f64>,   // none until there are two samples
+    pub progress_per_second: Option<f64>, // in verification progress, from 0 to 1
+    pub bytes_per_second: Option<f64>,    // growth of the block and undo files on disk
+    pub eta: Option<Duration>, // from the verification progress; none while it isn't advancing
+    pub eta_by_blocks: Option<Duration>, // from the block count, which is less reliable
+}
+
+impl SyncEstimate {
+    pub fn remaining_blocks(&self) -> u64 {
+        self.sample.headers.saturating_sub(self.sample.blocks)
+    }
+    // Out of initial block download with every known header validated.
+    pub fn is_synced(&self) -> bool {
+        !self.sample.initial_block_download && self.remaining_blocks() == 0
+    }
+    // The time since the tip's block time, if the node reports it.
+    pub fn tip_age(&self) -> Option<Duration> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+        let tip_time = Duration::from_secs(self.sample.tip_time?);
+        Some(now.saturating_sub(tip_time))
+    }
+}
+
+// Estimates from samples added over time. Samples older than the window are dropped, except
+// that the oldest one is kept until a newer one spans the whole window.
+pub struct SyncTracker {
+    window: Duration,
+    samples: VecDeque<SyncSample>,
+}
+
+impl SyncTracker {
+    pub fn new() -> Self {
+        SyncTracker {
+            window: DEFAULT_WINDOW,
+            samples: VecDeque::new(),
+        }
+    }
+    // Longer windows give steadier estimates that are slower to follow changes in speed.
+    pub fn set_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    pub fn add_sample(&mut self, sample: SyncSample) -> SyncEstimate {
+        self.samples.push_back(sample);
+        let newest = self.samples.back().unwrap().at;
+        while self.samples.len() > 2 && newest.duration_since(self.samples[1].at) >= self.window {
+            self.samples.pop_front();
+        }
+        self.estimate().unwrap()
+    }
+
+    // The estimate from the samples so far, none before the first one.
+    pub fn estimate(&self) -> Option<SyncEstimate> {
+        let newest = self.samples.back()?;
+        let oldest = self.samples.front()?;
+        let elapsed = newest.at.duration_since(oldest.at).as_secs_f64();
+        let rate = |delta: f64| {
+            if elapsed > 0.0 {
+                Some(delta / elapsed)
+            } else {
+                None
+            }
+        };
+        let blocks_per_second = rate(newest.blocks as f64 - oldest.blocks as f64);
+        let progress_per_second = rate(newest.verification_progress - oldest.verification_progress);
+        let bytes_per_second = rate(newest.size_on_disk as f64 - oldest.size_on_disk as f64);
+        let remaining_blocks = newest.headers.saturating_sub(newest.blocks) as f64;
+        let remaining_progress = (1.0 - newest.verification_progress).max(0.0);
+        Some(SyncEstimate {
+            sample: newest.clone(),
+            blocks_per_second,
+            progress_per_second,
+            bytes_per_second,
+            eta: time_left(remaining_progress, progress_per_second),
+            eta_by_blocks: time_left(remaining_blocks, blocks_per_second),
+        })
+    }
+}
+
+impl Default for SyncTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn time_left(remaining: f64, per_second: Option<f64>) -> Option<Duration> {
+    match per_second {
+        Some(per_second) if per_second > 0.0 => {
+            Duration::try_from_secs_f64(remaining / per_second).ok()
+        }
+        _ => None,
+    }
+}
+
+// Yields an estimate per sample, sampling every interval until the node is synced (the synced
+// estimate is the last one) or the monitor is cancelled. Cancelling takes effect within an
+// interval.
+//
+// An error is yielded as an item, and iterating again samples again.
+pub struct SyncMonitor<'a> {
+    client: &'a Client,
+    cancel: LongPollCancel,
+    interval: Duration,
+    tracker: SyncTracker,
+    sampled: bool,
+    synced: bool,
+}
+
+impl<'a> SyncMonitor<'a> {
+    pub fn new(client: &'a Client, cancel: LongPollCancel) -> Self {
+        SyncMonitor {
+            client,
+            cancel,
+            interval: DEFAULT_INTERVAL,
+            tracker: SyncTracker::new(),
+            sampled: false,
+            synced: false,
+        }
+    }
+    pub fn set_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+    pub fn set_window(mut self, window: Duration) -> Self {
+        self.tracker = self.tracker.set_window(window);
+        self
+    }
+
+    // Calls `on_estimate` with each estimate until the node is synced or the monitor is
+    // cancelled, and returns the last estimate. Stops at the first error.
+    pub fn run<F: FnMut(&SyncEstimate)>(
+        self,
+        mut on_estimate: F,
+    ) -> Result<Option<SyncEstimate>, jsonrpc::Error> {
+        let mut last = None;
+        for estimate in self {
+            let estimate = estimate?;
+            on_estimate(&estimate);
+            last = Some(estimate);
+        }
+        Ok(last)
+    }
+}
+
+impl Iterator for SyncMonitor<'_> {
+    type Item = Result<SyncEstimate, jsonrpc::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.synced || self.cancel.is_cancelled() {
+            return None;
+        }
+        if self.sampled {
+            thread::sleep(self.interval);
+            if self.cancel.is_cancelled() {
+                return None;
+            }
+        }
+        self.sampled = true;
+        match SyncSample::fetch(self.client) {
+            Ok(sample) => {
+                let estimate = self.tracker.add_sample(sample);
+                self.synced = estimate.is_synced();
+                Some(Ok(estimate))
+            }
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(at: Instant, blocks: u64, verification_progress: f64) -> SyncSample {
+        SyncSample {
+            at,
+            blocks,
+            headers: 800_000,
+            verification_progress,
+            size_on_disk: blocks * 1000,
+            initial_block_download: true,
+            tip_time: None,
+        }
+    }
+
+    fn assert_close(duration: Option<Duration>, seconds: f64) {
+        let duration = duration.expect("no estimate");
+        assert!(
+            (duration.as_secs_f64() - seconds).abs() < 1e-6,
+            "{:?} isn't {}s",
+            duration,
+            seconds
+        );
+    }
+
+    #[test]
+    fn no_rates_from_a_single_sample() {
+        let mut tracker = SyncTracker::new();
+        assert_eq!(tracker.estimate(), None);
+        let estimate = tracker.add_sample(sample(Instant::now(), 400_000, 0.25));
+        assert_eq!(estimate.blocks_per_second, None);
+        assert_eq!(estimate.progress_per_second, None);
+        assert_eq!(estimate.eta, None);
+        assert_eq!(estimate.remaining_blocks(), 400_000);
+        assert!(!estimate.is_synced());
+    }
+
+    #[test]
+    fn a_steady_rate() {
+        let start = Instant::now();
+        let mut tracker = SyncTracker::new();
+        tracker.add_sample(sample(start, 400_000, 0.25));
+        tracker.add_sample(sample(start + Duration::from_secs(10), 401_000, 0.2625));
+        let estimate = tracker.add_sample(sample(start + Duration::from_secs(20), 402_000, 0.275));
+        assert_eq!(estimate.blocks_per_second, Some(100.0));
+        assert_eq!(estimate.bytes_per_second, Some(100_000.0));
+        // 0.725 left at 0.00125 a second.
+        assert_close(estimate.eta, 580.0);
+        // 398,000 blocks left at 100 a second.
+        assert_close(estimate.eta_by_blocks, 3980.0);
+    }
+
+    #[test]
+    fn no_eta_while_stalled() {
+        let start = Instant::now();
+        let mut tracker = SyncTracker::new();
+        tracker.add_sample(sample(start, 400_000, 0.25));
+        let estimate = tracker.add_sample(sample(start + Duration::from_secs(60), 400_000, 0.25));
+        assert_eq!(estimate.blocks_per_second, Some(0.0));
+        assert_eq!(estimate.progress_per_second, Some(0.0));
+        assert_eq!(estimate.eta, None);
+        assert_eq!(estimate.eta_by_blocks, None);
+    }
+
+    #[test]
+    fn no_rates_from_samples_taken_at_once() {
+        let start = Instant::now();
+        let mut tracker = SyncTracker::new();
+        tracker.add_sample(sample(start, 400_000, 0.25));
+        let estimate = tracker.add_sample(sample(start, 400_100, 0.26));
+        assert_eq!(estimate.blocks_per_second, None);
+        assert_eq!(estimate.eta, None);
+    }
+
+    #[test]
+    fn samples_older_than_the_window_are_dropped() {
+        let start = Instant::now();
+        let at = |seconds| start + Duration::from_secs(seconds);
+        let mut tracker = SyncTracker::new().set_window(Duration::from_secs(60));
+        // Fast at first, then 10 blocks a second.
+        tracker.add_sample(sample(at(0), 100_000, 0.1));
+        tracker.add_sample(sample(at(30), 200_000, 0.2));
+        // The first sample is kept until a newer one spans the window.
+        let estimate = tracker.add_sample(sample(at(60), 200_300, 0.2));
+        assert_eq!(estimate.blocks_per_second, Some(100_300.0 / 60.0));
+        let estimate = tracker.add_sample(sample(at(90), 200_600, 0.2));
+        assert_eq!(estimate.blocks_per_second, Some(10.0));
+        let estimate = tracker.add_sample(sample(at(150), 201_200, 0.2));
+        assert_eq!(estimate.blocks_per_second, Some(10.0));
+    }
+
+    #[test]
+    fn synced_once_out_of_initial_block_download_with_every_header_validated() {
+        let mut tracker = SyncTracker::new();
+        let estimate = tracker.add_sample(SyncSample {
+            initial_block_download: false,
+            ..sample(Instant::now(), 800_000, 0.999_999)
+        });
+        assert_eq!(estimate.remaining_blocks(), 0);
+        assert!(estimate.is_synced());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod transaction;
+pub mod work;
@@ -0,0 +1,247 @@
+// Transaction model shared by every command that returns decoded transaction data:
+// getblock (verbosity 2 and 3), getrawtransaction (verbose), decoderawtransaction, and the
+// mempool commands (getrawmempool verbose, getmempoolentry).
+use serde::{Deserialize, Serialize};
+
+// Transactions with an input sequence number at or below this value signal replaceability (BIP125).
+const MAX_BIP125_RBF_SEQUENCE: u64 = 0xffff_fffd;
+
+pub type Txid = String;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OutPoint {
+    pub txid: Txid, // "hex" The id of the transaction that created the output
+    pub vout: u64,  // The index of the output in that transaction
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HexEncodedWitnessData(pub String);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BitcoinAddress(pub String);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptSig {
+    pub asm: String, // "asm", NOT A HEX
+    pub hex: String, // "hex", hex
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptPubKey {
+    pub asm: String,          // "asm", NOT A HEX
+    pub desc: Option<String>, // Inferred descriptor for the output (only returned by newer versions of bitcoin core)
+    pub hex: String,          // the hex
+    // deprecated
+    pub req_sigs: Option<u64>, // The required sigs
+    #[serde(rename = "type")]
+    pub type_: String, // The type, eg 'pubkeyhash'
+    pub address: Option<String>, // bitcoin address (only if a well-defined address exists)
+    // deprecated
+    pub addresses: Option<Vec<String>>,
+}
+
+// Information about the output being spent by an input (only present for getblock verbosity 3)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Prevout {
+    pub generated: bool, // Coinbase or not
+    pub height: u64,     // The height of the prevout
+    pub value: f64,      // The value in BTC
+    pub script_pub_key: ScriptPubKey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+pub enum Vin {
+    Coinbase(CoinbaseVin),
+    NonCoinbase(NonCoinbaseVin),
+}
+
+impl Vin {
+    pub fn sequence(&self) -> u64 {
+        match self {
+            Vin::Coinbase(vin) => vin.sequence,
+            Vin::NonCoinbase(vin) => vin.sequence,
+        }
+    }
+    pub fn txinwitness(&self) -> Option<&Vec<HexEncodedWitnessData>> {
+        match self {
+            Vin::Coinbase(vin) => vin.txinwitness.as_ref(),
+            Vin::NonCoinbase(vin) => vin.txinwitness.as_ref(),
+        }
+    }
+    pub fn outpoint(&self) -> Option<OutPoint> {
+        match self {
+            Vin::Coinbase(_) => None,
+            Vin::NonCoinbase(vin) => Some(OutPoint::from(vin)),
+        }
+    }
+}
+
+impl From<CoinbaseVin> for Vin {
+    fn from(vin: CoinbaseVin) -> Self {
+        Vin::Coinbase(vin)
+    }
+}
+
+impl From<NonCoinbaseVin> for Vin {
+    fn from(vin: NonCoinbaseVin) -> Self {
+        Vin::NonCoinbase(vin)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinbaseVin {
+    pub coinbase: String,                                // "hex" The coinbase script
+    pub sequence: u64,                                   // The script sequence number
+    pub txinwitness: Option<Vec<HexEncodedWitnessData>>, // hex-encoded witness data (if any)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NonCoinbaseVin {
+    pub txid: Txid, // "hex" The transaction id
+    pub vout: u64,  // The output number
+    pub script_sig: ScriptSig,
+    pub sequence: u64, // The script sequence number
+    // Only present if the input has witness data
+    pub txinwitness: Option<Vec<HexEncodedWitnessData>>,
+    pub prevout: Option<Prevout>, // (only present for getblock verbosity 3)
+}
+
+impl From<&NonCoinbaseVin> for OutPoint {
+    fn from(vin: &NonCoinbaseVin) -> Self {
+        OutPoint {
+            txid: vin.txid.clone(),
+            vout: vin.vout,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Vout {
+    pub value: f64, // The value in BTC
+    pub n: u64,     // index
+    pub script_pub_key: ScriptPubKey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Transaction {
+    pub in_active_chain: Option<bool>, // Whether specified block is in the active chain or not (only present with explicit "blockhash" argument to getrawtransaction)
+    pub hex: Option<String>, // "hex" The serialized, hex-encoded data for 'txid' (not returned by decoderawtransaction)
+    pub txid: Txid,          // "hex" The transaction id
+    pub hash: String, // "hex" The transaction hash (differs from txid for witness transactions)
+    pub size: u64,    // The serialized transaction size
+    pub vsize: u64,   // The virtual transaction size (differs from size for witness transactions)
+    pub weight: u64,  // The transaction's weight (between vsize*4-3 and vsize*4)
+    pub version: i64, // The version: signed before bitcoin core 28, unsigned from then on
+    pub locktime: u64, // The lock time
+    pub vin: Vec<Vin>,
+    pub vout: Vec<Vout>,
+    pub fee: Option<f64>, // The transaction fee in BTC (only present for getblock verbosity 2 and 3, if undo data is available)
+    pub blockhash: Option<String>, // "hex" the block hash (only present for confirmed transactions returned by getrawtransaction)
+    pub confirmations: Option<u64>, // The confirmations (only present for confirmed transactions returned by getrawtransaction)
+    pub blocktime: Option<u64>,     // "unix time" The block time expressed in UNIX epoch time
+    pub time: Option<u64>,          // "unix time" Same as "blocktime"
+}
+
+// TODO: I don't think this belongs in this package. We should focus on RPC request and responses
+// and abstract a better data layer into another package.
+impl Transaction {
+    pub fn is_coinbase(&self) -> bool {
+        matches!(self.vin.first(), Some(Vin::Coinbase(_)))
+    }
+    #[deprecated(note = "use is_coinbase")]
+    pub fn is_coinbase_transaction(&self) -> bool {
+        self.is_coinbase()
+    }
+    pub fn has_witness(&self) -> bool {
+        self.vin.iter().any(|vin| vin.txinwitness().is_some())
+    }
+    // Sum of all output values in BTC
+    pub fn total_output(&self) -> f64 {
+        self.vout.iter().map(|vout| vout.value).sum()
+    }
+    // The outputs spent by this transaction. Empty for coinbase transactions.
+    pub fn outpoints(&self) -> Vec<OutPoint> {
+        self.vin.iter().filter_map(Vin::outpoint).collect()
+    }
+    // Whether any input opts in to replacement as defined in BIP125
+    pub fn is_rbf_signaled(&self) -> bool {
+        self.vin
+            .iter()
+            .any(|vin| vin.sequence() <= MAX_BIP125_RBF_SEQUENCE)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MempoolEntryFees {
+    pub base: f64,       // transaction fee in BTC
+    pub modified: f64,   // transaction fee with fee deltas used for mining priority in BTC
+    pub ancestor: f64, // modified fees (see above) of in-mempool ancestors (including this one) in BTC
+    pub descendant: f64, // modified fees (see above) of in-mempool descendants (including this one) in BTC
+}
+
+// A transaction as seen by the mempool. Returned by getmempoolentry and getrawmempool (verbose).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MempoolEntry {
+    pub vsize: u64,  // virtual transaction size as defined in BIP 141.
+    pub weight: u64, // transaction weight as defined in BIP 141.
+    // deprecated
+    pub fee: Option<f64>, // transaction fee in BTC
+    // deprecated
+    pub modifiedfee: Option<f64>, // transaction fee with fee deltas used for mining priority
+    // TODO: represent using unix time
+    pub time: u64, // local time transaction entered pool in seconds since 1 Jan 1970 GMT
+    pub height: u64, // block height when transaction entered pool
+    pub descendantcount: u64, // number of in-mempool descendant transactions (including this one)
+    pub descendantsize: u64, // virtual transaction size of in-mempool descendants (including this one)
+    // deprecated
+    pub descendantfees: Option<u64>, // modified fees (see above) of in-mempool descendants (including this one)
+    pub ancestorcount: u64, // number of in-mempool ancestor transactions (including this one)
+    pub ancestorsize: u64,  // virtual transaction size of in-mempool ancestors (including this one)
+    // deprecated
+    pub ancestorfees: Option<u64>, // modified fees (see above) of in-mempool ancestors (including this one)
+    pub wtxid: String,             // "hex" hash of serialized transaction, including witness data
+    pub fees: MempoolEntryFees,
+    pub depends: Vec<Txid>, // unconfirmed transactions used as inputs for this transaction
+    pub spentby: Vec<Txid>, // unconfirmed transactions spending outputs from this transaction
+    #[serde(rename = "bip125-replaceable")]
+    pub bip125_replaceable: bool, // Whether this transaction could be replaced due to BIP125 (replace-by-fee)
+    pub unbroadcast: bool, // Whether this transaction is currently unbroadcast (initial broadcast not yet acknowledged by any peers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transaction(version: i64) -> serde_json::Value {
+        json!({
+            "txid": "0e3e2357e806b6cdb1f70b54c3a3a17b6714ee1f0e68bebb44a74b1efd512098",
+            "hash": "0e3e2357e806b6cdb1f70b54c3a3a17b6714ee1f0e68bebb44a74b1efd512098",
+            "size": 134,
+            "vsize": 134,
+            "weight": 536,
+            "version": version,
+            "locktime": 0,
+            "vin": [{"coinbase": "04ffff001d0104", "sequence": 4294967295u32}],
+            "vout": [],
+        })
+    }
+
+    #[test]
+    fn versions_with_the_high_bit_set() {
+        // Printed signed before bitcoin core 28, and unsigned from then on.
+        for version in [-1, 0xffff_ffff, 2] {
+            let transaction: Transaction = serde_json::from_value(transaction(version)).unwrap();
+            assert_eq!(transaction.version, version);
+            assert!(transaction.is_coinbase());
+        }
+    }
+}
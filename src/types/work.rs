@@ -0,0 +1,281 @@
+// Proof of work quantities as reported by bitcoin core: "bits" (the compact encoding of a block's
+// target), targets and chainwork. They (de)serialize from the hex strings used in RPC responses.
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub};
+use std::str::FromStr;
+
+use crate::consensus::encode::DecodeError;
+use crate::consensus::pow::{block_proof, target_from_compact, target_to_compact};
+use crate::consensus::uint::U256;
+
+// The target of difficulty 1, which is what difficulty is measured against on every network.
+const DIFFICULTY_1_BITS: u32 = 0x1d00ffff;
+
+// The compact "bits" encoding of a target, e.g. "1d00ffff".
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Bits(pub u32);
+
+impl Bits {
+    pub fn target(&self) -> Target {
+        Target(target_from_compact(self.0).target)
+    }
+    // The difficulty as reported by getblockheader and getdifficulty (core's GetDifficulty).
+    pub fn difficulty(&self) -> f64 {
+        let mut shift = (self.0 >> 24) & 0xff;
+        let mut difficulty = 0x0000_ffff as f64 / (self.0 & 0x00ff_ffff) as f64;
+        while shift < 29 {
+            difficulty *= 256.0;
+            shift += 1;
+        }
+        while shift > 29 {
+            difficulty /= 256.0;
+            shift -= 1;
+        }
+        difficulty
+    }
+    // The expected number of hashes needed to find a block with these bits.
+    pub fn work(&self) -> Work {
+        Work(block_proof(self.0))
+    }
+}
+
+impl fmt::Display for Bits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:08x}", self.0)
+    }
+}
+
+impl FromStr for Bits {
+    type Err = DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u32::from_str_radix(s, 16)
+            .map(Bits)
+            .map_err(|_| DecodeError::InvalidHex)
+    }
+}
+
+impl From<Target> for Bits {
+    fn from(target: Target) -> Self {
+        Bits(target_to_compact(target.0))
+    }
+}
+
+// A block hash must be at or below the target to be valid.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Default)]
+pub struct Target(pub U256);
+
+impl Target {
+    pub fn from_bits(bits: Bits) -> Self {
+        bits.target()
+    }
+    pub fn to_bits(&self) -> Bits {
+        Bits::from(*self)
+    }
+    // Difficulty 1's target divided by this target. Lower targets are more difficult.
+    pub fn difficulty(&self) -> f64 {
+        let difficulty_1 = target_from_compact(DIFFICULTY_1_BITS).target;
+        difficulty_1.to_f64() / self.0.to_f64()
+    }
+    // The expected number of hashes needed to find a block hash at or below this target,
+    // 2**256 / (target+1).
+    pub fn work(&self) -> Work {
+        if self.0.is_zero() {
+            return Work::default();
+        }
+        Work((!self.0 / (self.0 + U256::ONE)) + U256::ONE)
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Target {
+    type Err = DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        U256::from_hex(s).map(Target)
+    }
+}
+
+// An amount of work: the expected number of hashes needed to produce a block or chain. Chains are
+// compared by their chainwork, the total work of their blocks.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Default)]
+pub struct Work(pub U256);
+
+impl Work {
+    pub fn from_bits(bits: Bits) -> Self {
+        bits.work()
+    }
+    // An approximation, e.g. for hashrate estimates.
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_f64()
+    }
+    // Core logs chainwork as log2, e.g. "log2_work=94.8".
+    pub fn log2(&self) -> f64 {
+        self.to_f64().log2()
+    }
+}
+
+impl Add for Work {
+    type Output = Work;
+    fn add(self, other: Work) -> Work {
+        Work(self.0 + other.0)
+    }
+}
+
+impl AddAssign for Work {
+    fn add_assign(&mut self, other: Work) {
+        self.0 = self.0 + other.0;
+    }
+}
+
+// Saturates at zero, e.g. for the work a chain has in excess of a shorter one.
+impl Sub for Work {
+    type Output = Work;
+    fn sub(self, other: Work) -> Work {
+        match self.0.overflowing_sub(other.0) {
+            (difference, false) => Work(difference),
+            (_, true) => Work::default(),
+        }
+    }
+}
+
+impl Sum for Work {
+    fn sum<I: Iterator<Item = Work>>(iter: I) -> Self {
+        iter.fold(Work::default(), |total, work| total + work)
+    }
+}
+
+impl fmt::Display for Work {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Work {
+    type Err = DecodeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        U256::from_hex(s).map(Work)
+    }
+}
+
+macro_rules! impl_hex_serde {
+    ($type:ty) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let hex = String::deserialize(deserializer)?;
+                hex.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+impl_hex_serde!(Bits);
+impl_hex_serde!(Target);
+impl_hex_serde!(Work);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The bits of the genesis block, and of every block until the first difficulty change.
+    const GENESIS_BITS: Bits = Bits(0x1d00ffff);
+    const GENESIS_TARGET: &str = "00000000ffff0000000000000000000000000000000000000000000000000000";
+    const GENESIS_WORK: &str = "0000000000000000000000000000000000000000000000000000000100010001";
+
+    #[test]
+    fn bits_to_target_and_back() {
+        let target = GENESIS_BITS.target();
+        assert_eq!(target, GENESIS_TARGET.parse().unwrap());
+        assert_eq!(target.to_bits(), GENESIS_BITS);
+        assert_eq!(Target::from_bits(GENESIS_BITS), target);
+        // From block 100,000 on mainnet.
+        let bits: Bits = "1b04864c".parse().unwrap();
+        assert_eq!(
+            bits.target().to_string(),
+            "000000000004864c000000000000000000000000000000000000000000000000"
+        );
+        assert_eq!(Bits::from(bits.target()), bits);
+        assert_eq!(bits.to_string(), "1b04864c");
+    }
+
+    #[test]
+    fn difficulty() {
+        assert_eq!(GENESIS_BITS.difficulty(), 1.0);
+        assert_eq!(GENESIS_BITS.target().difficulty(), 1.0);
+        let difficulty = Bits(0x1b04864c).difficulty();
+        assert!(
+            (difficulty - 14484.162361225399).abs() < 1e-6,
+            "{}",
+            difficulty
+        );
+    }
+
+    #[test]
+    fn work_from_bits_and_target() {
+        let work = Work::from_bits(GENESIS_BITS);
+        assert_eq!(work, Work(U256::from_u64(0x1_0001_0001)));
+        assert_eq!(GENESIS_BITS.target().work(), work);
+        assert_eq!(work.to_f64(), 0x1_0001_0001u64 as f64);
+        assert!((work.log2() - 32.0).abs() < 1e-4);
+        // Genesis and block 1.
+        assert_eq!(work + work, Work(U256::from_u64(0x2_0002_0002)));
+        assert_eq!(
+            [work, work].into_iter().sum::<Work>(),
+            Work(U256::from_u64(0x2_0002_0002))
+        );
+        let mut total = Work::default();
+        total += work;
+        assert_eq!(total, work);
+    }
+
+    #[test]
+    fn subtraction_saturates() {
+        let work = Work::from_bits(GENESIS_BITS);
+        assert_eq!((work + work) - work, work);
+        assert_eq!(work - (work + work), Work::default());
+        assert_eq!(work - work, Work::default());
+    }
+
+    #[test]
+    fn unusable_bits_have_no_work() {
+        // A set sign bit.
+        assert_eq!(Bits(0x04923456).work(), Work::default());
+        // Too big for 256 bits.
+        assert_eq!(Bits(0xff123456).work(), Work::default());
+        assert_eq!(Bits(0).work(), Work::default());
+        assert_eq!(Target::default().work(), Work::default());
+        // A set sign bit on a zero mantissa is just zero.
+        assert_eq!(Bits(0x01800000).target(), Target::default());
+    }
+
+    #[test]
+    fn serde_as_hex() {
+        let work = Work::from_bits(GENESIS_BITS);
+        let json = serde_json::to_string(&work).unwrap();
+        assert_eq!(json, format!("\"{}\"", GENESIS_WORK));
+        assert_eq!(serde_json::from_str::<Work>(&json).unwrap(), work);
+
+        let json = serde_json::to_string(&GENESIS_BITS).unwrap();
+        assert_eq!(json, "\"1d00ffff\"");
+        assert_eq!(serde_json::from_str::<Bits>(&json).unwrap(), GENESIS_BITS);
+
+        let target = GENESIS_BITS.target();
+        let json = serde_json::to_string(&target).unwrap();
+        assert_eq!(json, format!("\"{}\"", GENESIS_TARGET));
+        assert_eq!(serde_json::from_str::<Target>(&json).unwrap(), target);
+
+        assert!(serde_json::from_str::<Bits>("\"1d00fffg\"").is_err());
+        assert!(serde_json::from_str::<Work>("\"xyz\"").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+// The version of bitcoin core a client is connected to. RPCs and their fields change between
+// releases, so commands check the version before sending a request the node won't understand,
+// and fail with an "unsupported on vX" error instead of an unhelpful parse error.
+//
+// The version is detected once per client with getnetworkinfo (see Client::node_version).
+use std::fmt;
+
+use jsonrpc::error::RpcError;
+use serde::Deserialize;
+
+use crate::client::Client;
+use crate::command::request::request;
+
+// The code bitcoin core returns for an unknown RPC (RPC_METHOD_NOT_FOUND). Unsupported errors
+// use it as well, so callers can treat both the same way.
+pub const RPC_METHOD_NOT_FOUND: i32 = -32601;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl NodeVersion {
+    // Versions are numbered 0.major.minor up to 0.21, and major.minor from 22 on, but
+    // getnetworkinfo reports both as major * 10000 + minor * 100 + patch.
+    pub const V0_17: NodeVersion = NodeVersion::new(17, 0, 0);
+    pub const V0_18: NodeVersion = NodeVersion::new(18, 0, 0);
+    pub const V0_19: NodeVersion = NodeVersion::new(19, 0, 0);
+    pub const V0_20: NodeVersion = NodeVersion::new(20, 0, 0);
+    pub const V0_21: NodeVersion = NodeVersion::new(21, 0, 0);
+    pub const V22: NodeVersion = NodeVersion::new(22, 0, 0);
+    pub const V23: NodeVersion = NodeVersion::new(23, 0, 0);
+    pub const V25: NodeVersion = NodeVersion::new(25, 0, 0);
+    pub const V26: NodeVersion = NodeVersion::new(26, 0, 0);
+    pub const V27: NodeVersion = NodeVersion::new(27, 0, 0);
+    pub const V28: NodeVersion = NodeVersion::new(28, 0, 0);
+
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        NodeVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+    // From the "version" field of getnetworkinfo, e.g. 210100 for 0.21.1 or 270000 for 27.0.
+    pub fn from_number(number: u64) -> Self {
+        NodeVersion::new(number / 10000, number / 100 % 100, number % 100)
+    }
+    pub fn number(&self) -> u64 {
+        self.major * 10000 + self.minor * 100 + self.patch
+    }
+}
+
+impl fmt::Display for NodeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.major < 22 {
+            write!(f, "v0.{}.{}", self.major, self.minor)?;
+        } else {
+            write!(f, "v{}.{}", self.major, self.minor)?;
+        }
+        if self.patch != 0 {
+            write!(f, ".{}", self.patch)?;
+        }
+        Ok(())
+    }
+}
+
+// Only the version is read, so detection doesn't depend on the rest of getnetworkinfo's fields.
+#[derive(Deserialize)]
+struct NetworkInfoVersion {
+    version: u64,
+}
+
+pub(crate) fn detect_node_version(client: &Client) -> Result<NodeVersion, jsonrpc::Error> {
+    let r = request(client, "getnetworkinfo", vec![])?;
+    let response: NetworkInfoVersion = r.result()?;
+    Ok(NodeVersion::from_number(response.version))
+}
+
+pub fn unsupported_error(command: &str, version: NodeVersion) -> jsonrpc::Error {
+    jsonrpc::Error::Rpc(RpcError {
+        code: RPC_METHOD_NOT_FOUND,
+        message: format!("{} is unsupported on {}", command, version),
+        data: None,
+    })
+}
+
+// Fails with an unsupported error if the node is older than `minimum`.
+pub fn require_version(
+    client: &Client,
+    command: &str,
+    minimum: NodeVersion,
+) -> Result<NodeVersion, jsonrpc::Error> {
+    let version = client.node_version()?;
+    if version < minimum {
+        return Err(unsupported_error(command, version));
+    }
+    Ok(version)
+}
@@ -0,0 +1,128 @@
+// Integration tests against a real regtest node. They only run when BITCOIND_EXE points to a
+// bitcoind binary, and pass trivially otherwise.
+use bitcoind_request::command::generate_block::GenerateBlockCommand;
+use bitcoind_request::command::generate_to_address::GenerateToAddressCommand;
+use bitcoind_request::command::generate_to_descriptor::OP_TRUE_DESCRIPTOR;
+use bitcoind_request::command::get_best_block_hash::GetBestBlockHashCommand;
+use bitcoind_request::command::get_block_count::GetBlockCountCommand;
+use bitcoind_request::command::get_deployment_info::is_deployment_active_at;
+use bitcoind_request::command::get_index_info::{GetIndexInfoCommand, IndexName};
+use bitcoind_request::command::invalidate_block::InvalidateBlockCommand;
+use bitcoind_request::command::reconsider_block::ReconsiderBlockCommand;
+use bitcoind_request::command::CallableCommand;
+use bitcoind_request::consensus::address::segwit_address_encode;
+use bitcoind_request::consensus::{Network, SupplyAuditor};
+use bitcoind_request::harness::BitcoindHarness;
+use bitcoind_request::version::NodeVersion;
+use std::thread;
+use std::time::Duration;
+
+fn start_node() -> Option<BitcoindHarness> {
+    BitcoindHarness::from_env().map(|node| node.expect("failed to start bitcoind"))
+}
+
+#[test]
+fn generate_helpers_mine_blocks() {
+    let node = match start_node() {
+        Some(node) => node,
+        None => return,
+    };
+    let client = node.client();
+
+    let hashes = node.generate(101).unwrap();
+    assert_eq!(hashes.len(), 101);
+
+    let address = segwit_address_encode("bcrt", 0, &[0x11; 20]);
+    let hashes = GenerateToAddressCommand::new(2, address)
+        .call(client)
+        .unwrap();
+    assert_eq!(hashes.0.len(), 2);
+
+    let block = GenerateBlockCommand::new(OP_TRUE_DESCRIPTOR.to_string(), vec![])
+        .call(client)
+        .unwrap();
+    assert!(block.hex.is_none());
+
+    let block_count = GetBlockCountCommand::new().call(client).unwrap();
+    assert_eq!(block_count.0, 104);
+}
+
+#[test]
+fn generateblock_without_submitting() {
+    let node = match start_node() {
+        Some(node) => node,
+        None => return,
+    };
+    let block = GenerateBlockCommand::new(OP_TRUE_DESCRIPTOR.to_string(), vec![])
+        .set_submit(false)
+        .call(node.client())
+        .unwrap();
+    assert!(block.hex.is_some());
+    let block_count = GetBlockCountCommand::new().call(node.client()).unwrap();
+    assert_eq!(block_count.0, 0);
+}
+
+#[test]
+fn invalidateblock_and_reconsiderblock_reorg() {
+    let node = match start_node() {
+        Some(node) => node,
+        None => return,
+    };
+    let client = node.client();
+    let hashes = node.generate(3).unwrap();
+
+    let command = InvalidateBlockCommand::new(hashes[1].clone());
+    assert!(!command.is_idempotent());
+    command.call(client).unwrap();
+    let block_count = GetBlockCountCommand::new().call(client).unwrap();
+    assert_eq!(block_count.0, 1);
+
+    ReconsiderBlockCommand::new(hashes[1].clone())
+        .call(client)
+        .unwrap();
+    let best_block_hash = GetBestBlockHashCommand::default().call(client).unwrap();
+    assert_eq!(best_block_hash.0, hashes[2]);
+}
+
+#[test]
+fn node_version_and_deployments() {
+    let node = match start_node() {
+        Some(node) => node,
+        None => return,
+    };
+    let client = node.client();
+    let version = client.node_version().unwrap();
+    assert!(version >= NodeVersion::V0_19);
+    assert_eq!(client.node_version().unwrap(), version);
+
+    // Segwit is buried at height 0 on regtest.
+    let active = is_deployment_active_at(client, "segwit", 0).unwrap();
+    assert_eq!(active, Some(true));
+}
+
+#[test]
+fn supply_audit_across_a_halving() {
+    let node = match BitcoindHarness::from_env_with_args(&["-coinstatsindex"]) {
+        Some(node) => node.expect("failed to start bitcoind"),
+        None => return,
+    };
+    let client = node.client();
+    // Regtest halves the subsidy every 150 blocks.
+    node.generate(160).unwrap();
+    while !GetIndexInfoCommand::new()
+        .call(client)
+        .unwrap()
+        .is_synced(&IndexName::CoinStatsIndex)
+    {
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    let audits: Vec<_> = SupplyAuditor::new(client, Network::Regtest)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(audits.len(), 161);
+    for audit in &audits {
+        assert!(audit.is_ok(), "{}", audit);
+    }
+    assert_eq!(audits[150].reported_subsidy, 2_500_000_000);
+}